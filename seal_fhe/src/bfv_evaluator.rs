@@ -98,6 +98,10 @@ impl Evaluator for BFVEvaluator {
         self.0.mod_switch_to_next_inplace_plaintext(a)
     }
 
+    fn rescale_to_next(&self, a: &Ciphertext) -> Result<Ciphertext> {
+        self.0.rescale_to_next(a)
+    }
+
     fn exponentiate(
         &self,
         a: &Ciphertext,
@@ -85,6 +85,20 @@ impl Context {
     pub fn get_handle(&self) -> *mut c_void {
         self.handle
     }
+
+    /**
+     * Returns the ParmsId of the first parameters in the modulus
+     * switching chain, i.e. those fresh encryptions use.
+     */
+    pub(crate) fn get_first_parms_id(&self) -> Result<Vec<u64>> {
+        let mut parms_id = vec![0u64; 4];
+
+        convert_seal_error(unsafe {
+            bindgen::SEALContext_FirstParmsId(self.handle, parms_id.as_mut_ptr())
+        })?;
+
+        Ok(parms_id)
+    }
 }
 
 impl Drop for Context {
@@ -282,6 +282,125 @@ impl Default for BFVScalarEncoder {
     }
 }
 
+/**
+ * Encodes vectors of complex numbers into CKKS plaintexts. If the
+ * polynomial modulus degree is N, a plaintext holds N/2 complex values
+ * and homomorphic operations act on them slot-wise.
+ *
+ * # Remarks
+ * CKKS is approximate: each value is multiplied by a scale and rounded
+ * to an integer when encoded, so decoding recovers it with roughly
+ * `log2(scale)` bits of precision, less the noise operations add.
+ */
+pub struct CKKSEncoder {
+    handle: *mut c_void,
+    parms_id: Vec<u64>,
+}
+
+unsafe impl Sync for CKKSEncoder {}
+unsafe impl Send for CKKSEncoder {}
+
+impl CKKSEncoder {
+    /**
+     * Creates a CKKSEncoder. The given context must use the CKKS scheme.
+     *
+     * * `ctx` - The Context
+     */
+    pub fn new(ctx: &Context) -> Result<Self> {
+        let mut handle: *mut c_void = null_mut();
+
+        convert_seal_error(unsafe { bindgen::CKKSEncoder_Create(ctx.get_handle(), &mut handle) })?;
+
+        Ok(Self {
+            handle,
+            parms_id: ctx.get_first_parms_id()?,
+        })
+    }
+
+    /**
+     * Encodes the given complex values, given as `(real, imaginary)`
+     * pairs, at the given scale. Slots past the end of `data` are zero.
+     *
+     * * `data` - At most [`get_slot_count`](Self::get_slot_count) values
+     * * `scale` - The scale to encode with
+     */
+    pub fn encode_complex(&self, data: &[(f64, f64)], scale: f64) -> Result<Plaintext> {
+        let plaintext = Plaintext::new()?;
+
+        // SEAL expects an array of std::complex<double>, which stores the
+        // real part followed by the imaginary part.
+        let mut values = data
+            .iter()
+            .flat_map(|(re, im)| [*re, *im])
+            .collect::<Vec<f64>>();
+
+        // The parms id isn't mutated either.
+        convert_seal_error(unsafe {
+            bindgen::CKKSEncoder_Encode2(
+                self.handle,
+                data.len() as u64,
+                values.as_mut_ptr(),
+                self.parms_id.as_ptr() as *mut u64,
+                scale,
+                plaintext.get_handle(),
+                null_mut(),
+            )
+        })?;
+
+        Ok(plaintext)
+    }
+
+    /**
+     * Inverse of [`encode_complex`](Self::encode_complex). Returns every
+     * slot's value as a `(real, imaginary)` pair.
+     *
+     * * `plain` - The plaintext to decode
+     */
+    pub fn decode_complex(&self, plaintext: &Plaintext) -> Result<Vec<(f64, f64)>> {
+        let mut values = vec![0f64; 2 * self.get_slot_count()];
+        let mut size: u64 = 0;
+
+        convert_seal_error(unsafe {
+            bindgen::CKKSEncoder_Decode2(
+                self.handle,
+                plaintext.get_handle(),
+                &mut size,
+                values.as_mut_ptr(),
+                null_mut(),
+            )
+        })?;
+
+        if values.len() < 2 * size as usize {
+            panic!("Allocation overflow CKKSEncoder::decode_complex");
+        }
+
+        Ok(values
+            .chunks_exact(2)
+            .take(size as usize)
+            .map(|c| (c[0], c[1]))
+            .collect())
+    }
+
+    /**
+     * Returns the number of complex values a plaintext holds.
+     */
+    pub fn get_slot_count(&self) -> usize {
+        let mut count: u64 = 0;
+
+        convert_seal_error(unsafe { bindgen::CKKSEncoder_SlotCount(self.handle, &mut count) })
+            .expect("Internal error in CKKSEncoder::get_slot_count().");
+
+        count as usize
+    }
+}
+
+impl Drop for CKKSEncoder {
+    fn drop(&mut self) {
+        convert_seal_error(unsafe { bindgen::CKKSEncoder_Destroy(self.handle) })
+            .expect("Internal error in CKKSEncoder::drop.");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -393,4 +512,33 @@ mod tests {
 
         assert_eq!(encoder.decode_signed(&p).unwrap(), 42);
     }
+
+    #[test]
+    fn ckks_encoder_can_encode_decode_complex() {
+        let params = CkksEncryptionParametersBuilder::new()
+            .set_poly_modulus_degree(8192)
+            .set_coefficient_modulus(CoefficientModulus::create(8192, &[60, 40, 40, 60]).unwrap())
+            .build()
+            .unwrap();
+
+        let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+
+        let encoder = CKKSEncoder::new(&ctx).unwrap();
+
+        assert_eq!(encoder.get_slot_count(), 4096);
+
+        let data = [(3., 4.), (-1.5, 0.25)];
+
+        let plaintext = encoder.encode_complex(&data, 2f64.powi(40)).unwrap();
+        let data_2 = encoder.decode_complex(&plaintext).unwrap();
+
+        assert_eq!(data_2.len(), 4096);
+
+        for ((re, im), (re_2, im_2)) in data.iter().zip(data_2.iter()) {
+            assert!((re - re_2).abs() < 1e-6);
+            assert!((im - im_2).abs() < 1e-6);
+        }
+
+        assert!(data_2[2].0.abs() < 1e-6);
+    }
 }
@@ -23,6 +23,9 @@ pub enum SchemeType {
 
     /// Cheon-Kim-Kim-Song scheme
     Ckks = 0x2,
+
+    /// Brakerski-Gentry-Vaikuntanathan scheme
+    Bgv = 0x3,
 }
 
 impl SchemeType {
@@ -31,6 +34,7 @@ impl SchemeType {
             0x0 => SchemeType::None,
             0x1 => SchemeType::Bfv,
             0x2 => SchemeType::Ckks,
+            0x3 => SchemeType::Bgv,
             _ => panic!("Illegal scheme type"),
         }
     }
@@ -268,7 +272,19 @@ impl BfvEncryptionParametersBuilder {
      * Validate the parameter choices and return the encryption parameters.
      */
     pub fn build(self) -> Result<EncryptionParameters, Error> {
-        let params = EncryptionParameters::new(SchemeType::Bfv)?;
+        self.build_scheme(SchemeType::Bfv)
+    }
+
+    /**
+     * Like [`build`](Self::build), but returns parameters for the BGV
+     * scheme, which takes the same parameters as BFV.
+     */
+    pub fn build_bgv(self) -> Result<EncryptionParameters, Error> {
+        self.build_scheme(SchemeType::Bgv)
+    }
+
+    fn build_scheme(self, scheme: SchemeType) -> Result<EncryptionParameters, Error> {
+        let params = EncryptionParameters::new(scheme)?;
 
         convert_seal_error(unsafe {
             bindgen::EncParams_SetPolyModulusDegree(
@@ -316,6 +332,85 @@ impl Default for BfvEncryptionParametersBuilder {
     }
 }
 
+/**
+ * Represents a builder that sets up and creates CKKS encryption scheme
+ * parameters. Unlike BFV, CKKS has no plaintext modulus; the precision of
+ * encoded values instead depends on the scale they're encoded with.
+ */
+pub struct CkksEncryptionParametersBuilder {
+    poly_modulus_degree: Option<u64>,
+    coefficient_modulus: CoefficientModulusType,
+}
+
+impl CkksEncryptionParametersBuilder {
+    /**
+     * Creates a new builder.
+     */
+    pub fn new() -> Self {
+        Self {
+            poly_modulus_degree: None,
+            coefficient_modulus: CoefficientModulusType::NotSet,
+        }
+    }
+
+    /**
+     * Set the degree of the polynomial used in the CKKS scheme. A
+     * plaintext holds half this many complex values.
+     */
+    pub fn set_poly_modulus_degree(mut self, degree: u64) -> Self {
+        self.poly_modulus_degree = Some(degree);
+        self
+    }
+
+    /**
+     * Sets the coefficient modulus parameter. Each rescaling consumes one
+     * prime from the end of the chain (before the special prime), so the
+     * chain should contain one prime near the encoding scale per
+     * multiplication.
+     */
+    pub fn set_coefficient_modulus(mut self, modulus: Vec<Modulus>) -> Self {
+        self.coefficient_modulus = CoefficientModulusType::Modulus(modulus);
+        self
+    }
+
+    /**
+     * Validate the parameter choices and return the encryption parameters.
+     */
+    pub fn build(self) -> Result<EncryptionParameters, Error> {
+        let params = EncryptionParameters::new(SchemeType::Ckks)?;
+
+        convert_seal_error(unsafe {
+            bindgen::EncParams_SetPolyModulusDegree(
+                params.handle,
+                self.poly_modulus_degree.ok_or(Error::DegreeNotSet)?,
+            )
+        })?;
+
+        match self.coefficient_modulus {
+            CoefficientModulusType::NotSet => return Err(Error::CoefficientModulusNotSet),
+            CoefficientModulusType::Modulus(m) => {
+                convert_seal_error(unsafe {
+                    let modulus_ref = m
+                        .iter()
+                        .map(|m| m.get_handle())
+                        .collect::<Vec<*mut c_void>>();
+                    let modulus_ptr = modulus_ref.as_ptr() as *mut *mut c_void;
+
+                    bindgen::EncParams_SetCoeffModulus(params.handle, m.len() as u64, modulus_ptr)
+                })?;
+            }
+        };
+
+        Ok(params)
+    }
+}
+
+impl Default for CkksEncryptionParametersBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Drop for EncryptionParameters {
     fn drop(&mut self) {
         unsafe { bindgen::EncParams_Destroy(self.handle) };
@@ -365,4 +460,32 @@ mod tests {
         assert_eq!(modulus[3].value(), 1125899906629633);
         assert_eq!(modulus[4].value(), 1125899906826241);
     }
+
+    #[test]
+    fn can_build_ckks_params() {
+        let params = CkksEncryptionParametersBuilder::new()
+            .set_poly_modulus_degree(8192)
+            .set_coefficient_modulus(CoefficientModulus::create(8192, &[60, 40, 40, 60]).unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(params.get_poly_modulus_degree(), 8192);
+        assert_eq!(params.get_scheme(), SchemeType::Ckks);
+        assert_eq!(params.get_coefficient_modulus().len(), 4);
+    }
+
+    #[test]
+    fn can_build_bgv_params() {
+        let params = BfvEncryptionParametersBuilder::new()
+            .set_poly_modulus_degree(8192)
+            .set_coefficient_modulus(CoefficientModulus::create(8192, &[60, 40, 40, 60]).unwrap())
+            .set_plain_modulus_u64(1024)
+            .build_bgv()
+            .unwrap();
+
+        assert_eq!(params.get_poly_modulus_degree(), 8192);
+        assert_eq!(params.get_scheme(), SchemeType::Bgv);
+        assert_eq!(params.get_plain_modulus().value(), 1024);
+        assert_eq!(params.get_coefficient_modulus().len(), 4);
+    }
 }
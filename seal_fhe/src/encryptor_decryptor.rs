@@ -89,6 +89,26 @@ impl Encryptor {
         Ok(Encryptor { handle })
     }
 
+    /**
+     * Creates an Encryptor instance initialized with the specified SEALContext
+     * and secret key. Such an Encryptor only supports symmetric-key
+     * encryption.
+     */
+    pub fn with_secret_key(ctx: &Context, secret_key: &SecretKey) -> Result<Encryptor> {
+        let mut handle: *mut c_void = null_mut();
+
+        convert_seal_error(unsafe {
+            bindgen::Encryptor_Create(
+                ctx.get_handle(),
+                null_mut(),
+                secret_key.get_handle(),
+                &mut handle,
+            )
+        })?;
+
+        Ok(Encryptor { handle })
+    }
+
     /**
      *
      * Encrypts a plaintext with the public key and returns the ciphertext as
@@ -120,6 +140,31 @@ impl Encryptor {
         Ok(ciphertext)
     }
 
+    /**
+     * Encrypts a plaintext with the secret key and returns the ciphertext.
+     *
+     * The encryption parameters for the resulting ciphertext correspond to:
+     * 1) in BFV, the highest (data) level in the modulus switching chain,
+     * 2) in CKKS, the encryption parameters of the plaintext.
+     *
+     * * `plainext` - The plaintext to encrypt.
+     */
+    pub fn encrypt_symmetric(&self, plaintext: &Plaintext) -> Result<Ciphertext> {
+        let ciphertext = Ciphertext::new()?;
+
+        convert_seal_error(unsafe {
+            bindgen::Encryptor_EncryptSymmetric(
+                self.handle,
+                plaintext.get_handle(),
+                false,
+                ciphertext.get_handle(),
+                null_mut(),
+            )
+        })?;
+
+        Ok(ciphertext)
+    }
+
     /**
      *
      * Encrypts a plaintext with the public key and returns the ciphertext as a
@@ -486,6 +531,37 @@ mod tests {
         assert_eq!(data, data_2);
     }
 
+    #[test]
+    fn can_encrypt_symmetric_and_decrypt() {
+        let params = BfvEncryptionParametersBuilder::new()
+            .set_poly_modulus_degree(8192)
+            .set_coefficient_modulus(
+                CoefficientModulus::create(8192, &[50, 30, 30, 50, 50]).unwrap(),
+            )
+            .set_plain_modulus(PlainModulus::batching(8192, 20).unwrap())
+            .build()
+            .unwrap();
+
+        let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+        let gen = KeyGenerator::new(&ctx).unwrap();
+
+        let encoder = BFVEncoder::new(&ctx).unwrap();
+
+        let data = (0..encoder.get_slot_count() as u64).collect::<Vec<_>>();
+
+        let plaintext = encoder.encode_unsigned(&data).unwrap();
+
+        let secret_key = gen.secret_key();
+
+        let encryptor = Encryptor::with_secret_key(&ctx, &secret_key).unwrap();
+        let decryptor = Decryptor::new(&ctx, &secret_key).unwrap();
+
+        let ciphertext = encryptor.encrypt_symmetric(&plaintext).unwrap();
+        let decrypted = decryptor.decrypt(&ciphertext).unwrap();
+
+        assert_eq!(data, encoder.decode_unsigned(&decrypted).unwrap());
+    }
+
     #[test]
     fn can_encrypt_and_decrypt_signed() {
         let params = BfvEncryptionParametersBuilder::new()
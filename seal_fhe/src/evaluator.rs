@@ -128,6 +128,16 @@ pub trait Evaluator {
      */
     fn mod_switch_to_next_inplace_plaintext(&self, a: &Plaintext) -> Result<()>;
 
+    /**
+     * Given a CKKS ciphertext encrypted modulo q_1...q_k, this function switches the modulus
+     * down to q_1...q_{k-1} and scales the message down accordingly.
+     *
+     * # Remarks
+     * Multiplying CKKS ciphertexts multiplies their scales, so rescale products to keep the
+     * scale from overflowing the coefficient modulus. Fails for BFV ciphertexts.
+     */
+    fn rescale_to_next(&self, a: &Ciphertext) -> Result<Ciphertext>;
+
     /**
      * This functions raises encrypted to a power and stores the result in the destination parameter. Dynamic
      * memory allocations in the process are allocated from the memory pool pointed to by the given
@@ -275,6 +275,21 @@ impl EvaluatorBase {
         Ok(())
     }
 
+    pub(crate) fn rescale_to_next(&self, a: &Ciphertext) -> Result<Ciphertext> {
+        let c = Ciphertext::new()?;
+
+        convert_seal_error(unsafe {
+            bindgen::Evaluator_RescaleToNext(
+                self.get_handle(),
+                a.get_handle(),
+                c.get_handle(),
+                null_mut(),
+            )
+        })?;
+
+        Ok(c)
+    }
+
     pub(crate) fn exponentiate(
         &self,
         a: &Ciphertext,
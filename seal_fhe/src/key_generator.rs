@@ -171,6 +171,34 @@ impl KeyGenerator {
         self.create_galois_keys_internal(false)
     }
 
+    /**
+     * Generates Galois keys supporting exactly the given row rotation
+     * steps. Positive steps rotate left, negative steps rotate right,
+     * and a step of 0 swaps rows (i.e. rotates columns).
+     *
+     * # Remarks
+     * Generating only the keys a computation needs is faster and
+     * produces smaller keys than
+     * [`create_galois_keys`](Self::create_galois_keys). Rotating by a
+     * step not in `steps` fails.
+     */
+    pub fn create_galois_keys_from_steps(&self, steps: &[i32]) -> Result<GaloisKeys> {
+        let mut handle = null_mut();
+        let mut steps = steps.to_vec();
+
+        convert_seal_error(unsafe {
+            bindgen::KeyGenerator_CreateGaloisKeysFromSteps(
+                self.handle,
+                steps.len() as u64,
+                steps.as_mut_ptr(),
+                false,
+                &mut handle,
+            )
+        })?;
+
+        Ok(GaloisKeys { handle })
+    }
+
     fn create_galois_keys_internal(&self, save_seed: bool) -> Result<GaloisKeys> {
         let mut handle = null_mut();
 
@@ -810,6 +838,23 @@ mod tests {
         gen.create_galois_keys().unwrap();
     }
 
+    #[test]
+    fn can_create_galois_keys_from_steps() {
+        let params = BfvEncryptionParametersBuilder::new()
+            .set_poly_modulus_degree(8192)
+            .set_coefficient_modulus(
+                CoefficientModulus::bfv_default(8192, SecurityLevel::TC128).unwrap(),
+            )
+            .set_plain_modulus(PlainModulus::batching(8192, 32).unwrap())
+            .build()
+            .unwrap();
+
+        let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+        let gen = KeyGenerator::new(&ctx).unwrap();
+
+        gen.create_galois_keys_from_steps(&[-1, 0, 3]).unwrap();
+    }
+
     #[test]
     fn can_init_from_existing_secret_key() {
         let params = BfvEncryptionParametersBuilder::new()
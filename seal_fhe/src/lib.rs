@@ -59,7 +59,7 @@ mod plaintext_ciphertext;
 pub use bfv_evaluator::BFVEvaluator;
 pub use context::Context;
 pub use data_structures::PolynomialArray;
-pub use encoder::{BFVEncoder, BFVScalarEncoder};
+pub use encoder::{BFVEncoder, BFVScalarEncoder, CKKSEncoder};
 pub use encryption_parameters::*;
 pub use encryptor_decryptor::{Decryptor, Encryptor};
 pub use error::{Error, Result};
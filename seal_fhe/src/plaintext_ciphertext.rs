@@ -376,6 +376,21 @@ impl Ciphertext {
         size
     }
 
+    /**
+     * Returns an upper bound on the number of bytes
+     * [`as_bytes`](ToBytes::as_bytes) produces, without serializing.
+     */
+    pub fn save_size(&self) -> u64 {
+        let mut num_bytes: i64 = 0;
+
+        convert_seal_error(unsafe {
+            bindgen::Ciphertext_SaveSize(self.handle, CompressionType::ZStd as u8, &mut num_bytes)
+        })
+        .unwrap();
+
+        num_bytes as u64
+    }
+
     /**
      * Returns the value at a specific point in the coefficient array. This is
      * not publically exported as it leaks the encoding of the array.
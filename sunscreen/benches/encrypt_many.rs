@@ -0,0 +1,65 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use seal_fhe::{CoefficientModulus, SecurityLevel};
+use sunscreen::{types::bfv::Signed, FheRuntime, Params, SchemeType};
+
+/// `Runtime::encrypt_many` and `decrypt_many` create their encryptor
+/// or decryptor once per batch and process values in parallel. This
+/// compares them against encrypting and decrypting one value at a time.
+fn batches(c: &mut Criterion) {
+    let lattice_dimension = 4096;
+
+    let params = Params {
+        lattice_dimension,
+        plain_modulus: 1024,
+        coeff_modulus: CoefficientModulus::bfv_default(lattice_dimension, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|m| m.value())
+            .collect(),
+        scheme_type: SchemeType::Bfv,
+        security_level: SecurityLevel::TC128,
+    };
+
+    let runtime = FheRuntime::new(&params).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let mut group = c.benchmark_group("encrypt_decrypt_many");
+    group.sample_size(10);
+
+    for len in [100, 1000] {
+        let values = (0..len).map(Signed::from).collect::<Vec<_>>();
+        let ciphertexts = runtime.encrypt_many(&values, &public_key).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("encrypt_loop", len), &values, |b, v| {
+            b.iter(|| {
+                v.iter()
+                    .map(|x| runtime.encrypt(*x, &public_key).unwrap())
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("encrypt_many", len), &values, |b, v| {
+            b.iter(|| runtime.encrypt_many(v, &public_key).unwrap())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("decrypt_loop", len),
+            &ciphertexts,
+            |b, c| {
+                b.iter(|| {
+                    c.iter()
+                        .map(|x| runtime.decrypt::<Signed>(x, &private_key).unwrap())
+                        .collect::<Vec<_>>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("decrypt_many", len),
+            &ciphertexts,
+            |b, c| b.iter(|| runtime.decrypt_many::<Signed>(c, &private_key).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, batches);
+criterion_main!(benches);
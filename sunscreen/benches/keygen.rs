@@ -0,0 +1,45 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use seal_fhe::{CoefficientModulus, SecurityLevel};
+use sunscreen::{FheRuntime, Params, SchemeType};
+
+/// `Runtime::generate_keys` generates the public, relinearization, and
+/// Galois keys concurrently on rayon's thread pool. This compares key
+/// generation on a single thread against the default pool for a large
+/// parameter set.
+fn large_params(c: &mut Criterion) {
+    let lattice_dimension = 16384;
+
+    let params = Params {
+        lattice_dimension,
+        plain_modulus: 1024,
+        coeff_modulus: CoefficientModulus::bfv_default(lattice_dimension, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|m| m.value())
+            .collect(),
+        scheme_type: SchemeType::Bfv,
+        security_level: SecurityLevel::TC128,
+    };
+
+    let runtime = FheRuntime::new(&params).unwrap();
+
+    let generate = || runtime.generate_keys().unwrap();
+
+    let single_thread = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("keygen_16384");
+    group.sample_size(10);
+
+    group.bench_function("single_thread", |bench| {
+        bench.iter(|| single_thread.install(generate))
+    });
+    group.bench_function("parallel", |bench| bench.iter(generate));
+
+    group.finish();
+}
+
+criterion_group!(benches, large_params);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, FheRuntime,
+};
+
+/// `Runtime::run` evaluates independent nodes concurrently on rayon's
+/// thread pool. This compares running a program with two independent
+/// multiplication chains on a single thread against the default pool.
+fn independent_chains(c: &mut Criterion) {
+    #[fhe_program(scheme = "bfv")]
+    fn chains(a: Cipher<Signed>, b: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>) {
+        (a * a * a * a, b * b * b * b)
+    }
+
+    let app = Compiler::new().fhe_program(chains).compile().unwrap();
+    let program = app.get_fhe_program(chains).unwrap();
+
+    let runtime = FheRuntime::new(app.params()).unwrap();
+    let (public_key, _) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(2), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+
+    let run = || {
+        runtime
+            .run(program, vec![a.clone(), b.clone()], &public_key)
+            .unwrap()
+    };
+
+    let single_thread = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("independent_chains");
+
+    group.bench_function("single_thread", |bench| {
+        bench.iter(|| single_thread.install(run))
+    });
+    group.bench_function("parallel", |bench| bench.iter(run));
+
+    group.finish();
+}
+
+criterion_group!(benches, independent_chains);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, FheRuntime,
+};
+
+/// `Runtime::run` validates the program and creates an evaluator on
+/// every call. This compares that against reusing a plan from
+/// `Runtime::prepare` for a cheap program, where the setup dominates.
+fn prepared_plan(c: &mut Criterion) {
+    #[fhe_program(scheme = "bfv")]
+    fn add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a + b
+    }
+
+    let app = Compiler::new().fhe_program(add).compile().unwrap();
+    let program = app.get_fhe_program(add).unwrap();
+
+    let runtime = FheRuntime::new(app.params()).unwrap();
+    let (public_key, _) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(2), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+
+    let plan = runtime.prepare(program).unwrap();
+
+    let mut group = c.benchmark_group("prepared_plan");
+
+    group.bench_function("run", |bench| {
+        bench.iter(|| {
+            runtime
+                .run(program, vec![a.clone(), b.clone()], &public_key)
+                .unwrap()
+        })
+    });
+    group.bench_function("run_plan", |bench| {
+        bench.iter(|| {
+            runtime
+                .run_plan(&plan, vec![a.clone(), b.clone()], &public_key)
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+/// `Runtime::run_many` runs each input set in turn, whereas
+/// `Runtime::run_batched` runs them concurrently. This compares the two
+/// on many input sets of a program too small to parallelize internally.
+fn batched_inputs(c: &mut Criterion) {
+    #[fhe_program(scheme = "bfv")]
+    fn mul(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * b
+    }
+
+    let app = Compiler::new().fhe_program(mul).compile().unwrap();
+    let program = app.get_fhe_program(mul).unwrap();
+
+    let runtime = FheRuntime::new(app.params()).unwrap();
+    let (public_key, _) = runtime.generate_keys().unwrap();
+
+    let inputs = (0..32)
+        .map(|i| {
+            vec![
+                runtime.encrypt(Signed::from(i), &public_key).unwrap(),
+                runtime.encrypt(Signed::from(3), &public_key).unwrap(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("batched_inputs");
+
+    group.bench_function("run_many", |bench| {
+        bench.iter(|| {
+            runtime
+                .run_many(program, inputs.clone(), &public_key)
+                .unwrap()
+        })
+    });
+    group.bench_function("run_batched", |bench| {
+        bench.iter(|| {
+            runtime
+                .run_batched(program, inputs.clone(), &public_key)
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, prepared_plan, batched_inputs);
+criterion_main!(benches);
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::warn;
+use sha3::{Digest, Sha3_256};
+use sunscreen_runtime::CompiledFheProgram;
+
+#[derive(Clone)]
+/**
+ * Accumulates everything a compiled application depends on into a
+ * hash, which names its entry in the on-disk cache.
+ */
+pub(crate) struct CacheKey {
+    hasher: Sha3_256,
+}
+
+impl CacheKey {
+    pub fn new() -> Self {
+        let mut key = Self {
+            hasher: Sha3_256::new(),
+        };
+
+        // The backend's output may change between releases.
+        key.update(env!("CARGO_PKG_VERSION").as_bytes());
+
+        key
+    }
+
+    /**
+     * Adds `bytes` to the key. Each update is length-prefixed, so
+     * different splits of the same bytes hash differently.
+     */
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.hasher.update((bytes.len() as u64).to_le_bytes());
+        self.hasher.update(bytes);
+    }
+
+    fn file_name(&self) -> String {
+        let hash = self
+            .hasher
+            .clone()
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+
+        format!("{hash}.bin")
+    }
+}
+
+/**
+ * A directory of compiled applications. See
+ * [`with_cache_dir`](crate::GenericCompiler::with_cache_dir).
+ */
+pub(crate) struct CompileCache<'a> {
+    dir: &'a Path,
+}
+
+impl<'a> CompileCache<'a> {
+    pub fn new(dir: &'a Path) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(key.file_name())
+    }
+
+    /**
+     * Returns the programs cached under `key`, if any. Unreadable
+     * entries count as misses.
+     */
+    pub fn load(&self, key: &CacheKey) -> Option<HashMap<String, CompiledFheProgram>> {
+        let bytes = fs::read(self.path(key)).ok()?;
+
+        bincode::deserialize(&bytes).ok()
+    }
+
+    /**
+     * Caches `programs` under `key`.
+     *
+     * # Remarks
+     * Failing to write the cache doesn't fail compilation, so this only
+     * logs a warning.
+     */
+    pub fn store(&self, key: &CacheKey, programs: &HashMap<String, CompiledFheProgram>) {
+        let path = self.path(key);
+
+        let result = bincode::serialize(programs)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| {
+                fs::create_dir_all(self.dir)
+                    .and_then(|_| {
+                        // Write then rename so concurrent compiles never
+                        // read a partial entry.
+                        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+
+                        fs::write(&tmp, bytes)?;
+                        fs::rename(&tmp, &path)
+                    })
+                    .map_err(|e| e.to_string())
+            });
+
+        if let Err(e) = result {
+            warn!("Failed to write compile cache {}: {}", path.display(), e);
+        }
+    }
+}
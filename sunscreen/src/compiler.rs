@@ -1,18 +1,39 @@
-use crate::fhe::{FheCompile, FheFrontendCompilation};
-use crate::params::{determine_params, PlainModulusConstraint};
+use crate::compile_cache::{CacheKey, CompileCache};
+use crate::fhe::{
+    to_fhe_program, EncodeCache, EncodeCacheScope, FheCompile, FheFrontendCompilation,
+};
+use crate::params::{
+    check_security_level, determine_params, params_for_depth, provisional_params,
+    PlainModulusConstraint, DEFAULT_NOISE_MARGIN, DEFAULT_PLAIN_MODULUS,
+};
+use crate::types::bfv::{Signed, Unsigned};
+use crate::types::TypeName;
 use crate::{
     zkp, Application, CallSignature, Error, FheProgramMetadata, Params, RequiredKeys, Result,
-    SchemeType, SecurityLevel, ZkpProgramFn,
+    RuntimeError, SchemeType, SecurityLevel, ZkpProgramFn,
 };
+use log::{debug, warn};
+use petgraph::{algo::toposort, stable_graph::NodeIndex, Direction};
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
-use sunscreen_fhe_program::FheProgramTrait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sunscreen_backend::noise_model::{
+    noise_to_noise_budget, predict_noise, CanonicalEmbeddingNormModel,
+};
+use sunscreen_backend::overflow::{find_overflows, CoefficientBound, OverflowWarning};
+use sunscreen_backend::{CompileOptions, PassPosition, UserPass};
+use sunscreen_compiler_common::transforms::CompilerPass;
+use sunscreen_fhe_program::{FheProgram, FheProgramTrait, Operation};
 use sunscreen_runtime::{marker, CompiledFheProgram, Fhe, FheRuntime, FheZkp, Zkp};
 use sunscreen_zkp_backend::{CompiledZkpProgram, FieldSpec, ZkpBackend};
 
 #[derive(Debug, Clone)]
 enum ParamsMode {
     Search,
+    Depth,
     Manual(Params),
 }
 
@@ -32,6 +53,11 @@ pub trait FheProgramFn {
      */
     fn build(&self, params: &Params) -> Result<FheFrontendCompilation>;
 
+    /**
+     * Get the name of each of the function's arguments, in order.
+     */
+    fn argument_names(&self) -> Vec<String>;
+
     /**
      * Get the scheme type.
      */
@@ -133,12 +159,89 @@ pub trait FheProgramFnExt: FheProgramFn {
 
 impl<T: ?Sized> FheProgramFnExt for T where T: FheProgramFn {}
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/**
+ * The wall-clock time spent in each phase of compiling FHE programs.
+ * Returned by `compile_timed`.
+ *
+ * # Remarks
+ * Graph construction and backend pass times are summed across every FHE
+ * program in the application. Building graphs while searching for
+ * parameters counts toward `param_search`, not `graph_build`.
+ */
+pub struct CompileTimings {
+    /**
+     * Time spent building the frontend graph of each FHE program.
+     */
+    pub graph_build: Duration,
+
+    /**
+     * Time spent searching for scheme parameters. Zero if parameters were
+     * given explicitly.
+     */
+    pub param_search: Duration,
+
+    /**
+     * Time spent in each backend pass, in the order the passes ran.
+     */
+    pub passes: Vec<(String, Duration)>,
+
+    /**
+     * The total time spent compiling FHE programs.
+     */
+    pub total: Duration,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/**
+ * The result of checking FHE programs without compiling them. Returned
+ * by `validate`.
+ *
+ * # Remarks
+ * Validation builds each program's graph and runs the same up-front
+ * checks as compilation, but doesn't search for parameters, run backend
+ * passes, or create any SEAL objects. When parameters aren't given with
+ * [`with_params`](GenericCompiler::with_params), programs are built
+ * under provisional parameters, so `estimated_depth` may differ slightly
+ * from that of the compiled application.
+ */
+pub struct ValidationReport {
+    /**
+     * The largest multiplicative depth of any FHE program, multiplied by
+     * its chain count.
+     */
+    pub estimated_depth: usize,
+
+    /**
+     * The sorted rotation steps the application's Galois keys must
+     * support.
+     */
+    pub required_galois_steps: Vec<i32>,
+
+    /**
+     * Problems that don't prevent compilation but likely make the
+     * application misbehave.
+     */
+    pub warnings: Vec<String>,
+
+    /**
+     * Problems that cause compilation to fail.
+     */
+    pub errors: Vec<String>,
+}
+
 struct FheCompilerData {
     fhe_program_fns: Vec<Box<dyn FheProgramFn>>,
     params_mode: ParamsMode,
     plain_modulus_constraint: PlainModulusConstraint,
     security_level: SecurityLevel,
     noise_margin: u32,
+    graph_invariant_check: bool,
+    relin_optimization: bool,
+    decryptability_check: bool,
+    encode_cache: Option<EncodeCache>,
+    user_passes: Vec<UserPass>,
+    cache_dir: Option<PathBuf>,
 }
 
 impl Default for FheCompilerData {
@@ -146,15 +249,64 @@ impl Default for FheCompilerData {
         Self {
             fhe_program_fns: vec![],
             params_mode: ParamsMode::Search,
-            // This default value is sufficient for doing 3 levels of 64-bit
-            // multiplications
-            plain_modulus_constraint: PlainModulusConstraint::Raw(262_144),
+            plain_modulus_constraint: PlainModulusConstraint::Raw(DEFAULT_PLAIN_MODULUS),
             security_level: SecurityLevel::TC128,
-            noise_margin: 20,
+            noise_margin: DEFAULT_NOISE_MARGIN,
+            // Catch broken passes early in debug builds, including the
+            // crate's own tests, without paying for it in release.
+            graph_invariant_check: cfg!(debug_assertions),
+            relin_optimization: true,
+            decryptability_check: false,
+            encode_cache: None,
+            user_passes: vec![],
+            cache_dir: None,
         }
     }
 }
 
+impl FheCompilerData {
+    /**
+     * Hashes the programs' graphs along with every setting that affects
+     * their compilation.
+     *
+     * # Remarks
+     * `frontends` are the programs' graphs, in order, as validation
+     * built them. Parameters aren't chosen yet, so unless they're given
+     * explicitly these were built under provisional parameters.
+     */
+    fn cache_key(&self, frontends: &[FheFrontendCompilation]) -> Result<CacheKey> {
+        fn to_bytes<T: Serialize>(x: &T) -> Result<Vec<u8>> {
+            Ok(bincode::serialize(x).map_err(RuntimeError::from)?)
+        }
+
+        let mut key = CacheKey::new();
+
+        key.update(
+            format!(
+                "{:?} {:?} {:?} {} {} {}",
+                self.params_mode,
+                self.plain_modulus_constraint,
+                self.security_level,
+                self.noise_margin,
+                self.relin_optimization,
+                self.decryptability_check
+            )
+            .as_bytes(),
+        );
+
+        for (prog, frontend) in self.fhe_program_fns.iter().zip(frontends) {
+            key.update(prog.name().as_bytes());
+            key.update(&to_bytes(&prog.signature())?);
+            key.update(&to_bytes(&prog.argument_names())?);
+            key.update(&(prog.chain_count() as u64).to_le_bytes());
+            key.update(&[u8::from(prog.scheme_type())]);
+            key.update(&to_bytes(frontend)?);
+        }
+
+        Ok(key)
+    }
+}
+
 impl<B> Default for ZkpCompilerData<B> {
     fn default() -> Self {
         Self {
@@ -296,63 +448,261 @@ impl Compiler {
     }
 }
 
+/**
+ * The FHE programs as validation built them, so compilation doesn't
+ * have to build them again.
+ */
+struct FheBuild {
+    /**
+     * The parameters the programs were built under: either those given
+     * to [`GenericCompiler::with_params`] or provisional ones.
+     */
+    params: Params,
+
+    /**
+     * Each program's frontend graph, in the order they were added.
+     */
+    frontends: Vec<FheFrontendCompilation>,
+
+    /**
+     * Each frontend graph lowered to an [`FheProgram`], without
+     * running the backend's passes.
+     */
+    irs: Vec<FheProgram>,
+}
+
 impl<T, B> GenericCompiler<T, B> {
-    fn compile_fhe(&self) -> Result<HashMap<String, CompiledFheProgram>> {
+    /**
+     * Runs the checks behind [`ValidationReport`], returning each
+     * problem found as the error compilation would have returned.
+     *
+     * # Remarks
+     * When there are no errors, also returns the programs as built for
+     * the checks.
+     */
+    fn validate_fhe(&self) -> (ValidationReport, Vec<Error>, Option<FheBuild>) {
         let fhe_data: &FheCompilerData = self.data.fhe_data();
 
-        if fhe_data.fhe_program_fns.is_empty() {
-            return Ok(HashMap::new());
-        }
+        let mut report = ValidationReport::default();
+        let mut errors = vec![];
+
+        let programs = &fhe_data.fhe_program_fns;
+
+        let first = match programs.first() {
+            Some(p) => p,
+            None => return (report, errors, None),
+        };
+
+        let scheme = first.scheme_type();
 
         // Check that all programs use the same scheme type.
-        // Unwrapping the iterator is safe because we checked that
-        // self.fhe_program_fns has at least 1 element
-        if fhe_data
-            .fhe_program_fns
-            .iter()
-            .any(|p| p.scheme_type() != fhe_data.fhe_program_fns.first().unwrap().scheme_type())
-        {
-            return Err(Error::SchemeMismatch);
+        if programs.iter().any(|p| p.scheme_type() != scheme) {
+            errors.push(Error::SchemeMismatch);
         }
 
         // Check that each fhe_program has a unique name
-        if fhe_data
-            .fhe_program_fns
+        if programs
             .iter()
             .map(|f| f.name().to_owned())
             .collect::<HashSet<String>>()
             .len()
-            != fhe_data.fhe_program_fns.len()
+            != programs.len()
         {
-            return Err(Error::NameCollision);
+            errors.push(Error::NameCollision);
         }
 
         // Check that every chain_count > 0.
-        if fhe_data
-            .fhe_program_fns
-            .iter()
-            .any(|p| p.chain_count() == 0)
-        {
-            return Err(Error::unsupported("Chain count must be greater than zero."));
+        if programs.iter().any(|p| p.chain_count() == 0) {
+            errors.push(Error::unsupported("Chain count must be greater than zero."));
         }
 
         // Check that either the max chain count is 1, or that only
         // one FHE program is specified in the application.
         // This restriction will be removed in the future.
-        let max_chain = fhe_data
-            .fhe_program_fns
+        let max_chain = programs
             .iter()
             .fold(0, |max, p| usize::max(p.chain_count(), max));
 
-        if max_chain > 1 && fhe_data.fhe_program_fns.len() > 1 {
-            return Err(Error::unsupported(
+        if max_chain > 1 && programs.len() > 1 {
+            errors.push(Error::unsupported(
                 "Cannot chain programs and specify more than one program in the same app.",
             ));
         }
 
+        // Parameter search only understands BFV's noise growth.
+        match &fhe_data.params_mode {
+            ParamsMode::Manual(p) if p.scheme_type != scheme => {
+                errors.push(Error::IncorrectScheme);
+            }
+            ParamsMode::Manual(_) => {}
+            _ if scheme != SchemeType::Bfv => errors.push(Error::IncorrectScheme),
+            _ => {}
+        }
+
+        // CKKS and BGV consume a modulus per multiplication.
+        if let ParamsMode::Manual(p) = &fhe_data.params_mode {
+            if p.scheme_type != SchemeType::Bfv && p.coeff_modulus.len() < 2 {
+                errors.push(Error::invalid_params(
+                    "Leveled schemes need at least 2 coefficient moduli",
+                ));
+            }
+        }
+
+        // Programs that can't share an application aren't worth
+        // analyzing further.
+        if !errors.is_empty() {
+            report.errors = errors.iter().map(|e| e.to_string()).collect();
+            return (report, errors, None);
+        }
+
+        let params = match &fhe_data.params_mode {
+            ParamsMode::Manual(p) => check_security_level(p).map(|_| p.clone()),
+            _ => provisional_params(fhe_data.plain_modulus_constraint, fhe_data.security_level),
+        };
+
+        let params = match params {
+            Ok(p) => p,
+            Err(e) => {
+                errors.push(e);
+
+                report.errors = errors.iter().map(|e| e.to_string()).collect();
+                return (report, errors, None);
+            }
+        };
+
+        let mut depth = 0;
+        let mut frontends = vec![];
+        let mut irs = vec![];
+
+        for prog in programs {
+            let frontend = match prog.build(&params) {
+                Ok(frontend) => frontend,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+
+            // The backend's passes don't change which rotations a
+            // program needs or the depth of paths to its outputs, so
+            // skip them.
+            let ir = to_fhe_program(&frontend);
+
+            if let Err(e) = ir.validate() {
+                errors.push(Error::FheProgramError(e));
+                continue;
+            }
+
+            if scheme == SchemeType::Ckks && combines_unaligned_ckks_operands(&ir) {
+                errors.push(Error::unsupported(&format!(
+                    "{} combines CKKS ciphertexts that went through different numbers of multiplications",
+                    prog.name()
+                )));
+                continue;
+            }
+
+            depth = usize::max(depth, ir.multiplicative_depth());
+            report
+                .required_galois_steps
+                .extend(ir.required_galois_steps());
+
+            frontends.push(frontend);
+            irs.push(ir);
+        }
+
+        report.estimated_depth = depth * max_chain;
+        report.required_galois_steps.sort_unstable();
+        report.required_galois_steps.dedup();
+
+        // Each CKKS multiplication rescales away a modulus, as does each
+        // BGV multiplication's modulus switch, and the last modulus is
+        // reserved for key switching. Plaintext multiplications count
+        // toward the depth but don't consume a modulus, so this only
+        // warns.
+        if let ParamsMode::Manual(p) = &fhe_data.params_mode {
+            let levels = p.coeff_modulus.len().saturating_sub(2);
+
+            if p.scheme_type != SchemeType::Bfv && report.estimated_depth > levels {
+                report.warnings.push(format!(
+                    "Multiplicative depth {} exceeds the {} levels the {:?} coefficient modulus supports",
+                    report.estimated_depth, levels, p.scheme_type
+                ));
+            }
+        }
+
+        report.errors = errors.iter().map(|e| e.to_string()).collect();
+
+        let build = if errors.is_empty() {
+            Some(FheBuild {
+                params,
+                frontends,
+                irs,
+            })
+        } else {
+            None
+        };
+
+        (report, errors, build)
+    }
+
+    fn compile_fhe(&self) -> Result<(HashMap<String, CompiledFheProgram>, CompileTimings)> {
+        let start = Instant::now();
+        let mut timings = CompileTimings::default();
+
+        let fhe_data: &FheCompilerData = self.data.fhe_data();
+
+        if fhe_data.fhe_program_fns.is_empty() {
+            return Ok((HashMap::new(), timings));
+        }
+
+        // Parameter search builds programs too, so install the cache
+        // before that.
+        let _encode_cache_scope = EncodeCacheScope::new(fhe_data.encode_cache.clone());
+
+        let (report, mut errors, build) = self.validate_fhe();
+
+        debug!(
+            "Pass validation: {} programs, {} errors",
+            fhe_data.fhe_program_fns.len(),
+            errors.len()
+        );
+
+        match errors.len() {
+            0 => {}
+            1 => return Err(errors.remove(0)),
+            _ => {
+                return Err(Error::validation_failed(
+                    &errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+                ))
+            }
+        }
+
+        // Validation builds every program when it finds no errors.
+        let build = build.unwrap();
+
         let scheme = fhe_data.fhe_program_fns.first().unwrap().scheme_type();
 
+        // User passes are arbitrary code, so they can't be part of the key.
+        let cache = match &fhe_data.cache_dir {
+            Some(dir) if fhe_data.user_passes.is_empty() => {
+                let cache = CompileCache::new(dir);
+                let key = fhe_data.cache_key(&build.frontends)?;
+
+                if let Some(fhe_programs) = cache.load(&key) {
+                    timings.total = start.elapsed();
+
+                    return Ok((fhe_programs, timings));
+                }
+
+                Some((cache, key))
+            }
+            _ => None,
+        };
+
+        let param_search_start = Instant::now();
+
         let params = match &fhe_data.params_mode {
+            // Validation already checked the security level.
             ParamsMode::Manual(p) => p.clone(),
             ParamsMode::Search => determine_params(
                 &fhe_data.fhe_program_fns,
@@ -361,15 +711,82 @@ impl<T, B> GenericCompiler<T, B> {
                 fhe_data.noise_margin,
                 scheme,
             )?,
+            // Validation measured the programs under provisional
+            // parameters.
+            ParamsMode::Depth => params_for_depth(
+                report.estimated_depth,
+                fhe_data.plain_modulus_constraint,
+                fhe_data.security_level,
+                fhe_data.noise_margin,
+                &build.irs,
+            )?,
         };
 
+        timings.param_search = param_search_start.elapsed();
+
+        debug!(
+            "Pass param_selection: lattice_dimension={}, plain_modulus={}, {} coefficient moduli in {:?}",
+            params.lattice_dimension,
+            params.plain_modulus,
+            params.coeff_modulus.len(),
+            timings.param_search
+        );
+
+        // Graphs built under other parameters may encode constants
+        // differently, so only reuse validation's if they match.
+        let mut prebuilt = if build.params == params {
+            build.frontends
+        } else {
+            vec![]
+        }
+        .into_iter();
+
         let fhe_programs = fhe_data
             .fhe_program_fns
             .iter()
             .map(|prog| {
-                let execution_graph = prog.build(&params);
+                let graph_build_start = Instant::now();
+                let execution_graph = match prebuilt.next() {
+                    Some(graph) => Ok(graph),
+                    None => prog.build(&params),
+                };
+                timings.graph_build += graph_build_start.elapsed();
+
                 let mut required_keys = vec![];
-                let fhe_program_fn = execution_graph?.compile();
+                let execution_graph = execution_graph?;
+
+                let (fhe_program_fn, pass_timings) = execution_graph.compile_with_passes(
+                    &CompileOptions {
+                        check_invariants: fhe_data.graph_invariant_check,
+                        relin_optimization: fhe_data.relin_optimization,
+                    },
+                    &fhe_data.user_passes,
+                    &params,
+                )?;
+
+                for t in pass_timings {
+                    match timings.passes.iter_mut().find(|(name, _)| name == t.name) {
+                        Some((_, duration)) => *duration += t.duration,
+                        None => timings.passes.push((t.name.to_owned(), t.duration)),
+                    }
+                }
+
+                // Each modulus switch consumes a prime, and the last one
+                // is reserved for key switching. SEAL fails when there's
+                // nothing left to switch to, so reject the program now.
+                if params.scheme_type == SchemeType::Bgv {
+                    let levels = params.coeff_modulus.len().saturating_sub(2);
+                    let needed = fhe_program_fn.mod_switch_depth() * prog.chain_count();
+
+                    if needed > levels {
+                        return Err(Error::invalid_params(&format!(
+                            "{} switches moduli {} times, but the coefficient modulus supports {}",
+                            prog.name(),
+                            needed,
+                            levels
+                        )));
+                    }
+                }
 
                 if fhe_program_fn.requires_relin_keys() {
                     required_keys.push(RequiredKeys::Relin);
@@ -383,6 +800,7 @@ impl<T, B> GenericCompiler<T, B> {
                     params: params.clone(),
                     required_keys,
                     signature: prog.signature(),
+                    argument_names: prog.argument_names(),
                 };
 
                 let compiled_program = CompiledFheProgram {
@@ -390,11 +808,38 @@ impl<T, B> GenericCompiler<T, B> {
                     metadata,
                 };
 
+                // Parameter search already ensures outputs are
+                // decryptable, but pinned parameters may not. CKKS has
+                // no noise budget to run out of.
+                if fhe_data.decryptability_check
+                    && matches!(fhe_data.params_mode, ParamsMode::Manual(_))
+                    && params.scheme_type == SchemeType::Bfv
+                {
+                    if let Some(node) = undecryptable_output(&compiled_program)? {
+                        return Err(Error::OutputUndecryptable { node });
+                    }
+                }
+
+                for w in overflow_warnings(&compiled_program) {
+                    warn!(
+                        "{}: node {} may overflow the plaintext modulus (coefficients up to {})",
+                        prog.name(),
+                        w.node.index(),
+                        w.bound.max_abs
+                    );
+                }
+
                 Ok((prog.name().to_owned(), compiled_program))
             })
             .collect::<Result<HashMap<_, _>>>()?;
 
-        Ok(fhe_programs)
+        if let Some((cache, key)) = cache {
+            cache.store(&key, &fhe_programs);
+        }
+
+        timings.total = start.elapsed();
+
+        Ok((fhe_programs, timings))
     }
 }
 
@@ -470,7 +915,23 @@ impl FheCompiler {
      * will return a [`Error::NameCollision`] error.
      */
     pub fn compile(self) -> Result<Application<Fhe>> {
-        Application::new(self.compile_fhe()?, HashMap::new())
+        let (fhe_programs, _) = self.compile_fhe()?;
+
+        Application::new(fhe_programs, HashMap::new())
+    }
+
+    /**
+     * Like [`compile`](Self::compile), but also returns a breakdown of
+     * how long each compilation phase took.
+     *
+     * # Remarks
+     * This is useful for finding which phase of compilation is
+     * responsible when compiling an application is slow.
+     */
+    pub fn compile_timed(self) -> Result<(Application<Fhe>, CompileTimings)> {
+        let (fhe_programs, timings) = self.compile_fhe()?;
+
+        Ok((Application::new(fhe_programs, HashMap::new())?, timings))
     }
 }
 
@@ -583,6 +1044,27 @@ impl<T, B> GenericCompiler<T, B>
 where
     T: marker::Fhe,
 {
+    /**
+     * Checks the FHE programs for problems without compiling them.
+     *
+     * # Remarks
+     * This runs the same checks `compile` does before searching for
+     * parameters: that programs agree on a scheme, have unique names and
+     * valid chain counts, build into well-formed graphs, and that any
+     * parameters given with [`with_params`](Self::with_params) meet their
+     * security level. It's much faster than compiling, since it skips
+     * parameter search, backend passes, and SEAL setup.
+     *
+     * `compile` fails whenever the returned report has errors. With
+     * a single error, `compile` returns it as is; with several, it
+     * returns an [`Error::ValidationFailed`] listing the same messages.
+     */
+    pub fn validate(&self) -> ValidationReport {
+        let _encode_cache_scope = EncodeCacheScope::new(self.data.fhe_data().encode_cache.clone());
+
+        self.validate_fhe().0
+    }
+
     /**
      * Set the compiler to search for suitable encryption scheme parameters for the FHE program.
      */
@@ -591,6 +1073,30 @@ where
         self
     }
 
+    /**
+     * Choose the smallest parameters supporting the FHE programs'
+     * multiplicative depth at the given security level.
+     *
+     * # Remarks
+     * The compiler first builds each program to measure its
+     * multiplicative depth, then selects parameters whose noise budget
+     * survives a chain of that many multiplications and compiles again
+     * with them. This is faster than [`find_params`](Self::find_params),
+     * which simulates the noise of every program under each candidate,
+     * but only models multiplications and so may pick larger or smaller
+     * parameters than necessary. The plain modulus constraint and noise
+     * margin still apply.
+     *
+     * Compilation fails with [`Error::NoParams`] if no lattice dimension
+     * permitted at `security` supports the programs' depth.
+     */
+    pub fn auto_params(mut self, security: SecurityLevel) -> Self {
+        let data = self.data.fhe_data_mut();
+        data.params_mode = ParamsMode::Depth;
+        data.security_level = security;
+        self
+    }
+
     /**
      * Set the constraint the parameter search algorithm places on the plaintext modulus.
      * You can either force the algorithm to use an exact value or any value that supports
@@ -604,6 +1110,11 @@ where
     /**
      * Don't use the parameter search algorithm, and instead explicitly set the scheme's parameters.
      * For expert use and may cause failures.
+     *
+     * # Remarks
+     * Compilation fails with [`Error::InvalidParams`] if the coefficient
+     * modulus is too large for the lattice dimension to meet
+     * `params.security_level`.
      */
     pub fn with_params(mut self, params: &Params) -> Self {
         self.data.fhe_data_mut().params_mode = ParamsMode::Manual(params.clone());
@@ -625,6 +1136,265 @@ where
         self.data.fhe_data_mut().noise_margin = noise_margin;
         self
     }
+
+    /**
+     * Whether to validate the FHE program's graph after every backend
+     * optimization pass.
+     *
+     * # Remarks
+     * This is a development tool for catching passes that break graph
+     * invariants (e.g. an output with no operand). If a pass produces an
+     * invalid graph, `compile` returns
+     * [`Error::GraphInvariantViolation`] with the name of the pass and
+     * the violated invariant.
+     *
+     * Enabled by default in builds with debug assertions and disabled
+     * otherwise.
+     */
+    pub fn with_graph_invariant_check(mut self, enabled: bool) -> Self {
+        self.data.fhe_data_mut().graph_invariant_check = enabled;
+        self
+    }
+
+    /**
+     * Reuse literal encodings from the given [`EncodeCache`] while
+     * compiling, and add any new ones to it.
+     *
+     * # Remarks
+     * Pass the same cache to several compilers to avoid re-encoding
+     * the constants they share.
+     */
+    pub fn encode_cache(mut self, cache: &EncodeCache) -> Self {
+        self.data.fhe_data_mut().encode_cache = Some(cache.clone());
+        self
+    }
+
+    /**
+     * Cache compiled programs in `dir`, and reuse them when compiling
+     * the same programs with the same settings again, e.g. after a
+     * restart.
+     *
+     * # Remarks
+     * Entries are keyed by a hash of each program's graph, its
+     * signature, and every compiler setting, including requested
+     * parameters and security level, so changing any of these
+     * recompiles. Loading a cached application skips parameter search
+     * and the backend as well as their overflow warnings. Compilers
+     * with [`with_pass`](Self::with_pass) passes don't use the cache.
+     *
+     * Only use cache directories you trust, as cached programs run as
+     * written.
+     */
+    pub fn with_cache_dir(mut self, dir: PathBuf) -> Self {
+        self.data.fhe_data_mut().cache_dir = Some(dir);
+        self
+    }
+
+    /**
+     * Whether to defer relinearizing products until their size matters,
+     * e.g. before another multiplication or an output. This emits one
+     * relinearization for `(a * b) + (c * d)` instead of two.
+     *
+     * # Remarks
+     * Enabled by default. Disabling it relinearizes after every
+     * ciphertext multiplication.
+     */
+    pub fn with_relin_optimization(mut self, enabled: bool) -> Self {
+        self.data.fhe_data_mut().relin_optimization = enabled;
+        self
+    }
+
+    /**
+     * Whether to reject FHE programs whose outputs the static noise
+     * estimate expects to exhaust their noise budget under parameters
+     * given with [`with_params`](Self::with_params). Such programs fail
+     * to compile with [`Error::OutputUndecryptable`].
+     *
+     * # Remarks
+     * Disabled by default. The estimate is pessimistic, so it may
+     * reject parameters under which a program decrypts correctly.
+     * Automatically chosen parameters always leave outputs
+     * decryptable, so this check doesn't apply to them.
+     */
+    pub fn with_decryptability_check(mut self, enabled: bool) -> Self {
+        self.data.fhe_data_mut().decryptability_check = enabled;
+        self
+    }
+
+    /**
+     * Run the given pass over each FHE program's graph after the
+     * built-in optimization passes (constant folding, dead code
+     * elimination, and common subexpression elimination) and before
+     * relinearizations are inserted.
+     *
+     * # Remarks
+     * Passes added this way run in the order they were added. Parameter
+     * search only runs the built-in passes, so use
+     * [`with_params`](Self::with_params) if your pass makes programs
+     * deeper.
+     *
+     * If the pass fails, `compile` returns
+     * [`Error::CompilerPassFailed`].
+     */
+    pub fn with_pass<P>(self, pass: P) -> Self
+    where
+        P: CompilerPass<Operation, Params> + Send + Sync + 'static,
+    {
+        self.add_pass(pass, PassPosition::AfterOptimizations)
+    }
+
+    /**
+     * Run the given pass immediately before the pass named `existing`.
+     * Built-in passes are named `constant_folding`,
+     * `dead_code_elimination`, `common_subexpression_elimination`,
+     * `insert_relinearizations`, `insert_mod_switches` (BGV only), and
+     * `compact`. Passes added earlier may also be named.
+     *
+     * # Remarks
+     * If no pass is named `existing`, `compile` returns
+     * [`Error::UnknownPass`].
+     */
+    pub fn with_pass_before<P>(self, existing: &str, pass: P) -> Self
+    where
+        P: CompilerPass<Operation, Params> + Send + Sync + 'static,
+    {
+        self.add_pass(pass, PassPosition::Before(existing.to_owned()))
+    }
+
+    /**
+     * Run the given pass immediately after the pass named `existing`.
+     * See [`with_pass_before`](Self::with_pass_before).
+     */
+    pub fn with_pass_after<P>(self, existing: &str, pass: P) -> Self
+    where
+        P: CompilerPass<Operation, Params> + Send + Sync + 'static,
+    {
+        self.add_pass(pass, PassPosition::After(existing.to_owned()))
+    }
+
+    fn add_pass<P>(mut self, pass: P, position: PassPosition) -> Self
+    where
+        P: CompilerPass<Operation, Params> + Send + Sync + 'static,
+    {
+        self.data.fhe_data_mut().user_passes.push(UserPass {
+            pass: Arc::new(pass),
+            position,
+        });
+        self
+    }
+}
+
+/**
+ * Statically finds nodes in `program` whose results may overflow its
+ * plaintext modulus.
+ *
+ * # Remarks
+ * Only programs whose arguments and return values are all
+ * [`Signed`](crate::types::bfv::Signed) or
+ * [`Unsigned`](crate::types::bfv::Unsigned) are analyzed, as other
+ * encodings don't bound their coefficients the same way. Each input is
+ * assumed to be any value of its type.
+ */
+pub(crate) fn overflow_warnings(program: &CompiledFheProgram) -> Vec<OverflowWarning> {
+    let signed = Signed::type_name().name;
+    let unsigned = Unsigned::<1>::type_name().name;
+
+    let signature = &program.metadata.signature;
+    let types = || signature.arguments.iter().chain(signature.returns.iter());
+
+    if !types().all(|t| t.name == signed || t.name == unsigned) {
+        return vec![];
+    }
+
+    // Both types encode each bit into a coefficient of magnitude at most
+    // 1. Unsigned's type name doesn't record its width, so assume the
+    // widest alias.
+    let bits = if types().any(|t| t.name == unsigned) {
+        512
+    } else {
+        i64::BITS as u64
+    };
+
+    let input_bound = CoefficientBound {
+        max_abs: 1,
+        len: bits,
+    };
+
+    let params = &program.metadata.params;
+
+    find_overflows(
+        &program.fhe_program_fn,
+        params.plain_modulus,
+        params.lattice_dimension,
+        input_bound,
+    )
+}
+
+/**
+ * Returns the first output node of `program` that the canonical
+ * embedding norm noise model expects to have no noise budget left
+ * under the program's parameters.
+ */
+pub(crate) fn undecryptable_output(program: &CompiledFheProgram) -> Result<Option<NodeIndex>> {
+    let model = CanonicalEmbeddingNormModel::new(&program.metadata.params)
+        .map_err(|_| Error::invalid_params("Cannot model noise under the given parameters"))?;
+
+    let ir = &program.fhe_program_fn;
+
+    // predict_noise orders outputs by node index too.
+    let outputs = ir
+        .graph
+        .node_indices()
+        .filter(|id| matches!(ir.graph[*id].operation, Operation::OutputCiphertext));
+
+    Ok(outputs
+        .zip(predict_noise(&model, ir))
+        .find(|(_, noise)| noise_to_noise_budget(*noise) <= 0.)
+        .map(|(id, _)| id))
+}
+
+/**
+ * Whether a CKKS program adds, subtracts, or multiplies ciphertexts
+ * that went through different numbers of multiplications, such as the
+ * addition in `a * b + c`.
+ *
+ * # Remarks
+ * The runtime rescales each product, which drops a prime and divides
+ * its scale, and SEAL only combines ciphertexts at the same level and
+ * scale.
+ */
+fn combines_unaligned_ckks_operands(ir: &FheProgram) -> bool {
+    let mut levels = HashMap::new();
+
+    // FHE programs are DAGs, so this can't fail.
+    for id in toposort(&ir.graph.0, None).unwrap() {
+        let op = &ir.graph[id].operation;
+
+        let operands = ir
+            .graph
+            .neighbors_directed(id, Direction::Incoming)
+            .filter_map(|p| levels.get(&p).copied())
+            .collect::<Vec<usize>>();
+
+        let level = match op {
+            Operation::InputCiphertext(_) => 0,
+            _ => match operands.iter().max() {
+                Some(l) => *l,
+                // Plaintexts have no level.
+                None => continue,
+            },
+        };
+
+        let combines = matches!(op, Operation::Add | Operation::Sub | Operation::Multiply);
+
+        if combines && operands.iter().any(|l| *l != level) {
+            return true;
+        }
+
+        levels.insert(id, level + usize::from(matches!(op, Operation::Multiply)));
+    }
+
+    false
 }
 
 /**
@@ -641,6 +1411,7 @@ pub type FheZkpCompiler<F> = GenericCompiler<FheZkp, BoxZkpFn<F>>;
 mod tests {
     use std::any::{Any, TypeId};
 
+    use seal_fhe::CoefficientModulus;
     use sunscreen_compiler_macros::{fhe_program, zkp_program};
     use sunscreen_zkp_backend::bulletproofs::BulletproofsBackend;
 
@@ -735,4 +1506,503 @@ mod tests {
 
         assert_eq!(app.type_id(), TypeId::of::<Application<FheZkp>>());
     }
+
+    #[test]
+    fn can_compile_with_graph_invariant_check() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn mul_add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b + a
+        }
+
+        let app = Compiler::new()
+            .fhe_program(mul_add)
+            .with_graph_invariant_check(true)
+            .compile()
+            .unwrap();
+
+        assert!(app.get_fhe_program(mul_add).is_some());
+    }
+
+    #[test]
+    fn graph_invariant_check_can_be_toggled() {
+        let compiler = Compiler::new();
+
+        assert_eq!(
+            compiler.data.fhe_data().graph_invariant_check,
+            cfg!(debug_assertions)
+        );
+
+        let compiler = compiler.with_graph_invariant_check(false);
+        assert!(!compiler.data.fhe_data().graph_invariant_check);
+
+        let compiler = compiler.with_graph_invariant_check(true);
+        assert!(compiler.data.fhe_data().graph_invariant_check);
+    }
+
+    #[test]
+    fn compile_timed_reports_phases() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn mul_add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b + a
+        }
+
+        let (app, timings) = Compiler::new()
+            .fhe_program(mul_add)
+            .compile_timed()
+            .unwrap();
+
+        assert!(app.get_fhe_program(mul_add).is_some());
+        assert!(timings.param_search > Duration::ZERO);
+        assert!(timings.graph_build > Duration::ZERO);
+        assert!(!timings.passes.is_empty());
+
+        let sum = timings.graph_build
+            + timings.param_search
+            + timings.passes.iter().map(|(_, d)| *d).sum::<Duration>();
+
+        // The phases should account for nearly all of compilation.
+        assert!(sum <= timings.total);
+        assert!(sum * 2 >= timings.total);
+    }
+
+    #[test]
+    fn encode_cache_is_reused_across_compiles() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn constants(a: Cipher<Signed>) -> Cipher<Signed> {
+            (a + 3) * 5 - 7
+        }
+
+        let cache = EncodeCache::new();
+
+        let compile = || {
+            Compiler::new()
+                .fhe_program(constants)
+                .encode_cache(&cache)
+                .compile()
+                .unwrap()
+        };
+
+        compile();
+
+        // Parameter search may encode each literal under several
+        // candidate parameter sets.
+        let entries = cache.len();
+        let hits = cache.hits();
+        assert!(entries >= 3);
+
+        compile();
+
+        // Recompiling encodes no new literals.
+        assert_eq!(cache.len(), entries);
+        assert!(cache.hits() >= hits + 3);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.hits(), 0);
+    }
+
+    #[test]
+    fn application_reports_multiplicative_depth() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn cube_plus(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * a * a + b
+        }
+
+        let app = Compiler::new().fhe_program(cube_plus).compile().unwrap();
+
+        assert_eq!(app.multiplicative_depth(cube_plus), Some(2));
+        assert_eq!(app.multiplicative_depth("missing"), None);
+    }
+
+    #[test]
+    fn compiled_programs_report_operation_counts() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn simple_add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a + b
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        let app = Compiler::new()
+            .fhe_program(simple_add)
+            .fhe_program(simple_multiply)
+            .compile()
+            .unwrap();
+
+        let add = app.get_fhe_program(simple_add).unwrap().operation_count();
+        let mul = app
+            .get_fhe_program(simple_multiply)
+            .unwrap()
+            .operation_count();
+
+        assert_eq!(add.additions, 1);
+        assert_eq!(add.multiplications, 0);
+        assert_eq!(add.relinearizations, 0);
+
+        assert_eq!(mul.additions, 0);
+        assert_eq!(mul.multiplications, 1);
+        assert_eq!(mul.relinearizations, 1);
+
+        assert!(add.total_cost() < mul.total_cost());
+    }
+
+    #[test]
+    fn auto_params_scales_with_depth() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a + b
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn pow_8(a: Cipher<Signed>) -> Cipher<Signed> {
+            let a_2 = a * a;
+            let a_4 = a_2 * a_2;
+            a_4 * a_4
+        }
+
+        let shallow = Compiler::new()
+            .fhe_program(add)
+            .auto_params(SecurityLevel::TC192)
+            .compile()
+            .unwrap();
+
+        let deep = Compiler::new()
+            .fhe_program(pow_8)
+            .auto_params(SecurityLevel::TC192)
+            .compile()
+            .unwrap();
+
+        assert_eq!(shallow.params().security_level, SecurityLevel::TC192);
+        assert_eq!(deep.params().security_level, SecurityLevel::TC192);
+        assert!(shallow.params().lattice_dimension < deep.params().lattice_dimension);
+
+        let runtime = FheRuntime::new(deep.params()).unwrap();
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        let a = runtime.encrypt(Signed::from(2), &public_key).unwrap();
+
+        let result = runtime
+            .run(deep.get_fhe_program(pow_8).unwrap(), vec![a], &public_key)
+            .unwrap();
+
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, 256.into());
+    }
+
+    #[test]
+    fn reports_multiplicative_depth_of_compiled_programs() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn chained_multiply(
+            a: Cipher<Signed>,
+            b: Cipher<Signed>,
+            c: Cipher<Signed>,
+        ) -> Cipher<Signed> {
+            (a * b) * c
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a + b
+        }
+
+        let app = Compiler::new()
+            .fhe_program(chained_multiply)
+            .fhe_program(add)
+            .compile()
+            .unwrap();
+
+        assert_eq!(app.multiplicative_depth(chained_multiply), Some(2));
+        assert_eq!(app.multiplicative_depth(add), Some(0));
+        assert_eq!(app.multiplicative_depth("missing"), None);
+    }
+
+    #[test]
+    fn validate_reports_depth_and_rotations() {
+        use crate::types::{
+            bfv::{Batched, Signed},
+            Cipher,
+        };
+
+        #[fhe_program(scheme = "bfv")]
+        fn chained_multiply(
+            a: Cipher<Signed>,
+            b: Cipher<Signed>,
+            c: Cipher<Signed>,
+        ) -> Cipher<Signed> {
+            (a * b) * c
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn rotate(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+            (a << 1) + (a >> 3)
+        }
+
+        let report = Compiler::new()
+            .fhe_program(chained_multiply)
+            .fhe_program(rotate)
+            .validate();
+
+        assert_eq!(
+            report,
+            ValidationReport {
+                estimated_depth: 2,
+                required_galois_steps: vec![-3, 1],
+                warnings: vec![],
+                errors: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn compile_fails_with_validation_errors() {
+        use crate::types::{bfv::Signed, ckks::Complex, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a + b
+        }
+
+        #[fhe_program(scheme = "ckks")]
+        fn add_complex(a: Cipher<Complex>, b: Cipher<Complex>) -> Cipher<Complex> {
+            a + b
+        }
+
+        let compiler = || Compiler::new().fhe_program(add).fhe_program(add_complex);
+
+        let report = compiler().validate();
+
+        assert_eq!(
+            report.errors,
+            vec![
+                Error::SchemeMismatch.to_string(),
+                Error::IncorrectScheme.to_string()
+            ]
+        );
+        assert!(matches!(
+            compiler().compile(),
+            Err(Error::ValidationFailed(e)) if *e == report.errors
+        ));
+
+        let compiler = || Compiler::new().fhe_program(add).fhe_program(add);
+
+        assert_eq!(
+            compiler().validate().errors,
+            vec![Error::NameCollision.to_string()]
+        );
+        assert!(matches!(compiler().compile(), Err(Error::NameCollision)));
+    }
+
+    #[test]
+    fn search_honors_each_security_level() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        for level in [
+            SecurityLevel::TC128,
+            SecurityLevel::TC192,
+            SecurityLevel::TC256,
+        ] {
+            let app = Compiler::new()
+                .fhe_program(simple_multiply)
+                .security_level(level)
+                .compile()
+                .unwrap();
+
+            let params = app.params();
+
+            assert_eq!(params.security_level, level);
+            assert_eq!(
+                params.coeff_modulus,
+                CoefficientModulus::bfv_default(params.lattice_dimension, level)
+                    .unwrap()
+                    .iter()
+                    .map(|m| m.value())
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn manual_params_must_meet_security_level() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        // The TC128 default chain for 8192 exceeds the TC256 bound.
+        let params = Params {
+            lattice_dimension: 8192,
+            plain_modulus: 1024,
+            coeff_modulus: CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|m| m.value())
+                .collect(),
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC256,
+        };
+
+        let result = Compiler::new()
+            .fhe_program(simple_multiply)
+            .with_params(&params)
+            .compile();
+
+        assert!(matches!(result, Err(Error::InvalidParams(_))));
+    }
+
+    #[test]
+    fn auto_params_meets_security_level() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        let app = Compiler::new()
+            .fhe_program(simple_multiply)
+            .auto_params(SecurityLevel::TC128)
+            .compile()
+            .unwrap();
+
+        let params = app.params();
+
+        assert_eq!(params.security_level, SecurityLevel::TC128);
+        assert_eq!(
+            params.coeff_modulus,
+            CoefficientModulus::bfv_default(params.lattice_dimension, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|m| m.value())
+                .collect::<Vec<_>>()
+        );
+
+        let runtime = FheRuntime::new(params).unwrap();
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        let a = runtime.encrypt(Signed::from(-3), &public_key).unwrap();
+        let b = runtime.encrypt(Signed::from(7), &public_key).unwrap();
+
+        let result = runtime
+            .run(
+                app.get_fhe_program(simple_multiply).unwrap(),
+                vec![a, b],
+                &public_key,
+            )
+            .unwrap();
+
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, (-21).into());
+    }
+
+    #[test]
+    #[ignore]
+    fn auto_params_fails_when_too_deep() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn pow_2_30(a: Cipher<Signed>) -> Cipher<Signed> {
+            let mut x = a;
+
+            for _ in 0..30 {
+                x = x * x;
+            }
+
+            x
+        }
+
+        let result = Compiler::new()
+            .fhe_program(pow_2_30)
+            .auto_params(SecurityLevel::TC128)
+            .compile();
+
+        assert!(matches!(result, Err(Error::NoParams)));
+    }
+
+    #[test]
+    fn compile_builds_each_program_once_under_manual_params() {
+        use crate::types::{bfv::Signed, Cipher};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[fhe_program(scheme = "bfv")]
+        fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        struct CountBuilds<F>(F, Arc<AtomicUsize>);
+
+        impl<F: FheProgramFn> FheProgramFn for CountBuilds<F> {
+            fn signature(&self) -> CallSignature {
+                self.0.signature()
+            }
+
+            fn build(&self, params: &Params) -> Result<FheFrontendCompilation> {
+                self.1.fetch_add(1, Ordering::SeqCst);
+                self.0.build(params)
+            }
+
+            fn argument_names(&self) -> Vec<String> {
+                self.0.argument_names()
+            }
+
+            fn scheme_type(&self) -> SchemeType {
+                self.0.scheme_type()
+            }
+
+            fn name(&self) -> &str {
+                self.0.name()
+            }
+
+            fn chain_count(&self) -> usize {
+                self.0.chain_count()
+            }
+        }
+
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1024,
+            coeff_modulus: CoefficientModulus::bfv_default(4096, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|m| m.value())
+                .collect(),
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let builds = Arc::new(AtomicUsize::new(0));
+
+        Compiler::new()
+            .fhe_program(CountBuilds(simple_multiply, builds.clone()))
+            .with_params(&params)
+            .compile()
+            .unwrap();
+
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+    }
 }
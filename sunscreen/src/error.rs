@@ -1,4 +1,5 @@
 use static_assertions::const_assert;
+use sunscreen_compiler_common::transforms::CompilerPassError;
 
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 /**
@@ -42,12 +43,26 @@ pub enum Error {
     #[error("Name collision")]
     NameCollision,
 
+    /**
+     * Attempted to combine FHE programs compiled under different
+     * parameters. See [`Application::merge`](crate::Application::merge).
+     */
+    #[error("Programs were compiled under different parameters")]
+    ParamsMismatch,
+
     /**
      * Failed to created an encryption scheme using the given parameters.
      */
     #[error("Cannot create encryption scheme from parameters")]
     SealEncryptionParameterError,
 
+    /**
+     * The parameters given to a [`ParamsBuilder`](crate::ParamsBuilder)
+     * are incomplete or don't meet the claimed security level.
+     */
+    #[error("Invalid parameters: {0}")]
+    InvalidParams(Box<String>),
+
     /**
      * The a constraint cannot be satisfied.
      */
@@ -77,6 +92,74 @@ pub enum Error {
      */
     #[error("Unsupported: {0}")]
     Unsupported(Box<String>),
+
+    /**
+     * A backend compiler pass (first argument) produced an FHE program
+     * that violates a graph invariant (second argument). Only returned
+     * when the check is enabled, which by default it is in debug builds.
+     * See
+     * [`with_graph_invariant_check`](crate::GenericCompiler::with_graph_invariant_check).
+     */
+    #[error("Graph invariant violated after pass {}: {}", .0.0, .0.1)]
+    GraphInvariantViolation(Box<(String, sunscreen_fhe_program::Error)>),
+
+    /**
+     * A user-defined pass was placed relative to a compiler pass that
+     * doesn't exist. See
+     * [`with_pass_before`](crate::GenericCompiler::with_pass_before).
+     */
+    #[error("No compiler pass named {0}")]
+    UnknownPass(Box<String>),
+
+    /**
+     * A user-defined compiler pass (first argument) failed (second
+     * argument).
+     */
+    #[error("Compiler pass {} failed: {}", .0.0, .0.1)]
+    CompilerPassFailed(Box<(String, CompilerPassError)>),
+
+    /**
+     * The static noise estimate expects the given output node to have
+     * no noise budget left under the parameters given with
+     * [`with_params`](crate::GenericCompiler::with_params), so it won't
+     * decrypt correctly. See
+     * [`with_decryptability_check`](crate::GenericCompiler::with_decryptability_check).
+     */
+    #[error("Output node {} is not expected to decrypt correctly under the given parameters", .node.index())]
+    OutputUndecryptable {
+        /**
+         * The undecryptable output's node in the compiled FHE program.
+         */
+        node: petgraph::stable_graph::NodeIndex,
+    },
+    /**
+     * Reading or writing a file failed.
+     */
+    #[error("IO error: {0}")]
+    IoError(Box<String>),
+
+    /**
+     * Failed to serialize or deserialize an
+     * [`Application`](crate::Application).
+     */
+    #[error("Serialization error: {0}")]
+    SerializationError(Box<String>),
+
+    /**
+     * The file passed to [`Application::load`](crate::Application::load)
+     * was written in the given format version, which this version of
+     * Sunscreen can't read.
+     */
+    #[error("Unsupported application format version {0}")]
+    UnsupportedFormatVersion(u32),
+
+    /**
+     * Validating the FHE programs found more than one problem. Each
+     * problem is described by a string, as in
+     * [`ValidationReport::errors`](crate::ValidationReport::errors).
+     */
+    #[error("Validation failed: {}", .0.join("; "))]
+    ValidationFailed(Box<Vec<String>>),
 }
 
 const_assert!(std::mem::size_of::<Error>() <= 24);
@@ -88,6 +171,55 @@ impl Error {
     pub fn unsupported(msg: &str) -> Self {
         Self::Unsupported(Box::new(msg.to_owned()))
     }
+
+    /**
+     * Create an [`Error::InvalidParams`]
+     */
+    pub fn invalid_params(msg: &str) -> Self {
+        Self::InvalidParams(Box::new(msg.to_owned()))
+    }
+
+    /**
+     * Create an [`Error::GraphInvariantViolation`]
+     */
+    pub fn graph_invariant_violation(pass: &str, err: sunscreen_fhe_program::Error) -> Self {
+        Self::GraphInvariantViolation(Box::new((pass.to_owned(), err)))
+    }
+
+    /**
+     * Create an [`Error::UnknownPass`]
+     */
+    pub fn unknown_pass(pass: &str) -> Self {
+        Self::UnknownPass(Box::new(pass.to_owned()))
+    }
+
+    /**
+     * Create an [`Error::CompilerPassFailed`]
+     */
+    pub fn compiler_pass_failed(pass: &str, err: CompilerPassError) -> Self {
+        Self::CompilerPassFailed(Box::new((pass.to_owned(), err)))
+    }
+
+    /**
+     * Create an [`Error::IoError`]
+     */
+    pub fn io_error(msg: &str) -> Self {
+        Self::IoError(Box::new(msg.to_owned()))
+    }
+
+    /**
+     * Create an [`Error::SerializationError`]
+     */
+    pub fn serialization_error(msg: &str) -> Self {
+        Self::SerializationError(Box::new(msg.to_owned()))
+    }
+
+    /**
+     * Create an [`Error::ValidationFailed`]
+     */
+    pub fn validation_failed(errors: &[String]) -> Self {
+        Self::ValidationFailed(Box::new(errors.to_owned()))
+    }
 }
 
 /**
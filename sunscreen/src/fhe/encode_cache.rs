@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use sunscreen_runtime::{InnerPlaintext, Params, TypeName};
+
+type EncodeCacheKey = (String, Vec<u64>, Params);
+
+#[derive(Default)]
+struct EncodeCacheData {
+    entries: HashMap<EncodeCacheKey, InnerPlaintext>,
+    hits: usize,
+}
+
+#[derive(Clone, Default)]
+/**
+ * A cache of encoded literal plaintexts shared across compilations.
+ *
+ * # Remarks
+ * Each FHE program deduplicates its own literals, but every compile
+ * re-encodes them from scratch. Tooling that compiles many programs
+ * sharing the same constants can pass one `EncodeCache` to each
+ * [`Compiler`](crate::Compiler) via `encode_cache` to encode each
+ * `(type, value, params)` combination only once.
+ *
+ * Currently caches [`Signed`](crate::types::bfv::Signed) and
+ * [`Unsigned`](crate::types::bfv::Unsigned) literals. Clones share the
+ * same underlying cache.
+ */
+pub struct EncodeCache {
+    inner: Arc<Mutex<EncodeCacheData>>,
+}
+
+impl EncodeCache {
+    /**
+     * Creates an empty cache.
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * The number of distinct encoded literals in the cache.
+     */
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    /**
+     * Whether the cache contains no encoded literals.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+     * The number of times a literal's encoding was served from the
+     * cache rather than recomputed.
+     */
+    pub fn hits(&self) -> usize {
+        self.inner.lock().unwrap().hits
+    }
+
+    /**
+     * Removes every entry and resets the hit count.
+     */
+    pub fn clear(&self) {
+        *self.inner.lock().unwrap() = EncodeCacheData::default();
+    }
+
+    fn get_or_insert_with<F>(&self, key: EncodeCacheKey, encode: F) -> InnerPlaintext
+    where
+        F: FnOnce() -> InnerPlaintext,
+    {
+        let mut data = self.inner.lock().unwrap();
+
+        if let Some(plaintext) = data.entries.get(&key) {
+            let plaintext = plaintext.clone();
+            data.hits += 1;
+
+            return plaintext;
+        }
+
+        let plaintext = encode();
+        data.entries.insert(key, plaintext.clone());
+
+        plaintext
+    }
+}
+
+thread_local! {
+    static CURRENT_ENCODE_CACHE: RefCell<Option<EncodeCache>> = RefCell::new(None);
+}
+
+/**
+ * Makes an [`EncodeCache`] available to literal encoding on this
+ * thread until dropped.
+ */
+pub(crate) struct EncodeCacheScope {
+    prev: Option<EncodeCache>,
+}
+
+impl EncodeCacheScope {
+    pub(crate) fn new(cache: Option<EncodeCache>) -> Self {
+        let prev = CURRENT_ENCODE_CACHE.with(|c| c.replace(cache));
+
+        Self { prev }
+    }
+}
+
+impl Drop for EncodeCacheScope {
+    fn drop(&mut self) {
+        CURRENT_ENCODE_CACHE.with(|c| *c.borrow_mut() = self.prev.take());
+    }
+}
+
+/**
+ * Encodes the literal of type `T` whose value has the little-endian
+ * bit pattern `words`, using the current [`EncodeCache`] if one is in
+ * scope.
+ */
+pub(crate) fn encode_literal<T, F>(words: &[u64], params: &Params, encode: F) -> InnerPlaintext
+where
+    T: TypeName,
+    F: FnOnce() -> InnerPlaintext,
+{
+    let cache = CURRENT_ENCODE_CACHE.with(|c| c.borrow().clone());
+
+    match cache {
+        Some(cache) => cache.get_or_insert_with(
+            (T::type_name().name, words.to_owned(), params.clone()),
+            encode,
+        ),
+        None => encode(),
+    }
+}
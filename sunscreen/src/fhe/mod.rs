@@ -1,6 +1,14 @@
+mod encode_cache;
+
+pub use encode_cache::*;
+
+use log::debug;
 use petgraph::stable_graph::NodeIndex;
 use serde::{Deserialize, Serialize};
-use sunscreen_backend::compile_inplace;
+use sunscreen_backend::{
+    compile_inplace, compile_inplace_timed, compile_inplace_with_invariant_check,
+    compile_inplace_with_passes, CompileOptions, PassTiming, UserPass,
+};
 use sunscreen_compiler_common::{
     CompilationResult, Context, EdgeInfo, NodeInfo, Operation as OperationTrait,
 };
@@ -11,6 +19,8 @@ use sunscreen_runtime::{InnerPlaintext, Params};
 
 use std::cell::RefCell;
 
+use crate::{Error, Result};
+
 #[derive(Clone, Debug, Deserialize, Hash, Serialize, PartialEq, Eq)]
 /**
  * Represents a literal node's data.
@@ -161,6 +171,36 @@ thread_local! {
      * implementation detail and not for public consumption.
      */
     pub static CURRENT_FHE_CTX: RefCell<Option<&'static mut FheContext>> = RefCell::new(None);
+
+    /**
+     * The first error raised while building the current FHE program.
+     */
+    static BUILD_ERROR: RefCell<Option<Error>> = RefCell::new(None);
+}
+
+/**
+ * Fails building the current [`fhe_program`](crate::fhe_program) with
+ * the given error once its body returns.
+ *
+ * # Remarks
+ * Graph operations can't return errors, so operations that are invalid
+ * under the current parameters call this and emit a placeholder node.
+ * Only the first error is kept.
+ */
+pub fn set_build_error(err: Error) {
+    BUILD_ERROR.with(|e| {
+        e.borrow_mut().get_or_insert(err);
+    });
+}
+
+#[doc(hidden)]
+/**
+ * Takes the error recorded by [`set_build_error`], if any. An
+ * implementation detail of the [`fhe_program`](crate::fhe_program)
+ * macro.
+ */
+pub fn take_build_error() -> Option<Error> {
+    BUILD_ERROR.with(|e| e.borrow_mut().take())
 }
 
 /**
@@ -345,59 +385,146 @@ pub trait FheCompile {
      * then perform backend compilation and return the result.
      */
     fn compile(&self) -> FheProgram;
+
+    /**
+     * Like [`compile`](FheCompile::compile), but validates the
+     * [`FheProgram`] after every backend pass.
+     *
+     * # Remarks
+     * Returns [`Error::GraphInvariantViolation`] naming the first
+     * pass that produced an invalid graph.
+     */
+    fn compile_with_invariant_check(&self) -> Result<FheProgram>;
+
+    /**
+     * Like [`compile`](FheCompile::compile), but under the given
+     * options and also returns how long each backend pass took. When
+     * `options.check_invariants` is set, validates the graph after
+     * every pass as in
+     * [`compile_with_invariant_check`](FheCompile::compile_with_invariant_check).
+     */
+    fn compile_timed(&self, options: &CompileOptions) -> Result<(FheProgram, Vec<PassTiming>)>;
+
+    /**
+     * Like [`compile_timed`](FheCompile::compile_timed), but also runs
+     * the given user-defined passes under `params`.
+     */
+    fn compile_with_passes(
+        &self,
+        options: &CompileOptions,
+        user_passes: &[UserPass],
+        params: &Params,
+    ) -> Result<(FheProgram, Vec<PassTiming>)>;
 }
 
 impl FheCompile for FheFrontendCompilation {
     fn compile(&self) -> FheProgram {
-        let mut fhe_program = FheProgram::new(SchemeType::Bfv);
-
-        let mapped_graph = self.0.map(
-            |id, n| match &n.operation {
-                FheOperation::Add => NodeInfo::new(FheProgramOperation::Add),
-                FheOperation::InputCiphertext => {
-                    // HACKHACK: Input nodes are always added first to the graph in the order
-                    // they're specified as function arguments. We should not depend on this.
-                    NodeInfo::new(FheProgramOperation::InputCiphertext(id.index()))
-                }
-                FheOperation::InputPlaintext => {
-                    // HACKHACK: Input nodes are always added first to the graph in the order
-                    // they're specified as function arguments. We should not depend on this.
-                    NodeInfo::new(FheProgramOperation::InputPlaintext(id.index()))
-                }
-                FheOperation::Literal(Literal::U64(x)) => {
-                    NodeInfo::new(FheProgramOperation::Literal(FheProgramLiteral::U64(*x)))
-                }
-                FheOperation::Literal(Literal::Plaintext(x)) => {
-                    // It's okay to unwrap here because fhe_program compilation will
-                    // catch the panic and return a compilation error.
-                    NodeInfo::new(FheProgramOperation::Literal(FheProgramLiteral::Plaintext(
-                        x.to_bytes().expect("Failed to serialize plaintext."),
-                    )))
-                }
-                FheOperation::Sub => NodeInfo::new(FheProgramOperation::Sub),
-                FheOperation::SubPlaintext => NodeInfo::new(FheProgramOperation::SubPlaintext),
-                FheOperation::Negate => NodeInfo::new(FheProgramOperation::Negate),
-                FheOperation::Multiply => NodeInfo::new(FheProgramOperation::Multiply),
-                FheOperation::MultiplyPlaintext => {
-                    NodeInfo::new(FheProgramOperation::MultiplyPlaintext)
-                }
-                FheOperation::Output => NodeInfo::new(FheProgramOperation::OutputCiphertext),
-                FheOperation::RotateLeft => NodeInfo::new(FheProgramOperation::ShiftLeft),
-                FheOperation::RotateRight => NodeInfo::new(FheProgramOperation::ShiftRight),
-                FheOperation::SwapRows => NodeInfo::new(FheProgramOperation::SwapRows),
-                FheOperation::AddPlaintext => NodeInfo::new(FheProgramOperation::AddPlaintext),
-            },
-            |_, e| match e {
-                EdgeInfo::Left => EdgeInfo::Left,
-                EdgeInfo::Right => EdgeInfo::Right,
-                EdgeInfo::Unary => EdgeInfo::Unary,
-                EdgeInfo::Unordered => unreachable!("FHE programs have no unordered edges."),
-                EdgeInfo::Ordered(_) => unreachable!("FHE programs have no ordered edges."),
-            },
-        );
-
-        fhe_program.graph = CompilationResult(mapped_graph);
-
-        compile_inplace(fhe_program)
+        compile_inplace(to_fhe_program(self))
     }
+
+    fn compile_with_invariant_check(&self) -> Result<FheProgram> {
+        compile_inplace_with_invariant_check(to_fhe_program(self)).map_err(map_backend_error)
+    }
+
+    fn compile_timed(&self, options: &CompileOptions) -> Result<(FheProgram, Vec<PassTiming>)> {
+        compile_inplace_timed(to_fhe_program(self), options).map_err(map_backend_error)
+    }
+
+    fn compile_with_passes(
+        &self,
+        options: &CompileOptions,
+        user_passes: &[UserPass],
+        params: &Params,
+    ) -> Result<(FheProgram, Vec<PassTiming>)> {
+        let mut ir = to_fhe_program(self);
+        ir.data = params.scheme_type;
+
+        compile_inplace_with_passes(ir, options, user_passes, params).map_err(map_backend_error)
+    }
+}
+
+fn map_backend_error(e: sunscreen_backend::Error) -> Error {
+    match e {
+        sunscreen_backend::Error::GraphInvariantViolation(pass, err) => {
+            Error::graph_invariant_violation(&pass, err)
+        }
+        sunscreen_backend::Error::UnknownPass(pass) => Error::unknown_pass(&pass),
+        sunscreen_backend::Error::PassFailed(pass, err) => Error::compiler_pass_failed(&pass, err),
+        sunscreen_backend::Error::IRError(err) => Error::FheProgramError(err),
+        sunscreen_backend::Error::InvalidParams => {
+            Error::invalid_params("The backend rejected the parameters")
+        }
+        sunscreen_backend::Error::SealError(err) => Error::SealError(err),
+        sunscreen_backend::Error::KeygenFailure => {
+            Error::invalid_params("Failed to generate keys under the parameters")
+        }
+        sunscreen_backend::Error::RuntimeError(err) => Error::RuntimeError(err.into()),
+        sunscreen_backend::Error::ImpossibleNoiseFloor => Error::NoParams,
+        sunscreen_backend::Error::NotApplicable => {
+            Error::unsupported("The noise model doesn't apply to this ciphertext")
+        }
+    }
+}
+
+/**
+ * Lowers the frontend graph into a backend [`FheProgram`] without
+ * running any backend passes.
+ */
+pub(crate) fn to_fhe_program(frontend: &FheFrontendCompilation) -> FheProgram {
+    let mut fhe_program = FheProgram::new(SchemeType::Bfv);
+
+    let mapped_graph = frontend.0.map(
+        |id, n| match &n.operation {
+            FheOperation::Add => NodeInfo::new(FheProgramOperation::Add),
+            FheOperation::InputCiphertext => {
+                // HACKHACK: Input nodes are always added first to the graph in the order
+                // they're specified as function arguments. We should not depend on this.
+                NodeInfo::new(FheProgramOperation::InputCiphertext(id.index()))
+            }
+            FheOperation::InputPlaintext => {
+                // HACKHACK: Input nodes are always added first to the graph in the order
+                // they're specified as function arguments. We should not depend on this.
+                NodeInfo::new(FheProgramOperation::InputPlaintext(id.index()))
+            }
+            FheOperation::Literal(Literal::U64(x)) => {
+                NodeInfo::new(FheProgramOperation::Literal(FheProgramLiteral::U64(*x)))
+            }
+            FheOperation::Literal(Literal::Plaintext(x)) => {
+                // It's okay to unwrap here because fhe_program compilation will
+                // catch the panic and return a compilation error.
+                NodeInfo::new(FheProgramOperation::Literal(FheProgramLiteral::Plaintext(
+                    x.to_bytes().expect("Failed to serialize plaintext."),
+                )))
+            }
+            FheOperation::Sub => NodeInfo::new(FheProgramOperation::Sub),
+            FheOperation::SubPlaintext => NodeInfo::new(FheProgramOperation::SubPlaintext),
+            FheOperation::Negate => NodeInfo::new(FheProgramOperation::Negate),
+            FheOperation::Multiply => NodeInfo::new(FheProgramOperation::Multiply),
+            FheOperation::MultiplyPlaintext => {
+                NodeInfo::new(FheProgramOperation::MultiplyPlaintext)
+            }
+            FheOperation::Output => NodeInfo::new(FheProgramOperation::OutputCiphertext),
+            FheOperation::RotateLeft => NodeInfo::new(FheProgramOperation::ShiftLeft),
+            FheOperation::RotateRight => NodeInfo::new(FheProgramOperation::ShiftRight),
+            FheOperation::SwapRows => NodeInfo::new(FheProgramOperation::SwapRows),
+            FheOperation::AddPlaintext => NodeInfo::new(FheProgramOperation::AddPlaintext),
+        },
+        |_, e| match e {
+            EdgeInfo::Left => EdgeInfo::Left,
+            EdgeInfo::Right => EdgeInfo::Right,
+            EdgeInfo::Unary => EdgeInfo::Unary,
+            EdgeInfo::Unordered => unreachable!("FHE programs have no unordered edges."),
+            EdgeInfo::Ordered(_) => unreachable!("FHE programs have no ordered edges."),
+        },
+    );
+
+    fhe_program.graph = CompilationResult(mapped_graph);
+
+    debug!(
+        "Pass lowering: {} -> {} nodes",
+        frontend.0.node_count(),
+        fhe_program.graph.node_count()
+    );
+
+    fhe_program
 }
@@ -36,6 +36,7 @@
 //! ```
 //!
 
+mod compile_cache;
 mod compiler;
 mod error;
 mod params;
@@ -54,24 +55,34 @@ pub mod zkp;
 use fhe::{FheOperation, Literal};
 use petgraph::stable_graph::StableGraph;
 use serde::{Deserialize, Serialize};
+use sunscreen_fhe_program::{FheProgramTrait, FORMAT_VERSION};
 use sunscreen_runtime::{marker, Fhe, FheZkp, Zkp};
 use sunscreen_zkp_backend::CompiledZkpProgram;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fs;
 use std::marker::PhantomData;
+use std::path::Path;
 
-pub use compiler::{Compiler, FheProgramFn, FheProgramFnExt, GenericCompiler};
+pub use compiler::{
+    CompileTimings, Compiler, FheProgramFn, FheProgramFnExt, GenericCompiler, ValidationReport,
+};
 pub use error::{Error, Result};
-pub use params::PlainModulusConstraint;
+pub use fhe::EncodeCache;
+pub use params::{ParamsBuilder, PlainModulusConstraint};
 pub use seal_fhe::Plaintext as SealPlaintext;
+pub use sunscreen_backend::overflow::{CoefficientBound, OverflowWarning};
+pub use sunscreen_compiler_common::transforms::{CompilerPass, CompilerPassError};
 pub use sunscreen_compiler_macros::*;
-pub use sunscreen_fhe_program::{SchemeType, SecurityLevel};
+pub use sunscreen_fhe_program::{OperationStats, SchemeType, SecurityLevel};
 pub use sunscreen_runtime::{
-    CallSignature, Ciphertext, CompiledFheProgram, Error as RuntimeError, FheProgramInput,
-    FheProgramInputTrait, FheProgramMetadata, FheRuntime, FheZkpRuntime, InnerCiphertext,
-    InnerPlaintext, Params, Plaintext, PrivateKey, ProofBuilder, PublicKey, RequiredKeys, Runtime,
-    VerificationBuilder, WithContext, ZkpProgramInput, ZkpRuntime,
+    CallSignature, Ciphertext, CompiledFheProgram, DecryptionStats, Error as RuntimeError,
+    ExecutionPlan, FheProgramInput, FheProgramInputTrait, FheProgramMetadata, FheRuntime,
+    FheZkpRuntime, InnerCiphertext, InnerPlaintext, KeyCache, NoiseBudgetEstimate, NoiseReport,
+    OperationProfile, Params, Plaintext, PrivateKey, ProfileReport, ProgramInputInfo,
+    ProgramOutputInfo, ProofBuilder, PublicKey, RequiredKeys, Runtime, VerificationBuilder,
+    WithContext, ZkpProgramInput, ZkpRuntime,
 };
 #[cfg(feature = "bulletproofs")]
 pub use sunscreen_zkp_backend::bulletproofs;
@@ -115,6 +126,49 @@ impl<T> Application<T> {
             _phantom: PhantomData,
         })
     }
+
+    /**
+     * Combines the programs of this and `other` into one
+     * [`Application`], e.g. to run programs compiled in separate
+     * modules with a single runtime.
+     *
+     * # Remarks
+     * Returns [`Error::NameCollision`] if both contain an FHE or ZKP
+     * program with the same name and [`Error::ParamsMismatch`] if their
+     * FHE programs were compiled under different parameters. Compile
+     * each with the same [`with_params`](GenericCompiler::with_params)
+     * to ensure they match.
+     */
+    pub fn merge(mut self, other: Self) -> Result<Self> {
+        let collides = other
+            .fhe_programs
+            .keys()
+            .any(|k| self.fhe_programs.contains_key(k))
+            || other
+                .zkp_programs
+                .keys()
+                .any(|k| self.zkp_programs.contains_key(k));
+
+        if collides {
+            return Err(Error::NameCollision);
+        }
+
+        let params = self
+            .fhe_programs
+            .values()
+            .chain(other.fhe_programs.values())
+            .map(|p| &p.metadata.params)
+            .collect::<Vec<_>>();
+
+        if params.windows(2).any(|w| w[0] != w[1]) {
+            return Err(Error::ParamsMismatch);
+        }
+
+        self.fhe_programs.extend(other.fhe_programs);
+        self.zkp_programs.extend(other.zkp_programs);
+
+        Ok(self)
+    }
 }
 
 impl<T> Application<T>
@@ -145,6 +199,11 @@ where
 
     /**
      * Gets the [`CompiledFheProgram`] with the given name or [`None`] if not present.
+     *
+     * # Remarks
+     * `name` can be the `#[fhe_program]` function itself or its name as
+     * a string, e.g. when looking up programs in a
+     * [`load`](Application::load)ed application.
      */
     pub fn get_fhe_program<N>(&self, name: N) -> Option<&CompiledFheProgram>
     where
@@ -171,6 +230,106 @@ where
         self.fhe_programs.iter()
     }
 
+    /**
+     * Returns the call signature of the FHE program with the given name
+     * or [`None`] if not present.
+     *
+     * # Remarks
+     * See [`CompiledFheProgram::inputs`] and
+     * [`CompiledFheProgram::outputs`] for argument names and a
+     * per-value breakdown.
+     */
+    pub fn get_signature<N>(&self, name: N) -> Option<&CallSignature>
+    where
+        N: AsRef<str>,
+    {
+        self.get_fhe_program(name).map(|p| &p.metadata.signature)
+    }
+
+    /**
+     * Returns the multiplicative depth of the FHE program with the given
+     * name or [`None`] if not present.
+     *
+     * # Remarks
+     * See [`FheProgramTrait::multiplicative_depth`](sunscreen_fhe_program::FheProgramTrait::multiplicative_depth).
+     */
+    pub fn multiplicative_depth<N>(&self, name: N) -> Option<usize>
+    where
+        N: AsRef<str>,
+    {
+        self.get_fhe_program(name)
+            .map(|p| p.fhe_program_fn.multiplicative_depth())
+    }
+
+    /**
+     * Returns the nodes of the FHE program with the given name whose
+     * results may overflow the plaintext modulus, or [`None`] if the
+     * program isn't present.
+     *
+     * # Remarks
+     * This is a conservative static analysis and only covers programs
+     * over [`Signed`](types::bfv::Signed) and
+     * [`Unsigned`](types::bfv::Unsigned) values. The compiler also logs
+     * these warnings. Choose a larger plaintext modulus via
+     * [`plain_modulus_constraint`](GenericCompiler::plain_modulus_constraint)
+     * to resolve them.
+     */
+    pub fn overflow_warnings<N>(&self, name: N) -> Option<Vec<OverflowWarning>>
+    where
+        N: AsRef<str>,
+    {
+        self.get_fhe_program(name).map(compiler::overflow_warnings)
+    }
+
+    /**
+     * Writes this application's FHE programs to the file at `path`, so
+     * [`load`](Self::load) can later restore them without recompiling.
+     *
+     * # Remarks
+     * The file is tagged with a format version, so files written by an
+     * incompatible version of Sunscreen fail to load with
+     * [`Error::UnsupportedFormatVersion`] rather than misbehaving.
+     *
+     * ZKP programs can't be saved, so this returns
+     * [`Error::Unsupported`] if this application contains any.
+     */
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if !self.zkp_programs.is_empty() {
+            return Err(Error::unsupported(
+                "Saving applications with ZKP programs isn't supported",
+            ));
+        }
+
+        let bytes = bincode::serialize(&ApplicationFile {
+            version: FORMAT_VERSION,
+            fhe_programs: self.fhe_programs.clone(),
+        })
+        .map_err(|e| Error::serialization_error(&e.to_string()))?;
+
+        fs::write(path, bytes).map_err(|e| Error::io_error(&e.to_string()))
+    }
+
+    /**
+     * Reads an application written by [`save`](Self::save) from the file
+     * at `path`.
+     */
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).map_err(|e| Error::io_error(&e.to_string()))?;
+
+        // bincode encodes fields in order, so the version comes first.
+        let version: u32 =
+            bincode::deserialize(&bytes).map_err(|e| Error::serialization_error(&e.to_string()))?;
+
+        if version != FORMAT_VERSION {
+            return Err(Error::UnsupportedFormatVersion(version));
+        }
+
+        let file: ApplicationFile =
+            bincode::deserialize(&bytes).map_err(|e| Error::serialization_error(&e.to_string()))?;
+
+        Self::new(file.fhe_programs, HashMap::new())
+    }
+
     /// Take ownership of a compiled program with the given name, removing it from this
     /// `Application`.
     ///
@@ -219,6 +378,19 @@ where
     }
 }
 
+#[derive(Serialize, Deserialize)]
+/**
+ * The contents of a file written by [`Application::save`].
+ *
+ * # Remarks
+ * This serializes each [`FheProgram`](sunscreen_fhe_program::FheProgram)
+ * directly, so it shares the FHE program serialization's version.
+ */
+struct ApplicationFile {
+    version: u32,
+    fhe_programs: HashMap<String, CompiledFheProgram>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 /**
  * Information about an edge in the frontend IR.
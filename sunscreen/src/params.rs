@@ -35,6 +35,17 @@ pub enum PlainModulusConstraint {
 }
 
 const LATTICE_DIMENSIONS: &[u64] = &[1024, 2048, 4096, 8192, 16384, 32768];
+
+/**
+ * The compiler's default plaintext modulus. Sufficient for doing 3
+ * levels of 64-bit multiplications.
+ */
+pub(crate) const DEFAULT_PLAIN_MODULUS: u64 = 262_144;
+
+/**
+ * The compiler's default noise margin, in bits.
+ */
+pub(crate) const DEFAULT_NOISE_MARGIN: u32 = 20;
 const BATCHING_MIN_BITS: &[u32] = &[14, 14, 16, 17, 17, 17];
 
 /**
@@ -112,6 +123,40 @@ fn can_make_required_keys(fhe_program: &FheProgram, params: &Params) -> Result<b
     Ok(create_galois && create_relin)
 }
 
+/**
+ * Returns [`Error::InvalidParams`] if `params`' coefficient modulus is
+ * too large for its lattice dimension to meet its security level.
+ */
+pub(crate) fn check_security_level(params: &Params) -> Result<()> {
+    if !LATTICE_DIMENSIONS.contains(&params.lattice_dimension) {
+        return Err(Error::invalid_params(&format!(
+            "Lattice dimension {} is not one of {:?}",
+            params.lattice_dimension, LATTICE_DIMENSIONS
+        )));
+    }
+
+    // The bit length of the product of the moduli.
+    let bits = params
+        .coeff_modulus
+        .iter()
+        .map(|q| (*q as f64).log2())
+        .sum::<f64>()
+        .floor() as u32
+        + 1;
+
+    let max_bits =
+        CoefficientModulus::max_bit_count(params.lattice_dimension, params.security_level);
+
+    if bits > max_bits {
+        return Err(Error::invalid_params(&format!(
+            "Coefficient modulus has {} bits, but {:?} allows at most {} bits for lattice dimension {}",
+            bits, params.security_level, max_bits, params.lattice_dimension
+        )));
+    }
+
+    Ok(())
+}
+
 /**
  * Determines the minimal parameters required to satisfy the noise constraint for
  * the given FHE program and plaintext modulo and security level.
@@ -134,7 +179,12 @@ pub fn determine_params(
         };
 
         // Tell SEAL to give us whatever modulus chain it finds suitable.
-        let coeff = CoefficientModulus::bfv_default(*n, security_level).unwrap();
+        let coeff = match CoefficientModulus::bfv_default(*n, security_level) {
+            Ok(v) => v,
+            Err(_) => {
+                continue 'params_loop;
+            }
+        };
 
         // Compile the given fhe_program.
         let params = Params {
@@ -234,3 +284,315 @@ pub fn determine_params(
 
     Err(Error::NoParams)
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/**
+ * Builds a [`Params`] and checks it meets the claimed security level.
+ *
+ * # Remarks
+ * Constructing [`Params`] directly performs no validation, so an
+ * oversized coefficient modulus silently weakens security.
+ * [`build`](Self::build) instead rejects parameter sets exceeding the
+ * [HomomorphicEncryption.org standard](https://homomorphicencryption.org/standard/)
+ * bound for the lattice dimension and security level.
+ *
+ * ```
+ * # use sunscreen::{ParamsBuilder, SecurityLevel};
+ * let params = ParamsBuilder::new()
+ *     .lattice_dimension(4096)
+ *     .plain_modulus(1024)
+ *     .security_level(SecurityLevel::TC128)
+ *     .build()
+ *     .unwrap();
+ * ```
+ */
+pub struct ParamsBuilder {
+    lattice_dimension: Option<u64>,
+    coeff_moduli: Option<Vec<u64>>,
+    plain_modulus: Option<u64>,
+    security_level: SecurityLevel,
+}
+
+impl Default for ParamsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParamsBuilder {
+    /**
+     * Creates a builder targeting [`SecurityLevel::TC128`] with no
+     * other fields set.
+     */
+    pub fn new() -> Self {
+        Self {
+            lattice_dimension: None,
+            coeff_moduli: None,
+            plain_modulus: None,
+            security_level: SecurityLevel::TC128,
+        }
+    }
+
+    /**
+     * Sets the lattice dimension. Required.
+     */
+    pub fn lattice_dimension(mut self, n: u64) -> Self {
+        self.lattice_dimension = Some(n);
+        self
+    }
+
+    /**
+     * Sets the coefficient modulus chain. If unset, uses SEAL's
+     * default chain for the lattice dimension and security level.
+     */
+    pub fn coeff_moduli(mut self, v: Vec<u64>) -> Self {
+        self.coeff_moduli = Some(v);
+        self
+    }
+
+    /**
+     * Sets the plaintext modulus. Required.
+     */
+    pub fn plain_modulus(mut self, t: u64) -> Self {
+        self.plain_modulus = Some(t);
+        self
+    }
+
+    /**
+     * Sets the security level the parameters must meet.
+     */
+    pub fn security_level(mut self, l: SecurityLevel) -> Self {
+        self.security_level = l;
+        self
+    }
+
+    /**
+     * Validates and returns the parameters.
+     *
+     * # Remarks
+     * Returns [`Error::InvalidParams`] if a required field is missing,
+     * the lattice dimension isn't supported, or the coefficient modulus
+     * is too large to meet the security level.
+     */
+    pub fn build(self) -> Result<Params> {
+        let lattice_dimension = self
+            .lattice_dimension
+            .ok_or_else(|| Error::invalid_params("Lattice dimension is required"))?;
+
+        if !LATTICE_DIMENSIONS.contains(&lattice_dimension) {
+            return Err(Error::invalid_params(&format!(
+                "Lattice dimension {} is not one of {:?}",
+                lattice_dimension, LATTICE_DIMENSIONS
+            )));
+        }
+
+        let plain_modulus = self
+            .plain_modulus
+            .ok_or_else(|| Error::invalid_params("Plaintext modulus is required"))?;
+
+        if plain_modulus < 2 {
+            return Err(Error::invalid_params(
+                "Plaintext modulus must be at least 2",
+            ));
+        }
+
+        let coeff_modulus = match self.coeff_moduli {
+            Some(v) => v,
+            None => CoefficientModulus::bfv_default(lattice_dimension, self.security_level)?
+                .iter()
+                .map(|m| m.value())
+                .collect(),
+        };
+
+        if coeff_modulus.is_empty() {
+            return Err(Error::invalid_params(
+                "Coefficient modulus chain must not be empty",
+            ));
+        }
+
+        let params = Params {
+            lattice_dimension,
+            coeff_modulus,
+            plain_modulus,
+            scheme_type: SchemeType::Bfv,
+            security_level: self.security_level,
+        };
+
+        check_security_level(&params)?;
+
+        Ok(params)
+    }
+
+    /**
+     * Chooses the smallest BFV parameters that can evaluate `depth`
+     * sequential ciphertext multiplications at the given security
+     * level.
+     *
+     * # Remarks
+     * Uses the compiler's default plaintext modulus and noise margin
+     * and measures the noise of a chain of `depth` squarings under each
+     * candidate lattice dimension. Returns [`Error::NoParams`] if no
+     * supported lattice dimension suffices.
+     */
+    pub fn for_depth(depth: usize, security: SecurityLevel) -> Result<Params> {
+        params_for_depth(
+            depth,
+            PlainModulusConstraint::Raw(DEFAULT_PLAIN_MODULUS),
+            security,
+            DEFAULT_NOISE_MARGIN,
+            &[],
+        )
+    }
+}
+
+/**
+ * Parameters generous enough to build any FHE program, used to measure
+ * a program's structure before choosing its real parameters.
+ */
+pub(crate) fn provisional_params(
+    plaintext_constraint: PlainModulusConstraint,
+    security_level: SecurityLevel,
+) -> Result<Params> {
+    let i = LATTICE_DIMENSIONS.len() - 1;
+
+    ParamsBuilder::new()
+        .lattice_dimension(LATTICE_DIMENSIONS[i])
+        .plain_modulus(plaintext_constraint_to_modulus(plaintext_constraint, i)?.value())
+        .security_level(security_level)
+        .build()
+}
+
+/**
+ * Finds the smallest parameters whose noise budget survives a chain of
+ * `depth` ciphertext multiplications with `noise_margin_bits` to spare
+ * and that can create the keys each of `fhe_programs` requires.
+ */
+pub(crate) fn params_for_depth(
+    depth: usize,
+    plaintext_constraint: PlainModulusConstraint,
+    security_level: SecurityLevel,
+    noise_margin_bits: u32,
+    fhe_programs: &[FheProgram],
+) -> Result<Params> {
+    let mut ir = FheProgram::new(SchemeType::Bfv);
+
+    let mut x = ir.add_input_ciphertext(0);
+
+    for _ in 0..depth {
+        x = ir.add_multiply(x, x);
+    }
+
+    ir.add_output_ciphertext(x);
+
+    let ir = sunscreen_backend::compile(&ir);
+
+    let target_noise = noise_budget_to_noise(noise_margin_bits as f64);
+
+    'params_loop: for (i, n) in LATTICE_DIMENSIONS.iter().enumerate() {
+        let plaintext_modulus = match plaintext_constraint_to_modulus(plaintext_constraint, i) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        let params = ParamsBuilder::new()
+            .lattice_dimension(*n)
+            .plain_modulus(plaintext_modulus.value())
+            .security_level(security_level)
+            .build()?;
+
+        for program in fhe_programs.iter().chain(std::iter::once(&ir)) {
+            if !can_make_required_keys(program, &params).unwrap_or(false) {
+                continue 'params_loop;
+            }
+        }
+
+        let model = match MeasuredModel::new(&ir, &params, &[TargetNoiseLevel::Fresh]) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        if predict_noise(&model, &ir)
+            .iter()
+            .all(|noise| *noise <= target_noise)
+        {
+            debug!(
+                "Using params lattice_dimension={} for multiplicative depth {}",
+                n, depth
+            );
+
+            return Ok(params);
+        }
+    }
+
+    Err(Error::NoParams)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_uses_default_coeff_modulus() {
+        let params = ParamsBuilder::new()
+            .lattice_dimension(4096)
+            .plain_modulus(1024)
+            .build()
+            .unwrap();
+
+        let expected = CoefficientModulus::bfv_default(4096, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|m| m.value())
+            .collect::<Vec<_>>();
+
+        assert_eq!(params.coeff_modulus, expected);
+        assert_eq!(params.security_level, SecurityLevel::TC128);
+    }
+
+    #[test]
+    fn builder_rejects_insecure_coeff_modulus() {
+        // The TC128 default chain for 8192 exceeds the TC256 bound.
+        let coeff = CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|m| m.value())
+            .collect::<Vec<_>>();
+
+        let result = ParamsBuilder::new()
+            .lattice_dimension(8192)
+            .coeff_moduli(coeff)
+            .plain_modulus(1024)
+            .security_level(SecurityLevel::TC256)
+            .build();
+
+        assert!(matches!(result, Err(Error::InvalidParams(_))));
+    }
+
+    #[test]
+    fn builder_rejects_missing_and_unsupported_fields() {
+        assert!(matches!(
+            ParamsBuilder::new().plain_modulus(1024).build(),
+            Err(Error::InvalidParams(_))
+        ));
+        assert!(matches!(
+            ParamsBuilder::new().lattice_dimension(4096).build(),
+            Err(Error::InvalidParams(_))
+        ));
+        assert!(matches!(
+            ParamsBuilder::new()
+                .lattice_dimension(3000)
+                .plain_modulus(1024)
+                .build(),
+            Err(Error::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn for_depth_grows_with_depth() {
+        let shallow = ParamsBuilder::for_depth(0, SecurityLevel::TC128).unwrap();
+        let deep = ParamsBuilder::for_depth(2, SecurityLevel::TC128).unwrap();
+
+        assert!(shallow.lattice_dimension <= deep.lattice_dimension);
+        assert!(deep.lattice_dimension > 1024);
+    }
+}
@@ -1,12 +1,12 @@
 use crate::{
-    fhe::{with_fhe_ctx, FheContextOps, Literal},
+    fhe::{set_build_error, with_fhe_ctx, FheContextOps, Literal},
     types::{
         intern::{Cipher, FheProgramNode},
         ops::*,
-        BfvType, FheType, LaneCount, NumCiphertexts, SwapRows, TryFromPlaintext, TryIntoPlaintext,
-        Type, TypeName, TypeNameInstance, Version,
+        BfvType, FheType, LaneCount, NumCiphertexts, Rotate, SwapRows, TryFromPlaintext,
+        TryIntoPlaintext, Type, TypeName, TypeNameInstance, Version,
     },
-    FheProgramInputTrait, InnerPlaintext, Params, Plaintext, WithContext,
+    Error, FheProgramInputTrait, InnerPlaintext, Params, Plaintext, WithContext,
 };
 use seal_fhe::{
     BFVEncoder, BfvEncryptionParametersBuilder, Context as SealContext, Modulus,
@@ -200,6 +200,8 @@ impl<const LANES: usize> TryFromPlaintext for Batched<LANES> {
         plaintext: &Plaintext,
         params: &Params,
     ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        plaintext.inner.check_coefficient_count(params)?;
+
         let plaintext = plaintext.inner_as_seal_plaintext()?;
 
         if plaintext.len() != 1 {
@@ -492,6 +494,16 @@ impl<const LANES: usize> SwapRows for Batched<LANES> {
     }
 }
 
+impl<const LANES: usize> Rotate for Batched<LANES> {
+    type Output = Self;
+
+    fn rotate(self, n: i32) -> Self::Output {
+        let n = n.rem_euclid(LANES as i32) as u64;
+
+        self << n
+    }
+}
+
 impl<const LANES: usize> Index<(usize, usize)> for Batched<LANES> {
     type Output = i64;
 
@@ -585,6 +597,90 @@ impl<const LANES: usize> GraphCipherConstMul for Batched<LANES> {
     }
 }
 
+impl<const LANES: usize> GraphCipherConstVecMul for Batched<LANES> {
+    /**
+     * `b` holds either `LANES` scalars, applied to both rows, or
+     * `2 * LANES` scalars covering the first row and then the second.
+     *
+     * # Remarks
+     * Fails compilation if `b` has any other length, or if every scalar
+     * is a multiple of the plain modulus, as SEAL can't represent the
+     * resulting transparent ciphertext.
+     */
+    fn graph_cipher_const_vec_mul(
+        a: FheProgramNode<Cipher<Self>>,
+        b: &[i64],
+    ) -> FheProgramNode<Cipher<Self>> {
+        let rows = if b.len() == LANES {
+            [b.to_vec(), b.to_vec()]
+        } else if b.len() == 2 * LANES {
+            [b[..LANES].to_vec(), b[LANES..].to_vec()]
+        } else {
+            set_build_error(Error::unsupported(&format!(
+                "Expected {} or {} scalars, got {}",
+                LANES,
+                2 * LANES,
+                b.len()
+            )));
+
+            return a;
+        };
+
+        let plain_modulus = FheProgramNode::<Cipher<Self>>::get_plain_modulus() as i64;
+
+        if b.iter().all(|x| x.rem_euclid(plain_modulus) == 0) {
+            set_build_error(Error::unsupported("Multiplying every slot by zero"));
+
+            return a;
+        }
+
+        let scalars = Self::try_from(rows).unwrap();
+
+        with_fhe_ctx(|ctx| {
+            let scalars = scalars.try_into_plaintext(&ctx.data).unwrap();
+            let l = ctx.add_plaintext_literal(scalars.inner);
+            let n = ctx.add_multiplication_plaintext(a.ids[0], l);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const LANES: usize> GraphCipherMask for Batched<LANES> {
+    /**
+     * Selects the lanes to keep. The same pattern applies to both rows.
+     */
+    type Pattern = [bool; LANES];
+
+    /**
+     * Fails compilation if `pattern` selects no lanes, as SEAL can't
+     * represent the resulting transparent ciphertext.
+     */
+    fn graph_cipher_mask(
+        a: FheProgramNode<Cipher<Self>>,
+        pattern: &Self::Pattern,
+    ) -> FheProgramNode<Cipher<Self>> {
+        if !pattern.contains(&true) {
+            set_build_error(Error::unsupported(
+                "Masking with a pattern that selects no lanes",
+            ));
+
+            return a;
+        }
+
+        let row = pattern.map(|keep| keep as i64);
+        let selector = Self { data: [row, row] };
+
+        with_fhe_ctx(|ctx| {
+            let selector = selector.try_into_plaintext(&ctx.data).unwrap();
+            let l = ctx.add_plaintext_literal(selector.inner);
+            let n = ctx.add_multiplication_plaintext(a.ids[0], l);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
 impl<const LANES: usize> GraphCipherSwapRows for Batched<LANES> {
     fn graph_cipher_swap_rows(x: FheProgramNode<Cipher<Self>>) -> FheProgramNode<Cipher<Self>> {
         with_fhe_ctx(|ctx| {
@@ -623,6 +719,119 @@ impl<const LANES: usize> GraphCipherRotateRight for Batched<LANES> {
     }
 }
 
+/**
+ * Deterministic Miller-Rabin primality test. These bases suffice for
+ * every 64-bit integer.
+ */
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+
+    for p in BASES {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+
+    let mul_mod = |a: u64, b: u64| ((a as u128 * b as u128) % n as u128) as u64;
+
+    let pow_mod = |mut base: u64, mut exp: u64| {
+        let mut result = 1;
+
+        while exp > 0 {
+            if exp & 0x1 == 1 {
+                result = mul_mod(result, base);
+            }
+
+            base = mul_mod(base, base);
+            exp >>= 1;
+        }
+
+        result
+    };
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    BASES.iter().all(|a| {
+        let mut x = pow_mod(*a, d);
+
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+
+        for _ in 1..s {
+            x = mul_mod(x, x);
+
+            if x == n - 1 {
+                return true;
+            }
+        }
+
+        false
+    })
+}
+
+impl<const LANES: usize> GraphCipherEq for Batched<LANES> {
+    /**
+     * By Fermat's little theorem, `(a - b)^(p - 1)` is 0 in lanes where
+     * `a == b` and 1 elsewhere, so `1 - (a - b)^(p - 1)` is the
+     * equality mask. The power is expanded via repeated squaring, so
+     * this costs roughly `2 log2(p)` multiplications of depth
+     * `log2(p)`.
+     *
+     * Fails compilation unless the plain modulus is prime.
+     */
+    fn graph_cipher_eq(
+        a: FheProgramNode<Cipher<Self>>,
+        b: FheProgramNode<Cipher<Self>>,
+    ) -> FheProgramNode<Cipher<Self>> {
+        let plain_modulus = FheProgramNode::<Cipher<Self>>::get_plain_modulus();
+        let diff = a - b;
+
+        if !is_prime(plain_modulus) {
+            set_build_error(Error::unsupported(
+                "Equality comparison requires a prime plain modulus",
+            ));
+
+            return diff;
+        }
+
+        let mut exp = plain_modulus - 1;
+        let mut base = diff;
+        let mut pow: Option<FheProgramNode<Cipher<Self>>> = None;
+
+        while exp > 0 {
+            if exp & 0x1 == 1 {
+                pow = Some(match pow {
+                    Some(pow) => pow * base,
+                    None => base,
+                });
+            }
+
+            exp >>= 1;
+
+            if exp > 0 {
+                base = base * base;
+            }
+        }
+
+        // p >= 2, so the exponent was nonzero.
+        let pow = pow.unwrap();
+        let one = Self::graph_cipher_insert(1);
+
+        with_fhe_ctx(|ctx| {
+            let pow_minus_one = ctx.add_subtraction_plaintext(pow.ids[0], one.ids[0]);
+            let n = ctx.add_negate(pow_minus_one);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
 impl<const LANES: usize> GraphCipherNeg for Batched<LANES> {
     type Val = Self;
 
@@ -725,4 +934,39 @@ mod tests {
 
         assert_eq!(a.swap_rows(), [[5, 6, 7, 8], [1, 2, 3, 4]].into());
     }
+
+    #[test]
+    fn can_test_primality() {
+        let primes = [2, 3, 37, 40961, 65537, 786433, 1_152_921_504_606_584_833];
+        let composites = [
+            0,
+            1,
+            4,
+            64,
+            1024,
+            65535,
+            3215031751,
+            4294967297,
+            18446743979220271189,
+        ];
+
+        for p in primes {
+            assert!(is_prime(p), "{p}");
+        }
+
+        for c in composites {
+            assert!(!is_prime(c), "{c}");
+        }
+    }
+
+    #[test]
+    fn can_rotate_non_fhe() {
+        let a = Batched::<4>::try_from(A_VEC).unwrap();
+
+        assert_eq!(a.rotate(1), a << 1);
+        assert_eq!(a.rotate(-1), a >> 1);
+        assert_eq!(a.rotate(2), [[3, 4, 1, 2], [7, 8, 5, 6]].into());
+        assert_eq!(a.rotate(4), a);
+        assert_eq!(a.rotate(0), a);
+    }
 }
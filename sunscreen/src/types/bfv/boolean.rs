@@ -0,0 +1,245 @@
+use seal_fhe::Plaintext as SealPlaintext;
+
+use crate as sunscreen;
+use crate::{
+    fhe::{with_fhe_ctx, FheContextOps},
+    types::{
+        bfv::Signed,
+        intern::FheProgramNode,
+        ops::{GraphCipherBitAnd, GraphCipherBitOr, GraphCipherBitXor, GraphCipherNot},
+        BfvType, Cipher, FheType, TypeNameInstance,
+    },
+    FheProgramInputTrait, Params, TypeName as DeriveTypeName, WithContext,
+};
+
+use sunscreen_runtime::{
+    Error as RuntimeError, InnerPlaintext, NumCiphertexts, Plaintext, TryFromPlaintext,
+    TryIntoPlaintext,
+};
+
+#[derive(Debug, Clone, Copy, DeriveTypeName, PartialEq, Eq, Default)]
+/**
+ * A single encrypted bit.
+ *
+ * # Remarks
+ * This type wraps a [`Signed`] whose value is 0 (`false`) or 1
+ * (`true`), encoded as the plaintext's constant coefficient. The
+ * logical operators map onto arithmetic that preserves this range:
+ * `a & b` is `a * b`, `a | b` is `a + b - a * b`, `a ^ b` is
+ * `a + b - 2 * a * b`, and `!a` is `1 - a`. Each of `&`, `|`, and `^`
+ * costs a ciphertext multiplication.
+ *
+ * Since results never leave `{0, 1}`, a plain modulus of 2 suffices,
+ * which keeps noise growth minimal.
+ */
+pub struct Boolean {
+    val: Signed,
+}
+
+impl NumCiphertexts for Boolean {
+    const NUM_CIPHERTEXTS: usize = 1;
+}
+
+impl FheProgramInputTrait for Boolean {}
+impl FheType for Boolean {}
+impl BfvType for Boolean {}
+
+impl std::fmt::Display for Boolean {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", bool::from(*self))
+    }
+}
+
+impl From<bool> for Boolean {
+    fn from(val: bool) -> Self {
+        Self {
+            val: Signed::from(val as i64),
+        }
+    }
+}
+
+impl From<Boolean> for bool {
+    fn from(x: Boolean) -> Self {
+        i64::from(x.val) != 0
+    }
+}
+
+impl TryIntoPlaintext for Boolean {
+    fn try_into_plaintext(
+        &self,
+        params: &Params,
+    ) -> std::result::Result<Plaintext, sunscreen_runtime::Error> {
+        let mut seal_plaintext = SealPlaintext::new()?;
+
+        seal_plaintext.resize(1);
+        seal_plaintext.set_coefficient(0, i64::from(self.val) as u64);
+
+        Ok(Plaintext {
+            data_type: self.type_name_instance(),
+            inner: InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data: seal_plaintext,
+            }]),
+        })
+    }
+}
+
+impl TryFromPlaintext for Boolean {
+    /**
+     * Decodes the bit from the plaintext's constant coefficient.
+     *
+     * # Remarks
+     * Returns [`ValueOutOfRange`](sunscreen_runtime::Error::ValueOutOfRange)
+     * if the plaintext doesn't encode 0 or 1, e.g. because it came from
+     * arithmetic other than this type's logical operators.
+     */
+    fn try_from_plaintext(
+        plaintext: &Plaintext,
+        params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        plaintext.inner.check_coefficient_count(params)?;
+
+        let p = plaintext.inner_as_seal_plaintext()?;
+
+        if p.len() != 1 {
+            return Err(RuntimeError::IncorrectCiphertextCount);
+        }
+
+        let p = &p[0].data;
+
+        let t = params.plain_modulus;
+
+        let bit = if p.len() > 0 {
+            p.get_coefficient(0) % t
+        } else {
+            0
+        };
+
+        if bit > 1 || (1..p.len()).any(|i| p.get_coefficient(i) % t != 0) {
+            return Err(RuntimeError::value_out_of_range(
+                "Boolean plaintext doesn't encode 0 or 1",
+            ));
+        }
+
+        Ok(Self::from(bit == 1))
+    }
+}
+
+impl GraphCipherBitAnd for Boolean {
+    type Left = Boolean;
+    type Right = Boolean;
+
+    fn graph_cipher_bitand(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_multiplication(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherBitOr for Boolean {
+    type Left = Boolean;
+    type Right = Boolean;
+
+    fn graph_cipher_bitor(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let sum = ctx.add_addition(a.ids[0], b.ids[0]);
+            let product = ctx.add_multiplication(a.ids[0], b.ids[0]);
+            let n = ctx.add_subtraction(sum, product);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherBitXor for Boolean {
+    type Left = Boolean;
+    type Right = Boolean;
+
+    fn graph_cipher_bitxor(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let sum = ctx.add_addition(a.ids[0], b.ids[0]);
+            let product = ctx.add_multiplication(a.ids[0], b.ids[0]);
+            let product_2 = ctx.add_addition(product, product);
+            let n = ctx.add_subtraction(sum, product_2);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherNot for Boolean {
+    type Val = Boolean;
+
+    fn graph_cipher_not(a: FheProgramNode<Cipher<Self>>) -> FheProgramNode<Cipher<Self>> {
+        with_fhe_ctx(|ctx| {
+            // Signed can't encode 1 under the usual plain modulus of 2.
+            let one = Boolean::from(true).try_into_plaintext(&ctx.data).unwrap();
+            let one = ctx.add_plaintext_literal(one.inner);
+
+            let negated = ctx.add_negate(a.ids[0]);
+            let n = ctx.add_addition_plaintext(negated, one);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SchemeType, SecurityLevel};
+
+    fn params() -> Params {
+        Params {
+            lattice_dimension: 4096,
+            plain_modulus: 2,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        }
+    }
+
+    #[test]
+    fn can_encode_decode() {
+        let params = params();
+
+        for x in [false, true] {
+            let pt = Boolean::from(x).try_into_plaintext(&params).unwrap();
+
+            match &pt.inner {
+                InnerPlaintext::Seal(p) => assert_eq!(p[0].data.get_coefficient(0), x as u64),
+            }
+
+            assert_eq!(
+                bool::from(Boolean::try_from_plaintext(&pt, &params).unwrap()),
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_values_other_than_0_or_1() {
+        let params = Params {
+            plain_modulus: 7,
+            ..params()
+        };
+
+        let pt = Signed::from(2).try_into_plaintext(&params).unwrap();
+
+        assert!(matches!(
+            Boolean::try_from_plaintext(&pt, &params),
+            Err(RuntimeError::ValueOutOfRange(_))
+        ));
+    }
+}
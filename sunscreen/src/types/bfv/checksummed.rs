@@ -0,0 +1,291 @@
+use seal_fhe::Plaintext as SealPlaintext;
+
+use crate as sunscreen;
+use crate::{
+    fhe::{with_fhe_ctx, FheContextOps},
+    types::{
+        bfv::Signed,
+        intern::FheProgramNode,
+        ops::{
+            GraphCipherAdd, GraphCipherNeg, GraphCipherPlainAdd, GraphCipherPlainSub,
+            GraphCipherSub,
+        },
+        BfvType, Cipher, FheType, TypeNameInstance,
+    },
+    FheProgramInputTrait, Params, TypeName as DeriveTypeName, WithContext,
+};
+
+use sunscreen_runtime::{
+    Error as RuntimeError, InnerPlaintext, NumCiphertexts, Plaintext, TryFromPlaintext,
+    TryIntoPlaintext,
+};
+
+/**
+ * The coefficient holding the checksum, just past the 64 coefficients
+ * holding the value's bits.
+ */
+const CHECKSUM_COEFFICIENT: usize = 64;
+
+#[derive(Debug, Clone, Copy, DeriveTypeName, PartialEq, Eq, Default)]
+/**
+ * A signed integer encoded like [`Signed`] that can detect when noise
+ * corrupted it.
+ *
+ * # Remarks
+ * In addition to [`Signed`]'s encoding, the coefficient after the
+ * value's 64 bits holds the sum of the value's coefficients modulo the
+ * plaintext modulus. Decoding (e.g. when decrypting) recomputes this
+ * sum and returns [`Error::CorruptedResult`](sunscreen_runtime::Error::CorruptedResult)
+ * if it doesn't match the stored checksum or any higher coefficient
+ * is nonzero. When a ciphertext exhausts its noise budget, its
+ * decryption is effectively random, so this catches all but roughly
+ * `1/plain_modulus` of corrupted results.
+ *
+ * This type only detects corruption; it can't correct it. Further,
+ * only linear operations preserve the checksum, so this type supports
+ * addition, subtraction, and negation, but not multiplication.
+ */
+pub struct ChecksummedSigned {
+    val: i64,
+}
+
+impl NumCiphertexts for ChecksummedSigned {
+    const NUM_CIPHERTEXTS: usize = 1;
+}
+
+impl FheProgramInputTrait for ChecksummedSigned {}
+impl FheType for ChecksummedSigned {}
+impl BfvType for ChecksummedSigned {}
+
+impl std::fmt::Display for ChecksummedSigned {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+impl From<i64> for ChecksummedSigned {
+    fn from(val: i64) -> Self {
+        Self { val }
+    }
+}
+
+impl From<ChecksummedSigned> for i64 {
+    fn from(x: ChecksummedSigned) -> Self {
+        x.val
+    }
+}
+
+/**
+ * Returns coefficient `i` of `p`, treating missing coefficients as 0.
+ */
+fn coefficient(p: &SealPlaintext, i: usize) -> u64 {
+    if i < p.len() {
+        p.get_coefficient(i)
+    } else {
+        0
+    }
+}
+
+/**
+ * Sums the coefficients holding the value's bits modulo `t`.
+ */
+fn checksum(p: &SealPlaintext, t: u64) -> u64 {
+    (0..CHECKSUM_COEFFICIENT).fold(0, |sum, i| {
+        ((sum as u128 + coefficient(p, i) as u128) % t as u128) as u64
+    })
+}
+
+impl TryIntoPlaintext for ChecksummedSigned {
+    fn try_into_plaintext(
+        &self,
+        params: &Params,
+    ) -> std::result::Result<Plaintext, sunscreen_runtime::Error> {
+        let signed = Signed::from(self.val).try_into_plaintext(params)?;
+
+        let mut seal_plaintext = match signed.inner {
+            InnerPlaintext::Seal(mut p) => p.remove(0).data,
+        };
+
+        let sum = checksum(&seal_plaintext, params.plain_modulus);
+
+        seal_plaintext.resize(CHECKSUM_COEFFICIENT + 1);
+        seal_plaintext.set_coefficient(CHECKSUM_COEFFICIENT, sum);
+
+        Ok(Plaintext {
+            data_type: self.type_name_instance(),
+            inner: InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data: seal_plaintext,
+            }]),
+        })
+    }
+}
+
+impl TryFromPlaintext for ChecksummedSigned {
+    fn try_from_plaintext(
+        plaintext: &Plaintext,
+        params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        plaintext.inner.check_coefficient_count(params)?;
+
+        let p = plaintext.inner_as_seal_plaintext()?;
+
+        if p.len() != 1 {
+            return Err(RuntimeError::IncorrectCiphertextCount);
+        }
+
+        let p = &p[0].data;
+
+        let t = params.plain_modulus;
+
+        let is_intact = checksum(p, t) == coefficient(p, CHECKSUM_COEFFICIENT) % t
+            && (CHECKSUM_COEFFICIENT + 1..p.len()).all(|i| p.get_coefficient(i) == 0);
+
+        if !is_intact {
+            return Err(RuntimeError::CorruptedResult);
+        }
+
+        // Signed ignores the coefficients past the value's bits.
+        let val = Signed::try_from_plaintext(plaintext, params)?;
+
+        Ok(Self { val: val.into() })
+    }
+}
+
+impl GraphCipherAdd for ChecksummedSigned {
+    type Left = ChecksummedSigned;
+    type Right = ChecksummedSigned;
+
+    fn graph_cipher_add(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_addition(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherPlainAdd for ChecksummedSigned {
+    type Left = ChecksummedSigned;
+    type Right = ChecksummedSigned;
+
+    fn graph_cipher_plain_add(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Self::Right>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_addition_plaintext(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherSub for ChecksummedSigned {
+    type Left = ChecksummedSigned;
+    type Right = ChecksummedSigned;
+
+    fn graph_cipher_sub(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_subtraction(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherPlainSub for ChecksummedSigned {
+    type Left = ChecksummedSigned;
+    type Right = ChecksummedSigned;
+
+    fn graph_cipher_plain_sub(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Self::Right>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_subtraction_plaintext(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherNeg for ChecksummedSigned {
+    type Val = ChecksummedSigned;
+
+    fn graph_cipher_neg(a: FheProgramNode<Cipher<Self>>) -> FheProgramNode<Cipher<Self>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_negate(a.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SchemeType, SecurityLevel};
+
+    fn params() -> Params {
+        Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        }
+    }
+
+    #[test]
+    fn can_encode_decode() {
+        let params = params();
+
+        for x in [0, 1, -1, 42, -1337, i64::MAX, i64::MIN] {
+            let pt = ChecksummedSigned::from(x)
+                .try_into_plaintext(&params)
+                .unwrap();
+
+            assert_eq!(
+                ChecksummedSigned::try_from_plaintext(&pt, &params).unwrap(),
+                x.into()
+            );
+        }
+    }
+
+    #[test]
+    fn detects_perturbed_coefficients() {
+        let params = params();
+
+        let perturb = |i: usize| {
+            let mut pt = ChecksummedSigned::from(-1337)
+                .try_into_plaintext(&params)
+                .unwrap();
+
+            match &mut pt.inner {
+                InnerPlaintext::Seal(p) => {
+                    let data = &mut p[0].data;
+
+                    if data.len() <= i {
+                        data.resize(i + 1);
+                    }
+
+                    let c = data.get_coefficient(i);
+                    data.set_coefficient(i, (c + 1) % params.plain_modulus);
+                }
+            }
+
+            ChecksummedSigned::try_from_plaintext(&pt, &params)
+        };
+
+        for i in [0, 3, CHECKSUM_COEFFICIENT, CHECKSUM_COEFFICIENT + 1, 100] {
+            assert_eq!(perturb(i), Err(RuntimeError::CorruptedResult), "{i}");
+        }
+    }
+}
@@ -0,0 +1,472 @@
+use seal_fhe::Plaintext as SealPlaintext;
+
+use crate::{
+    fhe::{with_fhe_ctx, FheContextOps},
+    types::{
+        ops::{
+            GraphCipherAdd, GraphCipherConstMul, GraphCipherInsert, GraphCipherMul, GraphCipherNeg,
+            GraphCipherSub,
+        },
+        Cipher,
+    },
+};
+use crate::{
+    types::{intern::FheProgramNode, BfvType, FheType, Type, Version},
+    FheProgramInputTrait, Params, WithContext,
+};
+
+use sunscreen_runtime::{
+    InnerPlaintext, NumCiphertexts, Plaintext, TryFromPlaintext, TryIntoPlaintext, TypeName,
+    TypeNameInstance,
+};
+
+use std::ops::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * A fixed-point value with `INT_BITS` integer bits and `FRAC_BITS`
+ * fractional bits.
+ *
+ * # Remarks
+ * Internally, this type stores the scaled integer `round(x * 2^FRAC_BITS)`.
+ * Conversions from [`f64`] round to the nearest representable value.
+ *
+ * ## Representation
+ * Each binary digit of the scaled integer maps to a single plaintext
+ * coefficient, as with [`Signed`](crate::types::bfv::Signed). Digits with
+ * non-negative powers of two occupy the low order coefficients, while
+ * the digit for `2^-i` occupies coefficient `N-i` with its sign inverted,
+ * where `N` is the `poly_degree`. See
+ * [`Fractional`](crate::types::bfv::Fractional) for a complete treatment
+ * of this encoding.
+ *
+ * Because `x^N = -1` in the plaintext ring, multiplying two encoded values
+ * places the product's fractional digits at the correct powers without
+ * any explicit shift. Decrypting rescales the result by rounding it to
+ * `FRAC_BITS` fractional bits, so `a * b` behaves like fixed-point
+ * multiplication. Multiplying by an [`f64`] constant encodes the
+ * constant the same way, so it needs no explicit shift either.
+ *
+ * ## Limitations
+ * Encoding fails if the integer portion needs more than `INT_BITS` bits
+ * or if `INT_BITS + FRAC_BITS` exceeds the `poly_degree`.
+ *
+ * As with [`Fractional`](crate::types::bfv::Fractional), arithmetic is
+ * carryless and digits must not exceed the `plain_modulus`. Each
+ * multiplication doubles the number of fractional digits in the
+ * ciphertext, so deep multiplication chains must keep
+ * `INT_BITS + depth * FRAC_BITS` within the `poly_degree`.
+ */
+pub struct Fixed<const INT_BITS: usize, const FRAC_BITS: usize> {
+    val: i64,
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> Fixed<INT_BITS, FRAC_BITS> {
+    /**
+     * Returns the scaled integer `x * 2^FRAC_BITS` this value stores.
+     */
+    pub fn to_bits(&self) -> i64 {
+        self.val
+    }
+
+    /**
+     * Creates a [`Fixed`] from the scaled integer `x * 2^FRAC_BITS`.
+     */
+    pub fn from_bits(val: i64) -> Self {
+        Self { val }
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> NumCiphertexts for Fixed<INT_BITS, FRAC_BITS> {
+    const NUM_CIPHERTEXTS: usize = 1;
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> FheProgramInputTrait
+    for Fixed<INT_BITS, FRAC_BITS>
+{
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> Default for Fixed<INT_BITS, FRAC_BITS> {
+    fn default() -> Self {
+        Self::from_bits(0)
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> std::fmt::Display
+    for Fixed<INT_BITS, FRAC_BITS>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", f64::from(*self))
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> TypeName for Fixed<INT_BITS, FRAC_BITS> {
+    fn type_name() -> Type {
+        let version = env!("CARGO_PKG_VERSION");
+
+        Type {
+            name: format!("sunscreen::types::Fixed<{}, {}>", INT_BITS, FRAC_BITS),
+            version: Version::parse(version).expect("Crate version is not a valid semver"),
+            is_encrypted: false,
+        }
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> TypeNameInstance
+    for Fixed<INT_BITS, FRAC_BITS>
+{
+    fn type_name_instance(&self) -> Type {
+        Self::type_name()
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> FheType for Fixed<INT_BITS, FRAC_BITS> {}
+impl<const INT_BITS: usize, const FRAC_BITS: usize> BfvType for Fixed<INT_BITS, FRAC_BITS> {}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> GraphCipherAdd for Fixed<INT_BITS, FRAC_BITS> {
+    type Left = Self;
+    type Right = Self;
+
+    fn graph_cipher_add(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_addition(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> GraphCipherSub for Fixed<INT_BITS, FRAC_BITS> {
+    type Left = Self;
+    type Right = Self;
+
+    fn graph_cipher_sub(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_subtraction(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> GraphCipherMul for Fixed<INT_BITS, FRAC_BITS> {
+    type Left = Self;
+    type Right = Self;
+
+    fn graph_cipher_mul(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_multiplication(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> GraphCipherInsert
+    for Fixed<INT_BITS, FRAC_BITS>
+{
+    type Lit = f64;
+    type Val = Self;
+
+    fn graph_cipher_insert(lit: Self::Lit) -> FheProgramNode<Self::Val> {
+        with_fhe_ctx(|ctx| {
+            let lit = Self::from(lit).try_into_plaintext(&ctx.data).unwrap();
+            let lit = ctx.add_plaintext_literal(lit.inner);
+
+            FheProgramNode::new(&[lit])
+        })
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> GraphCipherConstMul
+    for Fixed<INT_BITS, FRAC_BITS>
+{
+    type Left = Self;
+    type Right = f64;
+
+    fn graph_cipher_const_mul(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: Self::Right,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        let lit = Self::graph_cipher_insert(b);
+
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_multiplication_plaintext(a.ids[0], lit.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> GraphCipherNeg for Fixed<INT_BITS, FRAC_BITS> {
+    type Val = Self;
+
+    fn graph_cipher_neg(a: FheProgramNode<Cipher<Self>>) -> FheProgramNode<Cipher<Self::Val>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_negate(a.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> TryIntoPlaintext
+    for Fixed<INT_BITS, FRAC_BITS>
+{
+    fn try_into_plaintext(
+        &self,
+        params: &Params,
+    ) -> std::result::Result<Plaintext, sunscreen_runtime::Error> {
+        let n = params.lattice_dimension as usize;
+
+        if INT_BITS + FRAC_BITS > n {
+            return Err(sunscreen_runtime::Error::fhe_type_error(
+                "INT_BITS + FRAC_BITS exceeds the lattice dimension",
+            ));
+        }
+
+        let magnitude = self.val.unsigned_abs();
+        let int_part = magnitude.checked_shr(FRAC_BITS as u32).unwrap_or(0);
+        let int_bits = (u64::BITS - int_part.leading_zeros()) as usize;
+
+        if int_bits > INT_BITS {
+            return Err(sunscreen_runtime::Error::fhe_type_error("Out of range"));
+        }
+
+        let mut seal_plaintext = SealPlaintext::new()?;
+        seal_plaintext.resize(n);
+
+        let is_negative = self.val < 0;
+
+        for i in 0..(u64::BITS as usize) {
+            let bit_value = (magnitude >> i) & 0x1;
+
+            if bit_value == 0 {
+                continue;
+            }
+
+            let power = i as i64 - FRAC_BITS as i64;
+
+            let (coeff_index, negate) = if power >= 0 {
+                (power as usize, is_negative)
+            } else {
+                // Negative powers live at the top of the polynomial with
+                // their sign inverted.
+                ((n as i64 + power) as usize, !is_negative)
+            };
+
+            let coeff = if negate {
+                params.plain_modulus - bit_value
+            } else {
+                bit_value
+            };
+
+            seal_plaintext.set_coefficient(coeff_index, coeff);
+        }
+
+        Ok(Plaintext {
+            data_type: self.type_name_instance(),
+            inner: InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data: seal_plaintext,
+            }]),
+        })
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> TryFromPlaintext
+    for Fixed<INT_BITS, FRAC_BITS>
+{
+    fn try_from_plaintext(
+        plaintext: &Plaintext,
+        params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        plaintext.inner.check_coefficient_count(params)?;
+
+        let val = match &plaintext.inner {
+            InnerPlaintext::Seal(p) => {
+                if p.len() != 1 {
+                    return Err(sunscreen_runtime::Error::IncorrectCiphertextCount);
+                }
+
+                let n = params.lattice_dimension as usize;
+                let len = p[0].len();
+
+                let negative_cutoff = (params.plain_modulus + 1) / 2;
+
+                // Accumulate in units of 2^-FRAC_BITS. Products contain
+                // digits finer than this, so round at the end.
+                let mut val = 0f64;
+
+                for i in 0..usize::min(n, len) {
+                    let coeff = p[0].get_coefficient(i);
+
+                    if coeff == 0 {
+                        continue;
+                    }
+
+                    let (power, sign) = if i < INT_BITS {
+                        (i as i64, 1f64)
+                    } else {
+                        (i as i64 - n as i64, -1f64)
+                    };
+
+                    let digit = if coeff < negative_cutoff {
+                        coeff as f64
+                    } else {
+                        -((params.plain_modulus - coeff) as f64)
+                    };
+
+                    val += sign * digit * ((power + FRAC_BITS as i64) as f64).exp2();
+                }
+
+                Self::from_bits(val.round() as i64)
+            }
+        };
+
+        Ok(val)
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> From<f64> for Fixed<INT_BITS, FRAC_BITS> {
+    fn from(val: f64) -> Self {
+        Self::from_bits((val * (FRAC_BITS as f64).exp2()).round() as i64)
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> From<Fixed<INT_BITS, FRAC_BITS>> for f64 {
+    fn from(val: Fixed<INT_BITS, FRAC_BITS>) -> Self {
+        val.val as f64 / (FRAC_BITS as f64).exp2()
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> Add for Fixed<INT_BITS, FRAC_BITS> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_bits(self.val + rhs.val)
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> Sub for Fixed<INT_BITS, FRAC_BITS> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_bits(self.val - rhs.val)
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> Mul for Fixed<INT_BITS, FRAC_BITS> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // Rescale the product back to FRAC_BITS, rounding to nearest
+        // with ties away from zero as decoding (f64::round) does.
+        let product = self.val as i128 * rhs.val as i128;
+        let half = if FRAC_BITS > 0 {
+            1i128 << (FRAC_BITS - 1)
+        } else {
+            0
+        };
+
+        let magnitude = (product.abs() + half) >> FRAC_BITS;
+
+        Self::from_bits((product.signum() * magnitude) as i64)
+    }
+}
+
+impl<const INT_BITS: usize, const FRAC_BITS: usize> Neg for Fixed<INT_BITS, FRAC_BITS> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_bits(-self.val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::approx_constant)]
+
+    use super::*;
+    use crate::{SchemeType, SecurityLevel};
+
+    type Fixed16 = Fixed<32, 16>;
+
+    fn params() -> Params {
+        Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        }
+    }
+
+    #[test]
+    fn can_encode_decode_fixed() {
+        let round_trip = |x: f64| {
+            let params = params();
+
+            let f_1 = Fixed16::from(x);
+            let pt = f_1.try_into_plaintext(&params).unwrap();
+            let f_2 = Fixed16::try_from_plaintext(&pt, &params).unwrap();
+
+            assert_eq!(f_1, f_2);
+            assert!((f64::from(f_2) - x).abs() <= (-16f64).exp2());
+        };
+
+        round_trip(3.14);
+        round_trip(-0.5);
+        round_trip(0.0);
+        round_trip(1.0);
+        round_trip(-6.6);
+        round_trip(1e6);
+    }
+
+    #[test]
+    fn out_of_range_fails_to_encode() {
+        let x = Fixed::<4, 16>::from(16.0);
+
+        assert!(x.try_into_plaintext(&params()).is_err());
+    }
+
+    #[test]
+    fn can_mul_non_fhe() {
+        let a = Fixed16::from(1.5);
+        let b = Fixed16::from(-2.25);
+
+        assert_eq!(a * b, Fixed16::from(-3.375));
+        assert_eq!(a + b, Fixed16::from(-0.75));
+        assert_eq!(a - b, Fixed16::from(3.75));
+        assert_eq!(-a, Fixed16::from(-1.5));
+    }
+
+    #[test]
+    fn mul_rounds_ties_like_decoding() {
+        // Products exactly half a unit in the last place from a
+        // representable value.
+        let half_ulp = Fixed16::from_bits(1 << 15);
+        let one_ulp = Fixed16::from_bits(1);
+
+        assert_eq!(one_ulp * half_ulp, Fixed16::from_bits(1));
+        assert_eq!(one_ulp * -half_ulp, Fixed16::from_bits(-1));
+        assert_eq!(Fixed16::from_bits(3) * -half_ulp, Fixed16::from_bits(-2));
+
+        // Decoding rounds the exact product of the encodings the same
+        // way.
+        let a = Fixed16::from(3.14);
+        let b = Fixed16::from(-0.5);
+        let exact = a.val as f64 * b.val as f64 / 16f64.exp2();
+
+        assert_eq!(a * b, Fixed16::from_bits(exact.round() as i64));
+    }
+}
@@ -202,7 +202,80 @@ impl<const INT_BITS: usize> TypeNameInstance for Fractional<INT_BITS> {
 impl<const INT_BITS: usize> FheType for Fractional<INT_BITS> {}
 impl<const INT_BITS: usize> BfvType for Fractional<INT_BITS> {}
 
-impl<const INT_BITS: usize> Fractional<INT_BITS> {}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * How [`Fractional::from_f64_rounded`] rounds values with more
+ * fractional bits than requested.
+ */
+pub enum RoundingMode {
+    /**
+     * Round to the nearest representable value, breaking ties toward
+     * the value whose last bit is 0 (banker's rounding). This is
+     * unbiased over many operations.
+     */
+    HalfEven,
+
+    /**
+     * Round to the nearest representable value, breaking ties away
+     * from zero.
+     */
+    HalfUp,
+
+    /**
+     * Discard the excess bits, rounding toward zero.
+     */
+    Truncate,
+}
+
+impl Default for RoundingMode {
+    fn default() -> Self {
+        Self::HalfEven
+    }
+}
+
+impl<const INT_BITS: usize> Fractional<INT_BITS> {
+    /**
+     * Creates a [`Fractional`] holding `val` rounded to `frac_bits`
+     * fractional bits under the given [`RoundingMode`].
+     *
+     * # Remarks
+     * Converting with [`From<f64>`] never rounds: every bit of the
+     * [`f64`] is encoded. Use this when a computation specifies its
+     * precision and rounding (e.g. for financial values) and results
+     * must be reproducible.
+     *
+     * Values too large to scale by `2^frac_bits` are returned
+     * unchanged.
+     */
+    pub fn from_f64_rounded(val: f64, frac_bits: u32, mode: RoundingMode) -> Self {
+        let scale = (frac_bits as f64).exp2();
+        let scaled = val * scale;
+
+        if !scaled.is_finite() {
+            return Self { val };
+        }
+
+        let rounded = match mode {
+            RoundingMode::Truncate => scaled.trunc(),
+            RoundingMode::HalfUp => scaled.round(),
+            RoundingMode::HalfEven => {
+                let r = scaled.round();
+
+                // round() breaks ties away from zero; step back toward
+                // zero when that lands on an odd value.
+                if (scaled - scaled.trunc()).abs() == 0.5 && r % 2.0 != 0.0 {
+                    r - r.signum()
+                } else {
+                    r
+                }
+            }
+        };
+
+        Self {
+            val: rounded / scale,
+        }
+    }
+}
 
 impl<const INT_BITS: usize> GraphCipherAdd for Fractional<INT_BITS> {
     type Left = Fractional<INT_BITS>;
@@ -517,6 +590,8 @@ impl<const INT_BITS: usize> TryFromPlaintext for Fractional<INT_BITS> {
         plaintext: &Plaintext,
         params: &Params,
     ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        plaintext.inner.check_coefficient_count(params)?;
+
         let val = match &plaintext.inner {
             InnerPlaintext::Seal(p) => {
                 if p.len() != 1 {
@@ -686,6 +761,52 @@ mod tests {
     use crate::{SchemeType, SecurityLevel};
     use float_cmp::ApproxEq;
 
+    #[test]
+    fn rounding_modes_round_ties_as_documented() {
+        let round = |x: f64, mode| *Fractional::<64>::from_f64_rounded(x, 2, mode);
+
+        assert_eq!(round(0.125, RoundingMode::HalfEven), 0.0);
+        assert_eq!(round(0.375, RoundingMode::HalfEven), 0.5);
+        assert_eq!(round(-0.375, RoundingMode::HalfEven), -0.5);
+
+        assert_eq!(round(0.125, RoundingMode::HalfUp), 0.25);
+        assert_eq!(round(0.375, RoundingMode::HalfUp), 0.5);
+        assert_eq!(round(-0.125, RoundingMode::HalfUp), -0.25);
+
+        assert_eq!(round(0.125, RoundingMode::Truncate), 0.0);
+        assert_eq!(round(0.375, RoundingMode::Truncate), 0.25);
+        assert_eq!(round(-0.375, RoundingMode::Truncate), -0.25);
+
+        // Values that already fit are unchanged.
+        assert_eq!(round(1.75, RoundingMode::Truncate), 1.75);
+        assert_eq!(*Fractional::<64>::from(0.375), 0.375);
+    }
+
+    #[test]
+    fn rounded_values_encode_exactly() {
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        for mode in [
+            RoundingMode::HalfEven,
+            RoundingMode::HalfUp,
+            RoundingMode::Truncate,
+        ] {
+            for x in [0.125, 0.375] {
+                let f_1 = Fractional::<64>::from_f64_rounded(x, 2, mode);
+                let pt = f_1.try_into_plaintext(&params).unwrap();
+                let f_2 = Fractional::<64>::try_from_plaintext(&pt, &params).unwrap();
+
+                assert_eq!(f_1, f_2);
+            }
+        }
+    }
+
     #[test]
     fn can_encode_decode_fractional() {
         let round_trip = |x: f64| {
@@ -0,0 +1,324 @@
+use paste::paste;
+use seal_fhe::Plaintext as SealPlaintext;
+
+use crate::{
+    fhe::{with_fhe_ctx, FheContextOps},
+    types::{
+        intern::FheProgramNode,
+        ops::{GraphCipherAdd, GraphCipherMul, GraphCipherPlainAdd, GraphCipherPlainMul},
+        BfvType, Cipher, FheType, Type, TypeNameInstance, Version,
+    },
+    FheProgramInputTrait, Params, WithContext,
+};
+
+use sunscreen_runtime::{
+    Error as RuntimeError, InnerPlaintext, NumCiphertexts, Plaintext, TryFromPlaintext,
+    TryIntoPlaintext, TypeName,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/**
+ * An unsigned integer of `BITS` bits.
+ *
+ * # Remarks
+ * Like [`Unsigned`](crate::types::bfv::Unsigned), this encodes bit `i`
+ * of the value as coefficient `i` of the plaintext, but never uses
+ * more than `BITS` coefficients. Decoding only reads the first `BITS`
+ * coefficients and returns
+ * [`ValueOutOfRange`](sunscreen_runtime::Error::ValueOutOfRange) if
+ * the result doesn't fit in `BITS` bits, e.g. because a sum
+ * overflowed. Using `BITS` larger than 64 fails to compile.
+ *
+ * Conversions to and from `u8`, `u16`, `u32`, and `u64` exist for the
+ * matching widths. Use [`new`](Self::new) for other widths.
+ *
+ * This type supports addition and multiplication.
+ */
+pub struct UnsignedN<const BITS: usize> {
+    val: u64,
+}
+
+impl<const BITS: usize> UnsignedN<BITS> {
+    /**
+     * The largest value this type holds, `2^BITS - 1`.
+     *
+     * # Remarks
+     * The shift overflows unless `BITS` is between 1 and 64, so using
+     * this constant rejects other widths at compile time.
+     */
+    pub const MAX: u64 = u64::MAX >> (64 - BITS);
+
+    /**
+     * Creates an `UnsignedN` holding `val`.
+     *
+     * # Remarks
+     * Returns [`ValueOutOfRange`](sunscreen_runtime::Error::ValueOutOfRange)
+     * if `val` exceeds [`MAX`](Self::MAX).
+     */
+    pub fn new(val: u64) -> std::result::Result<Self, RuntimeError> {
+        if val > Self::MAX {
+            return Err(RuntimeError::value_out_of_range(&format!(
+                "{} doesn't fit in {} bits",
+                val, BITS
+            )));
+        }
+
+        Ok(Self { val })
+    }
+}
+
+impl<const BITS: usize> TypeName for UnsignedN<BITS> {
+    fn type_name() -> Type {
+        let version = env!("CARGO_PKG_VERSION");
+
+        Type {
+            name: format!("sunscreen::types::bfv::UnsignedN<{}>", BITS),
+            version: Version::parse(version).expect("Crate version is not a valid semver"),
+            is_encrypted: false,
+        }
+    }
+}
+
+impl<const BITS: usize> TypeNameInstance for UnsignedN<BITS> {
+    fn type_name_instance(&self) -> Type {
+        Self::type_name()
+    }
+}
+
+impl<const BITS: usize> NumCiphertexts for UnsignedN<BITS> {
+    const NUM_CIPHERTEXTS: usize = 1;
+}
+
+impl<const BITS: usize> FheProgramInputTrait for UnsignedN<BITS> {}
+impl<const BITS: usize> FheType for UnsignedN<BITS> {}
+impl<const BITS: usize> BfvType for UnsignedN<BITS> {}
+
+impl<const BITS: usize> std::fmt::Display for UnsignedN<BITS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+macro_rules! impl_primitive_conversions {
+    ($(($ty:ty, $bits:expr)),+) => {
+        $(
+            impl From<$ty> for UnsignedN<$bits> {
+                fn from(val: $ty) -> Self {
+                    Self { val: val as u64 }
+                }
+            }
+
+            impl From<UnsignedN<$bits>> for $ty {
+                fn from(x: UnsignedN<$bits>) -> Self {
+                    // UnsignedN never holds more than $bits bits.
+                    x.val as $ty
+                }
+            }
+        )+
+    };
+}
+
+impl_primitive_conversions! {
+    (u8, 8),
+    (u16, 16),
+    (u32, 32),
+    (u64, 64)
+}
+
+impl<const BITS: usize> TryIntoPlaintext for UnsignedN<BITS> {
+    fn try_into_plaintext(
+        &self,
+        params: &Params,
+    ) -> std::result::Result<Plaintext, sunscreen_runtime::Error> {
+        // Reject invalid widths.
+        let _ = Self::MAX;
+
+        let mut seal_plaintext = SealPlaintext::new()?;
+
+        let sig_bits = (u64::BITS - self.val.leading_zeros()) as usize;
+
+        // Zero needs no bits, but SEAL expects at least one coefficient.
+        seal_plaintext.resize(usize::max(sig_bits, 1));
+
+        for i in 0..sig_bits {
+            seal_plaintext.set_coefficient(i, (self.val >> i) & 0x1);
+        }
+
+        Ok(Plaintext {
+            data_type: self.type_name_instance(),
+            inner: InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data: seal_plaintext,
+            }]),
+        })
+    }
+}
+
+impl<const BITS: usize> TryFromPlaintext for UnsignedN<BITS> {
+    fn try_from_plaintext(
+        plaintext: &Plaintext,
+        params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        plaintext.inner.check_coefficient_count(params)?;
+
+        let p = plaintext.inner_as_seal_plaintext()?;
+
+        if p.len() != 1 {
+            return Err(RuntimeError::IncorrectCiphertextCount);
+        }
+
+        let p = &p[0].data;
+
+        let t = params.plain_modulus;
+
+        // Centered coefficients are below 2^63, so summing BITS <= 64 of
+        // them can't overflow an i128.
+        let exact = (0..usize::min(BITS, p.len())).fold(0i128, |sum, i| {
+            let coeff = p.get_coefficient(i);
+
+            let centered = if coeff < (t + 1) / 2 {
+                coeff as i128
+            } else {
+                coeff as i128 - t as i128
+            };
+
+            sum + centered * (1i128 << i)
+        });
+
+        if exact < 0 || exact > Self::MAX as i128 {
+            return Err(RuntimeError::value_out_of_range(&format!(
+                "{} doesn't fit in {} bits",
+                exact, BITS
+            )));
+        }
+
+        Ok(Self { val: exact as u64 })
+    }
+}
+
+macro_rules! impl_graph_cipher_op {
+    ($(($op:ident, $op_noun:ident)),+) => {
+        $(
+            paste! {
+                impl<const BITS: usize> [<GraphCipher $op>] for UnsignedN<BITS> {
+                    type Left = Self;
+                    type Right = Self;
+
+                    fn [<graph_cipher_ $op:lower>](
+                        a: FheProgramNode<Cipher<Self::Left>>,
+                        b: FheProgramNode<Cipher<Self::Right>>,
+                    ) -> FheProgramNode<Cipher<Self::Left>> {
+                        with_fhe_ctx(|ctx| {
+                            let n = ctx.[<add_ $op_noun>](a.ids[0], b.ids[0]);
+
+                            FheProgramNode::new(&[n])
+                        })
+                    }
+                }
+
+                impl<const BITS: usize> [<GraphCipherPlain $op>] for UnsignedN<BITS> {
+                    type Left = Self;
+                    type Right = Self;
+
+                    fn [<graph_cipher_plain_ $op:lower>](
+                        a: FheProgramNode<Cipher<Self::Left>>,
+                        b: FheProgramNode<Self::Right>,
+                    ) -> FheProgramNode<Cipher<Self::Left>> {
+                        with_fhe_ctx(|ctx| {
+                            let n = ctx.[<add_ $op_noun _plaintext>](a.ids[0], b.ids[0]);
+
+                            FheProgramNode::new(&[n])
+                        })
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_graph_cipher_op! {
+    (Add, addition),
+    (Mul, multiplication)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SchemeType, SecurityLevel};
+
+    fn params() -> Params {
+        Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        }
+    }
+
+    #[test]
+    fn can_roundtrip_8_bit_value() {
+        let params = params();
+
+        let x = UnsignedN::<8>::from(200u8);
+        let pt = x.try_into_plaintext(&params).unwrap();
+
+        match &pt.inner {
+            InnerPlaintext::Seal(p) => assert!(p[0].len() <= 8),
+        }
+
+        let decoded = UnsignedN::<8>::try_from_plaintext(&pt, &params).unwrap();
+
+        assert_eq!(u8::from(decoded), 200);
+    }
+
+    #[test]
+    fn rejects_values_wider_than_bits() {
+        assert!(matches!(
+            UnsignedN::<8>::new(256),
+            Err(RuntimeError::ValueOutOfRange(_))
+        ));
+
+        assert_eq!(UnsignedN::<8>::new(255).unwrap(), 255u8.into());
+        assert_eq!(UnsignedN::<12>::MAX, 4095);
+        assert_eq!(UnsignedN::<64>::MAX, u64::MAX);
+    }
+
+    #[test]
+    fn type_name_includes_bits() {
+        assert_eq!(
+            UnsignedN::<8>::type_name().name,
+            "sunscreen::types::bfv::UnsignedN<8>"
+        );
+        assert_ne!(UnsignedN::<8>::type_name(), UnsignedN::<16>::type_name());
+    }
+
+    #[test]
+    fn decoding_only_reads_bits_coefficients() {
+        let params = params();
+
+        // Coefficient 8 is past an 8-bit value, so it's ignored.
+        let pt = UnsignedN::<16>::from(0x1ffu16)
+            .try_into_plaintext(&params)
+            .unwrap();
+
+        assert_eq!(
+            u8::from(UnsignedN::<8>::try_from_plaintext(&pt, &params).unwrap()),
+            0xff
+        );
+
+        // A sum that carries past 8 bits doesn't fit.
+        let mut pt = UnsignedN::<8>::from(0x80u8)
+            .try_into_plaintext(&params)
+            .unwrap();
+
+        match &mut pt.inner {
+            InnerPlaintext::Seal(p) => p[0].data.set_coefficient(7, 2),
+        }
+
+        assert!(matches!(
+            UnsignedN::<8>::try_from_plaintext(&pt, &params),
+            Err(RuntimeError::ValueOutOfRange(_))
+        ));
+    }
+}
@@ -1,11 +1,21 @@
 mod batched;
+mod boolean;
+mod checksummed;
+mod fixed;
 mod fractional;
+mod integer;
+mod polynomial;
 mod rational;
 mod signed;
 mod unsigned;
 
 pub use batched::*;
+pub use boolean::*;
+pub use checksummed::*;
+pub use fixed::*;
 pub use fractional::*;
+pub use integer::*;
+pub use polynomial::*;
 pub use rational::*;
 pub use signed::*;
 pub use unsigned::*;
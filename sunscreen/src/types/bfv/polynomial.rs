@@ -0,0 +1,230 @@
+use crate as sunscreen;
+use crate::{
+    fhe::{with_fhe_ctx, FheContextOps},
+    types::{
+        bfv::Signed,
+        intern::FheProgramNode,
+        ops::{GraphCipherAdd, GraphCipherMul},
+        BfvType, Cipher, FheType, TypeName, TypeNameInstance,
+    },
+    FheProgramInputTrait, Params, TypeName as DeriveTypeName,
+};
+
+use sunscreen_runtime::{
+    Error as RuntimeError, InnerPlaintext, NumCiphertexts, Plaintext, TryFromPlaintext,
+    TryIntoPlaintext,
+};
+
+#[derive(Debug, Clone, Copy, DeriveTypeName, PartialEq, Eq)]
+/**
+ * A polynomial `c_0 + c_1 x + ... + c_{N-1} x^{N-1}` whose `N`
+ * coefficients are each a separately encrypted [`Signed`].
+ *
+ * # Remarks
+ * Each coefficient occupies its own ciphertext, in order from the
+ * constant term up, so this type uses `N` ciphertexts. Addition and
+ * multiplication operate coefficient-wise; they don't compute the
+ * polynomial product. Use
+ * [`evaluate_at`](crate::types::intern::FheProgramNode::evaluate_at)
+ * to evaluate the polynomial at an encrypted point, which costs
+ * `N - 1` ciphertext multiplications.
+ */
+pub struct EncryptedPolynomial<const N: usize> {
+    coefficients: [Signed; N],
+}
+
+impl<const N: usize> EncryptedPolynomial<N> {
+    /**
+     * The coefficients, starting with the constant term.
+     */
+    pub fn coefficients(&self) -> [i64; N] {
+        self.coefficients.map(i64::from)
+    }
+}
+
+impl<const N: usize> NumCiphertexts for EncryptedPolynomial<N> {
+    const NUM_CIPHERTEXTS: usize = N;
+}
+
+impl<const N: usize> FheProgramInputTrait for EncryptedPolynomial<N> {}
+impl<const N: usize> FheType for EncryptedPolynomial<N> {}
+impl<const N: usize> BfvType for EncryptedPolynomial<N> {}
+
+impl<const N: usize> Default for EncryptedPolynomial<N> {
+    fn default() -> Self {
+        Self {
+            coefficients: [Signed::default(); N],
+        }
+    }
+}
+
+impl<const N: usize> std::fmt::Display for EncryptedPolynomial<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.coefficients())
+    }
+}
+
+impl<const N: usize> From<[i64; N]> for EncryptedPolynomial<N> {
+    fn from(coefficients: [i64; N]) -> Self {
+        Self {
+            coefficients: coefficients.map(Signed::from),
+        }
+    }
+}
+
+impl<const N: usize> From<EncryptedPolynomial<N>> for [i64; N] {
+    fn from(x: EncryptedPolynomial<N>) -> Self {
+        x.coefficients()
+    }
+}
+
+impl<const N: usize> TryIntoPlaintext for EncryptedPolynomial<N> {
+    fn try_into_plaintext(
+        &self,
+        params: &Params,
+    ) -> std::result::Result<Plaintext, sunscreen_runtime::Error> {
+        let mut plaintexts = Vec::with_capacity(N);
+
+        for c in &self.coefficients {
+            match c.try_into_plaintext(params)?.inner {
+                InnerPlaintext::Seal(p) => plaintexts.extend(p),
+            }
+        }
+
+        Ok(Plaintext {
+            data_type: self.type_name_instance(),
+            inner: InnerPlaintext::Seal(plaintexts),
+        })
+    }
+}
+
+impl<const N: usize> TryFromPlaintext for EncryptedPolynomial<N> {
+    fn try_from_plaintext(
+        plaintext: &Plaintext,
+        params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        let p = plaintext.inner_as_seal_plaintext()?;
+
+        if p.len() != N {
+            return Err(RuntimeError::IncorrectCiphertextCount);
+        }
+
+        let mut coefficients = [Signed::default(); N];
+
+        for (c, p) in coefficients.iter_mut().zip(p) {
+            *c = Signed::try_from_plaintext(
+                &Plaintext {
+                    data_type: Signed::type_name(),
+                    inner: InnerPlaintext::Seal(vec![p.clone()]),
+                },
+                params,
+            )?;
+        }
+
+        Ok(Self { coefficients })
+    }
+}
+
+impl<const N: usize> GraphCipherAdd for EncryptedPolynomial<N> {
+    type Left = EncryptedPolynomial<N>;
+    type Right = EncryptedPolynomial<N>;
+
+    fn graph_cipher_add(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let ids = a
+                .ids
+                .iter()
+                .zip(b.ids)
+                .map(|(a, b)| ctx.add_addition(*a, *b))
+                .collect::<Vec<_>>();
+
+            FheProgramNode::new(&ids)
+        })
+    }
+}
+
+impl<const N: usize> GraphCipherMul for EncryptedPolynomial<N> {
+    type Left = EncryptedPolynomial<N>;
+    type Right = EncryptedPolynomial<N>;
+
+    fn graph_cipher_mul(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let ids = a
+                .ids
+                .iter()
+                .zip(b.ids)
+                .map(|(a, b)| ctx.add_multiplication(*a, *b))
+                .collect::<Vec<_>>();
+
+            FheProgramNode::new(&ids)
+        })
+    }
+}
+
+impl<const N: usize> FheProgramNode<Cipher<EncryptedPolynomial<N>>> {
+    /**
+     * Evaluates the polynomial at `point` using Horner's method.
+     *
+     * # Remarks
+     * Panics during FHE program construction if `N` is 0.
+     */
+    pub fn evaluate_at(
+        self,
+        point: FheProgramNode<Cipher<Signed>>,
+    ) -> FheProgramNode<Cipher<Signed>> {
+        let (last, rest) = self
+            .ids
+            .split_last()
+            .expect("Can't evaluate a polynomial without coefficients");
+
+        with_fhe_ctx(|ctx| {
+            let result = rest.iter().rev().fold(*last, |acc, c| {
+                let product = ctx.add_multiplication(acc, point.ids[0]);
+
+                ctx.add_addition(product, *c)
+            });
+
+            FheProgramNode::new(&[result])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SchemeType, SecurityLevel};
+
+    #[test]
+    fn can_encode_decode() {
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let x = EncryptedPolynomial::from([3, -7, 0, 42]);
+
+        let pt = x.try_into_plaintext(&params).unwrap();
+
+        match &pt.inner {
+            InnerPlaintext::Seal(p) => assert_eq!(p.len(), 4),
+        }
+
+        assert_eq!(
+            EncryptedPolynomial::<4>::try_from_plaintext(&pt, &params).unwrap(),
+            x
+        );
+        assert_eq!(
+            EncryptedPolynomial::<3>::try_from_plaintext(&pt, &params),
+            Err(RuntimeError::IncorrectCiphertextCount)
+        );
+    }
+}
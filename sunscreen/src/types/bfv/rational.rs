@@ -100,6 +100,58 @@ impl TryFrom<f64> for Rational {
     }
 }
 
+impl From<(i64, i64)> for Rational {
+    /**
+     * Creates the rational `num / den` from a `(num, den)` pair.
+     *
+     * # Remarks
+     * The ratio is reduced to lowest terms and the sign is carried on
+     * the numerator, so `(2, -4)` becomes `-1 / 2`.
+     *
+     * # Panics
+     * Panics if `den` is zero.
+     */
+    fn from((num, den): (i64, i64)) -> Self {
+        let val = Rational64::new(num, den);
+
+        Self {
+            num: Signed::from(*val.numer()),
+            den: Signed::from(*val.denom()),
+        }
+    }
+}
+
+impl Rational {
+    /**
+     * The numerator of this ratio.
+     *
+     * # Remarks
+     * Results computed under FHE aren't reduced, so a decrypted
+     * `Rational` may hold, e.g., `6 / 12` rather than `1 / 2`. Use
+     * [`Rational::reduce`] to obtain the canonical pair.
+     */
+    pub fn numerator(&self) -> i64 {
+        self.num.into()
+    }
+
+    /**
+     * The denominator of this ratio. See [`Rational::numerator`].
+     */
+    pub fn denominator(&self) -> i64 {
+        self.den.into()
+    }
+
+    /**
+     * Returns this ratio in lowest terms with a positive denominator.
+     *
+     * # Panics
+     * Panics if the denominator is zero.
+     */
+    pub fn reduce(&self) -> Self {
+        Self::from((self.numerator(), self.denominator()))
+    }
+}
+
 impl From<Rational> for f64 {
     fn from(val: Rational) -> Self {
         let num: i64 = val.num.into();
@@ -646,6 +698,31 @@ impl GraphCipherNeg for Rational {
 mod tests {
     use super::*;
 
+    #[test]
+    fn from_pair_reduces_to_lowest_terms() {
+        let a = Rational::from((6, -12));
+
+        assert_eq!(a.numerator(), -1);
+        assert_eq!(a.denominator(), 2);
+
+        let b = Rational::from((0, 7));
+
+        assert_eq!(b.numerator(), 0);
+        assert_eq!(b.denominator(), 1);
+    }
+
+    #[test]
+    fn reduce_normalizes_unreduced_pair() {
+        let a = Rational {
+            num: Signed::from(9),
+            den: Signed::from(-3),
+        };
+
+        let a = a.reduce();
+
+        assert_eq!((a.numerator(), a.denominator()), (-3, 1));
+    }
+
     #[test]
     fn can_add_non_fhe() {
         let a = Rational::try_from(5.).unwrap();
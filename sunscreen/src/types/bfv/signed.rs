@@ -3,15 +3,17 @@ use seal_fhe::Plaintext as SealPlaintext;
 use crate as sunscreen;
 use crate::types::ops::GraphCipherInsert;
 use crate::{
-    fhe::{with_fhe_ctx, FheContextOps},
+    fhe::{encode_literal, set_build_error, with_fhe_ctx, FheContextOps, FheOperation},
     types::{
         ops::{
-            GraphCipherAdd, GraphCipherConstAdd, GraphCipherConstMul, GraphCipherConstSub,
-            GraphCipherMul, GraphCipherNeg, GraphCipherPlainAdd, GraphCipherPlainMul,
-            GraphCipherPlainSub, GraphCipherSub, GraphConstCipherSub, GraphPlainCipherSub,
+            GraphCipherAdd, GraphCipherConstAdd, GraphCipherConstMul, GraphCipherConstPow,
+            GraphCipherConstSub, GraphCipherMul, GraphCipherNeg, GraphCipherPlainAdd,
+            GraphCipherPlainMul, GraphCipherPlainSub, GraphCipherShift, GraphCipherSub,
+            GraphConstCipherSub, GraphPlainCipherSub,
         },
         Cipher,
     },
+    Error,
 };
 use crate::{
     types::{intern::FheProgramNode, BfvType, FheType, TypeNameInstance},
@@ -27,6 +29,14 @@ use std::ops::*;
 #[derive(Debug, Clone, Copy, DeriveTypeName, PartialEq, Eq)]
 /**
  * A single signed integer.
+ *
+ * # Remarks
+ * Zero encodes to a plaintext with a single zero coefficient rather
+ * than an empty one, so it's a valid operand for every homomorphic
+ * operation and decodes back to zero. Note that multiplying a
+ * ciphertext by an unencrypted zero yields a transparent ciphertext,
+ * which SEAL rejects unless the `transparent-ciphertexts` feature is
+ * enabled.
  */
 pub struct Signed {
     val: i64,
@@ -52,16 +62,16 @@ impl Default for Signed {
     }
 }
 
+/**
+ * Returns the number of bits needed to represent `val`, which is the
+ * number of coefficients needed to encode it.
+ */
 fn significant_bits(val: u64) -> usize {
-    let bits = std::mem::size_of::<u64>() * 8;
-
-    for i in 0..bits {
-        if (0x1 << (bits - i - 1)) & val != 0 {
-            return bits - i + 1;
-        }
+    if val == 0 {
+        return 0;
     }
 
-    0
+    (u64::BITS - val.leading_zeros()) as usize
 }
 
 impl TryIntoPlaintext for Signed {
@@ -69,12 +79,22 @@ impl TryIntoPlaintext for Signed {
         &self,
         params: &Params,
     ) -> std::result::Result<Plaintext, sunscreen_runtime::Error> {
+        // From<i64> has no params to check against, so check here.
+        debug_assert!(
+            Self::is_representable(self.val, params),
+            "{} isn't representable as a Signed with plain modulus {}",
+            self.val,
+            params.plain_modulus
+        );
+
         let mut seal_plaintext = SealPlaintext::new()?;
 
-        let signed_val = if self.val < 0 { -self.val } else { self.val } as u64;
+        let signed_val = self.val.unsigned_abs();
 
         let sig_bits = significant_bits(signed_val);
-        seal_plaintext.resize(sig_bits);
+
+        // Zero needs no bits, but SEAL expects at least one coefficient.
+        seal_plaintext.resize(usize::max(sig_bits, 1));
 
         for i in 0..sig_bits {
             let bit_value = (signed_val & 0x1 << i) >> i;
@@ -99,41 +119,191 @@ impl TryIntoPlaintext for Signed {
 }
 
 impl TryFromPlaintext for Signed {
+    /**
+     * Decodes the value from the plaintext's first 64 coefficients.
+     *
+     * # Remarks
+     * Returns [`PlaintextTooLarge`](sunscreen_runtime::Error::PlaintextTooLarge)
+     * if the plaintext has more coefficients than the lattice dimension
+     * and [`ValueOutOfRange`](sunscreen_runtime::Error::ValueOutOfRange)
+     * if the decoded value doesn't fit in an `i64`. Higher coefficients
+     * are ignored, as they only hold bits shifted out of the value (see
+     * [`Shr`](std::ops::Shr)).
+     */
     fn try_from_plaintext(
         plaintext: &Plaintext,
         params: &Params,
     ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        plaintext.inner.check_coefficient_count(params)?;
+
         let val = match &plaintext.inner {
             InnerPlaintext::Seal(p) => {
                 if p.len() != 1 {
                     return Err(sunscreen_runtime::Error::IncorrectCiphertextCount);
                 }
 
-                let bits = usize::min(
-                    usize::min(std::mem::size_of::<u64>() * 8, p[0].len()),
-                    p[0].len(),
-                );
+                let bits = usize::min(std::mem::size_of::<u64>() * 8, p[0].len());
+
+                let t = params.plain_modulus;
+                let negative_cutoff = (t + 1) / 2;
 
-                let negative_cutoff = (params.plain_modulus + 1) / 2;
+                let out_of_range = || {
+                    sunscreen_runtime::Error::value_out_of_range(
+                        "Signed value exceeds the range of an i64",
+                    )
+                };
 
-                let mut val: i64 = 0;
+                // With centered coefficients below 2^63, the sum across 64
+                // bits can't overflow an i128, but check anyway.
+                let mut exact: i128 = 0;
 
-                for i in 0..bits {
+                for i in (0..bits).rev() {
                     let coeff = p[0].get_coefficient(i);
 
-                    if coeff < negative_cutoff {
-                        val += ((0x1 << i) * coeff) as i64;
+                    let centered = if coeff < negative_cutoff {
+                        coeff as i128
                     } else {
-                        val -= ((0x1 << i) * (params.plain_modulus - coeff)) as i64;
-                    }
+                        coeff as i128 - t as i128
+                    };
+
+                    exact = exact
+                        .checked_mul(2)
+                        .and_then(|x| x.checked_add(centered))
+                        .ok_or_else(out_of_range)?;
                 }
 
-                Self { val }
+                Self {
+                    val: i64::try_from(exact).map_err(|_| out_of_range())?,
+                }
             }
         };
 
         Ok(val)
     }
+
+    /**
+     * Decodes as [`try_from_plaintext`](Self::try_from_plaintext) does,
+     * but returns [`OverflowDetected`](sunscreen_runtime::Error::OverflowDetected)
+     * if a coefficient's magnitude exceeds `plain_modulus / 4`.
+     *
+     * # Remarks
+     * A coefficient that overflows the plaintext modulus wraps to the
+     * opposite sign, so wrapped coefficients tend to lie near `t / 2`.
+     * This is a heuristic: a coefficient that wrapped far past `t / 2`
+     * goes undetected, while a legitimate coefficient above `t / 4` is
+     * flagged.
+     */
+    fn try_from_plaintext_checked(
+        plaintext: &Plaintext,
+        params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        let val = Self::try_from_plaintext(plaintext, params)?;
+
+        // try_from_plaintext checked there's exactly 1 plaintext.
+        let p = &plaintext.inner_as_seal_plaintext()?[0].data;
+
+        let t = params.plain_modulus;
+        let bits = usize::min(std::mem::size_of::<u64>() * 8, p.len());
+
+        let overflowed = (0..bits).any(|i| {
+            let coeff = p.get_coefficient(i);
+
+            let centered = if coeff < (t + 1) / 2 {
+                coeff as i128
+            } else {
+                coeff as i128 - t as i128
+            };
+
+            centered.unsigned_abs() > (t / 4) as u128
+        });
+
+        if overflowed {
+            return Err(sunscreen_runtime::Error::OverflowDetected {
+                recovered_value: val.val,
+            });
+        }
+
+        Ok(val)
+    }
+}
+
+impl Signed {
+    /**
+     * Whether a single coefficient can hold `val` without checked
+     * decoding flagging it as an overflow under `params`, i.e.
+     * `|val| <= plain_modulus / 4`.
+     *
+     * # Remarks
+     * Coefficients grow with the number of terms a computation sums
+     * rather than with its value, so results in this range can still
+     * overflow, e.g. when adding many values that cancel out.
+     */
+    pub fn is_in_range(val: i64, params: &Params) -> bool {
+        val.unsigned_abs() <= params.plain_modulus / 4
+    }
+
+    /**
+     * The largest value that encodes and decodes exactly under `params`.
+     *
+     * # Remarks
+     * Each coefficient holds a single bit of the value's magnitude, so
+     * any plain modulus above 2 represents every `i64`. A plain modulus
+     * of 2 can't tell a set bit from a negated one, so positive values
+     * decode as negative ones. The range of a computation's *result* is
+     * narrower, since coefficients grow with each operation; see
+     * [`is_in_range`](Self::is_in_range).
+     */
+    pub fn max_value(params: &Params) -> i64 {
+        if params.plain_modulus > 2 {
+            i64::MAX
+        } else {
+            0
+        }
+    }
+
+    /**
+     * The smallest value that encodes and decodes exactly under
+     * `params`. See [`max_value`](Self::max_value).
+     */
+    pub fn min_value(params: &Params) -> i64 {
+        if params.plain_modulus >= 2 {
+            i64::MIN
+        } else {
+            0
+        }
+    }
+
+    /**
+     * Whether `val` lies in
+     * [`min_value`](Self::min_value)`..=`[`max_value`](Self::max_value)
+     * under `params`.
+     */
+    fn is_representable(val: i64, params: &Params) -> bool {
+        (Self::min_value(params)..=Self::max_value(params)).contains(&val)
+    }
+
+    /**
+     * Creates a [`Signed`] after checking that `val` lies in
+     * [`min_value`](Self::min_value)`..=`[`max_value`](Self::max_value)
+     * under `params`.
+     *
+     * # Remarks
+     * Returns [`ValueOutOfRange`](sunscreen_runtime::Error::ValueOutOfRange)
+     * otherwise.
+     */
+    pub fn try_from_i64(
+        val: i64,
+        params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        if !Self::is_representable(val, params) {
+            return Err(sunscreen_runtime::Error::value_out_of_range(&format!(
+                "{} isn't representable as a Signed with plain modulus {}",
+                val, params.plain_modulus
+            )));
+        }
+
+        Ok(Self::from(val))
+    }
 }
 
 impl From<i64> for Signed {
@@ -284,8 +454,10 @@ impl GraphCipherInsert for Signed {
 
     fn graph_cipher_insert(lit: Self::Lit) -> FheProgramNode<Self::Val> {
         with_fhe_ctx(|ctx| {
-            let lit = Self::from(lit).try_into_plaintext(&ctx.data).unwrap();
-            let lit = ctx.add_plaintext_literal(lit.inner);
+            let lit = encode_literal::<Self, _>(&[lit as u64], &ctx.data, || {
+                Self::from(lit).try_into_plaintext(&ctx.data).unwrap().inner
+            });
+            let lit = ctx.add_plaintext_literal(lit);
 
             FheProgramNode::new(&[lit])
         })
@@ -452,9 +624,153 @@ impl GraphCipherPlainMul for Signed {
     }
 }
 
+impl GraphCipherConstPow for Signed {
+    /**
+     * Computes `x^n` by repeated squaring, which takes
+     * `floor(log2(n)) + popcount(n) - 1` multiplications and has a
+     * multiplicative depth of `ceil(log2(n))`. For example, `x^8` takes
+     * 3 multiplications rather than 7.
+     *
+     * Fails compilation if `n` is 0, since a ciphertext alone can't
+     * produce an encryption of 1.
+     */
+    fn graph_cipher_const_pow(
+        x: FheProgramNode<Cipher<Self>>,
+        n: u32,
+    ) -> FheProgramNode<Cipher<Self>> {
+        if n == 0 {
+            set_build_error(Error::unsupported(
+                "Can't raise a ciphertext to the power 0",
+            ));
+
+            return x;
+        }
+
+        with_fhe_ctx(|ctx| {
+            // Square x once per bit of n, multiplying the squares for
+            // set bits into the result.
+            let mut square = x.ids[0];
+            let mut result = None;
+            let mut remaining = n;
+
+            loop {
+                if remaining & 0x1 == 1 {
+                    result = Some(match result {
+                        Some(r) => ctx.add_multiplication(r, square),
+                        None => square,
+                    });
+                }
+
+                remaining >>= 1;
+
+                if remaining == 0 {
+                    break;
+                }
+
+                square = ctx.add_multiplication(square, square);
+            }
+
+            FheProgramNode::new(&[result.unwrap()])
+        })
+    }
+}
+
+/**
+ * Records a build error and returns `false` unless `amount` is a valid
+ * shift amount.
+ */
+fn check_shift_amount(amount: i32) -> bool {
+    let valid = (0..63).contains(&amount);
+
+    if !valid {
+        set_build_error(Error::unsupported(
+            "Shift amounts must be non-negative and less than 63",
+        ));
+    }
+
+    valid
+}
+
+impl GraphCipherShift for Signed {
+    /**
+     * Multiplies by `2^amount`.
+     *
+     * Fails compilation unless `0 <= amount < 63`.
+     */
+    fn graph_cipher_shl(
+        x: FheProgramNode<Cipher<Self>>,
+        amount: i32,
+    ) -> FheProgramNode<Cipher<Self>> {
+        if !check_shift_amount(amount) || amount == 0 {
+            return x;
+        }
+
+        Self::graph_cipher_const_mul(x, 1 << amount)
+    }
+
+    /**
+     * Divides by `2^amount`, truncating toward zero.
+     *
+     * # Remarks
+     * Since `Signed` encodes bit `i` as coefficient `i`, this multiplies
+     * by `x^-amount = -x^(n - amount)`, which moves each coefficient
+     * down `amount` places and wraps the discarded ones into the top
+     * coefficients.
+     *
+     * This only divides by `2^amount` when each coefficient is a single
+     * bit, i.e. on fresh inputs. Additions and multiplications leave
+     * coefficients larger than 1, and the discarded low coefficients
+     * then no longer amount to the remainder. For example, with `a = 3`,
+     * `(a + a) >> 1` would decode to 2, not 3.
+     *
+     * Fails compilation unless `x` is an FHE program input and
+     * `0 <= amount < 63`.
+     */
+    fn graph_cipher_shr(
+        x: FheProgramNode<Cipher<Self>>,
+        amount: i32,
+    ) -> FheProgramNode<Cipher<Self>> {
+        let is_input = with_fhe_ctx(|ctx| {
+            matches!(ctx.graph[x.ids[0]].operation, FheOperation::InputCiphertext)
+        });
+
+        if !is_input {
+            set_build_error(Error::unsupported(
+                "Only FHE program inputs of type Signed can be shifted right",
+            ));
+
+            return x;
+        }
+
+        if !check_shift_amount(amount) || amount == 0 {
+            return x;
+        }
+
+        with_fhe_ctx(|ctx| {
+            let degree = ctx.data.lattice_dimension as usize - amount as usize;
+
+            let mut monomial = SealPlaintext::new().unwrap();
+            monomial.resize(degree + 1);
+            monomial.set_coefficient(degree, ctx.data.plain_modulus - 1);
+
+            let monomial = InnerPlaintext::Seal(vec![WithContext {
+                params: ctx.data.clone(),
+                data: monomial,
+            }]);
+
+            let lit = ctx.add_plaintext_literal(monomial);
+
+            let n = ctx.add_multiplication_plaintext(x.ids[0], lit);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{SchemeType, SecurityLevel};
 
     #[test]
     fn can_add_non_fhe() {
@@ -492,4 +808,236 @@ mod tests {
 
         assert_eq!(-a, (-5).into());
     }
+
+    #[test]
+    fn significant_bits_is_exact() {
+        assert_eq!(significant_bits(0), 0);
+        assert_eq!(significant_bits(1), 1);
+        assert_eq!(significant_bits(2), 2);
+        assert_eq!(significant_bits(3), 2);
+        assert_eq!(significant_bits(4), 3);
+        assert_eq!(significant_bits(i64::MAX as u64), 63);
+        assert_eq!(significant_bits(u64::MAX), 64);
+
+        for i in 0..64 {
+            assert_eq!(significant_bits(0x1 << i), i + 1);
+        }
+    }
+
+    #[test]
+    fn can_encode_decode_signed() {
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let round_trip = |x: i64| {
+            let s_1 = Signed::from(x);
+            let pt = s_1.try_into_plaintext(&params).unwrap();
+            let s_2 = Signed::try_from_plaintext(&pt, &params).unwrap();
+
+            assert_eq!(s_1, s_2);
+        };
+
+        for i in 0..63 {
+            round_trip(0x1 << i);
+            round_trip(-(0x1 << i));
+        }
+
+        round_trip(0);
+        round_trip(i64::MAX);
+        round_trip(i64::MIN + 1);
+        round_trip(i64::MIN);
+    }
+
+    #[test]
+    fn zero_encodes_to_single_zero_coefficient() {
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let pt = Signed::from(0).try_into_plaintext(&params).unwrap();
+
+        match &pt.inner {
+            InnerPlaintext::Seal(p) => {
+                assert_eq!(p.len(), 1);
+                assert_eq!(p[0].len(), 1);
+                assert_eq!(p[0].get_coefficient(0), 0);
+            }
+        }
+
+        assert_eq!(Signed::try_from_plaintext(&pt, &params).unwrap(), 0.into());
+    }
+
+    #[test]
+    fn decode_rejects_too_many_coefficients() {
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let len = params.lattice_dimension as usize + 1;
+
+        let mut seal_plaintext = SealPlaintext::new().unwrap();
+        seal_plaintext.resize(len);
+
+        let pt = Plaintext {
+            data_type: Signed::from(0).type_name_instance(),
+            inner: InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data: seal_plaintext,
+            }]),
+        };
+
+        match Signed::try_from_plaintext(&pt, &params) {
+            Err(sunscreen_runtime::Error::PlaintextTooLarge(d)) => {
+                assert_eq!(*d, (len, 4096));
+            }
+            x => panic!("Expected PlaintextTooLarge, got {:?}", x),
+        }
+    }
+
+    fn plaintext_with_coefficients(params: &Params, coefficients: &[(usize, u64)]) -> Plaintext {
+        let len = coefficients.iter().map(|(i, _)| i + 1).max().unwrap_or(1);
+
+        let mut seal_plaintext = SealPlaintext::new().unwrap();
+        seal_plaintext.resize(len);
+
+        for (i, c) in coefficients {
+            seal_plaintext.set_coefficient(*i, *c);
+        }
+
+        Plaintext {
+            data_type: Signed::from(0).type_name_instance(),
+            inner: InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data: seal_plaintext,
+            }]),
+        }
+    }
+
+    #[test]
+    fn decode_rejects_more_coefficients_than_lattice_dimension() {
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let pt = plaintext_with_coefficients(&params, &[(4096, 1)]);
+
+        match Signed::try_from_plaintext(&pt, &params) {
+            Err(sunscreen_runtime::Error::PlaintextTooLarge(d)) => {
+                assert_eq!(*d, (4097, 4096));
+            }
+            x => panic!("Expected PlaintextTooLarge, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn decode_rejects_values_beyond_i64() {
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let t = params.plain_modulus;
+
+        // 2^63, -2^63 - 1, and 3 * 2^62 previously wrapped silently.
+        for coefficients in [vec![(63, 1)], vec![(0, t - 1), (63, t - 1)], vec![(62, 3)]] {
+            let pt = plaintext_with_coefficients(&params, &coefficients);
+
+            assert!(matches!(
+                Signed::try_from_plaintext(&pt, &params),
+                Err(sunscreen_runtime::Error::ValueOutOfRange(_))
+            ));
+        }
+
+        // Extreme values that fit still decode.
+        let pt = plaintext_with_coefficients(&params, &[(63, t - 1)]);
+        assert_eq!(
+            Signed::try_from_plaintext(&pt, &params).unwrap(),
+            i64::MIN.into()
+        );
+
+        let pt = plaintext_with_coefficients(&params, &[(63, 1), (0, t - 1)]);
+        assert_eq!(
+            Signed::try_from_plaintext(&pt, &params).unwrap(),
+            i64::MAX.into()
+        );
+    }
+
+    #[test]
+    fn try_from_i64_checks_range() {
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 2,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        // Values above plain_modulus / 2 don't survive a plain modulus
+        // of 2.
+        for val in [1, 2, 1000, i64::MAX] {
+            assert!(matches!(
+                Signed::try_from_i64(val, &params),
+                Err(sunscreen_runtime::Error::ValueOutOfRange(_))
+            ));
+        }
+
+        for val in [0, -1, i64::MIN] {
+            let x = Signed::try_from_i64(val, &params).unwrap();
+            let pt = x.try_into_plaintext(&params).unwrap();
+
+            assert_eq!(Signed::try_from_plaintext(&pt, &params).unwrap(), x);
+        }
+
+        // Larger plain moduli represent every i64, including values
+        // outside is_in_range.
+        let params = Params {
+            plain_modulus: 1024,
+            ..params
+        };
+
+        assert_eq!(Signed::max_value(&params), i64::MAX);
+        assert_eq!(Signed::min_value(&params), i64::MIN);
+
+        for val in [i64::MAX, 300, 1, -300, i64::MIN] {
+            let x = Signed::try_from_i64(val, &params).unwrap();
+            let pt = x.try_into_plaintext(&params).unwrap();
+
+            assert_eq!(Signed::try_from_plaintext(&pt, &params).unwrap(), x);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "isn't representable")]
+    fn encoding_unrepresentable_value_asserts() {
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 2,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let _ = Signed::from(1).try_into_plaintext(&params);
+    }
 }
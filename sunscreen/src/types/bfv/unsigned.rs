@@ -11,7 +11,7 @@ use sunscreen_runtime::{
 use crate as sunscreen;
 use crate::types::ops::GraphCipherInsert;
 use crate::{
-    fhe::{with_fhe_ctx, FheContextOps},
+    fhe::{encode_literal, with_fhe_ctx, FheContextOps},
     types::{
         ops::{
             GraphCipherAdd, GraphCipherConstAdd, GraphCipherConstMul, GraphCipherConstSub,
@@ -81,10 +81,25 @@ impl<const LIMBS: usize> TryIntoPlaintext for Unsigned<LIMBS> {
 }
 
 impl<const LIMBS: usize> TryFromPlaintext for Unsigned<LIMBS> {
+    /**
+     * Decodes the value from the plaintext's first `64 * LIMBS`
+     * coefficients.
+     *
+     * # Remarks
+     * Returns [`PlaintextTooLarge`](sunscreen_runtime::Error::PlaintextTooLarge)
+     * if the plaintext has more coefficients than the lattice dimension.
+     *
+     * Unlike [`Signed`](crate::types::bfv::Signed), values outside
+     * `[0, 2^(64 * LIMBS))` aren't an error: [`Unsigned`] arithmetic
+     * wraps like [`Uint`]'s `wrapping_*` operations, so e.g. `0 - 1`
+     * decodes to the maximum value.
+     */
     fn try_from_plaintext(
         plaintext: &Plaintext,
         params: &Params,
     ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        plaintext.inner.check_coefficient_count(params)?;
+
         let val = match &plaintext.inner {
             InnerPlaintext::Seal(p) => {
                 if p.len() != 1 {
@@ -283,8 +298,13 @@ impl<const LIMBS: usize> GraphCipherInsert for Unsigned<LIMBS> {
 
     fn graph_cipher_insert(lit: Self::Lit) -> FheProgramNode<Self::Val> {
         with_fhe_ctx(|ctx| {
-            let lit = Self::from(lit).try_into_plaintext(&ctx.data).unwrap();
-            let lit = ctx.add_plaintext_literal(lit.inner);
+            #[allow(clippy::unnecessary_cast)]
+            let words = lit.as_words().iter().map(|w| *w as u64).collect::<Vec<_>>();
+
+            let lit = encode_literal::<Self, _>(&words, &ctx.data, || {
+                Self::from(lit).try_into_plaintext(&ctx.data).unwrap().inner
+            });
+            let lit = ctx.add_plaintext_literal(lit);
 
             FheProgramNode::new(&[lit])
         })
@@ -373,4 +393,36 @@ mod tests {
         assert_eq!(b - a, 6.into());
         assert_eq!(b - 5, 6.into());
     }
+
+    #[test]
+    fn decode_rejects_more_coefficients_than_lattice_dimension() {
+        use crate::{SchemeType, SecurityLevel};
+
+        let params = Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let mut seal_plaintext = SealPlaintext::new().unwrap();
+        seal_plaintext.resize(4097);
+        seal_plaintext.set_coefficient(4096, 1);
+
+        let pt = Plaintext {
+            data_type: Unsigned64::from(0).type_name_instance(),
+            inner: InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data: seal_plaintext,
+            }]),
+        };
+
+        match Unsigned64::try_from_plaintext(&pt, &params) {
+            Err(sunscreen_runtime::Error::PlaintextTooLarge(d)) => {
+                assert_eq!(*d, (4097, 4096));
+            }
+            x => panic!("Expected PlaintextTooLarge, got {:?}", x),
+        }
+    }
 }
@@ -0,0 +1,252 @@
+use seal_fhe::{
+    CKKSEncoder, CkksEncryptionParametersBuilder, Context as SealContext, Modulus,
+    Result as SealResult,
+};
+
+use crate as sunscreen;
+use crate::{
+    fhe::{with_fhe_ctx, FheContextOps},
+    types::{
+        intern::FheProgramNode,
+        ops::{GraphCipherAdd, GraphCipherMul},
+        Cipher, CkksType, FheType, TypeNameInstance,
+    },
+    FheProgramInputTrait, Params, SchemeType, TypeName as DeriveTypeName, WithContext,
+};
+
+use sunscreen_runtime::{
+    Error as RuntimeError, InnerPlaintext, NumCiphertexts, Plaintext, TryFromPlaintext,
+    TryIntoPlaintext,
+};
+
+#[derive(Debug, Clone, Copy, DeriveTypeName, PartialEq, Default)]
+/**
+ * A single complex number under the experimental CKKS scheme.
+ *
+ * # Remarks
+ * Values encode into every slot of a CKKS plaintext at a scale of
+ * `2^b`, where `b` is the bit width of the second to last coefficient
+ * modulus. The parameters should thus use equally sized middle moduli,
+ * e.g. `[60, 40, 40, 60]` bits, so each multiplication's rescale
+ * returns the product to roughly this scale.
+ *
+ * CKKS is approximate: decrypted values differ slightly from the exact
+ * result.
+ */
+pub struct Complex {
+    /**
+     * The real part.
+     */
+    pub re: f64,
+
+    /**
+     * The imaginary part.
+     */
+    pub im: f64,
+}
+
+impl NumCiphertexts for Complex {
+    const NUM_CIPHERTEXTS: usize = 1;
+}
+
+impl FheProgramInputTrait for Complex {}
+impl FheType for Complex {}
+impl CkksType for Complex {}
+
+impl std::fmt::Display for Complex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.im < 0. {
+            write!(f, "{}-{}i", self.re, -self.im)
+        } else {
+            write!(f, "{}+{}i", self.re, self.im)
+        }
+    }
+}
+
+impl Complex {
+    /**
+     * Creates the complex number `re + im * i`.
+     */
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+}
+
+impl From<(f64, f64)> for Complex {
+    fn from((re, im): (f64, f64)) -> Self {
+        Self { re, im }
+    }
+}
+
+impl From<f64> for Complex {
+    fn from(re: f64) -> Self {
+        Self { re, im: 0. }
+    }
+}
+
+/**
+ * Creates a CKKS encoder for `params`.
+ */
+fn encoder(params: &Params) -> std::result::Result<CKKSEncoder, RuntimeError> {
+    if params.scheme_type != SchemeType::Ckks {
+        return Err(RuntimeError::fhe_type_error(
+            "Complex requires the CKKS scheme",
+        ));
+    }
+
+    let encryption_params = CkksEncryptionParametersBuilder::new()
+        .set_poly_modulus_degree(params.lattice_dimension)
+        .set_coefficient_modulus(
+            params
+                .coeff_modulus
+                .iter()
+                .map(|x| Modulus::new(*x))
+                .collect::<SealResult<Vec<Modulus>>>()?,
+        )
+        .build()?;
+
+    let context = SealContext::new(&encryption_params, true, params.security_level)?;
+
+    Ok(CKKSEncoder::new(&context)?)
+}
+
+/**
+ * The scale at which fresh values get encoded.
+ */
+fn scale(params: &Params) -> std::result::Result<f64, RuntimeError> {
+    let len = params.coeff_modulus.len();
+
+    if len < 2 {
+        return Err(RuntimeError::fhe_type_error(
+            "CKKS requires at least 2 coefficient moduli",
+        ));
+    }
+
+    let bits = u64::BITS - params.coeff_modulus[len - 2].leading_zeros();
+
+    Ok(f64::powi(2., bits as i32))
+}
+
+impl TryIntoPlaintext for Complex {
+    fn try_into_plaintext(
+        &self,
+        params: &Params,
+    ) -> std::result::Result<Plaintext, sunscreen_runtime::Error> {
+        let encoder = encoder(params)?;
+
+        let data = vec![(self.re, self.im); encoder.get_slot_count()];
+        let plaintext = encoder.encode_complex(&data, scale(params)?)?;
+
+        Ok(Plaintext {
+            data_type: self.type_name_instance(),
+            inner: InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data: plaintext,
+            }]),
+        })
+    }
+}
+
+impl TryFromPlaintext for Complex {
+    fn try_from_plaintext(
+        plaintext: &Plaintext,
+        params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        // CKKS plaintexts are in NTT form with a coefficient per slot and
+        // modulus, so the BFV coefficient count bound doesn't apply.
+        let plaintext = plaintext.inner_as_seal_plaintext()?;
+
+        if plaintext.len() != 1 {
+            return Err(RuntimeError::IncorrectCiphertextCount);
+        }
+
+        if plaintext[0].params != *params {
+            return Err(RuntimeError::ParameterMismatch);
+        }
+
+        let data = encoder(params)?.decode_complex(&plaintext[0].data)?;
+
+        let (re, im) = data
+            .first()
+            .copied()
+            .ok_or_else(|| RuntimeError::fhe_type_error("Empty CKKS plaintext"))?;
+
+        Ok(Self { re, im })
+    }
+}
+
+impl GraphCipherAdd for Complex {
+    type Left = Complex;
+    type Right = Complex;
+
+    fn graph_cipher_add(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_addition(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherMul for Complex {
+    type Left = Complex;
+    type Right = Complex;
+
+    fn graph_cipher_mul(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_multiplication(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecurityLevel;
+    use seal_fhe::CoefficientModulus;
+
+    fn params() -> Params {
+        Params {
+            lattice_dimension: 8192,
+            coeff_modulus: CoefficientModulus::create(8192, &[60, 40, 40, 60])
+                .unwrap()
+                .iter()
+                .map(|m| m.value())
+                .collect(),
+            plain_modulus: 0,
+            scheme_type: SchemeType::Ckks,
+            security_level: SecurityLevel::TC128,
+        }
+    }
+
+    #[test]
+    fn can_roundtrip_plaintext() {
+        let params = params();
+        let x = Complex::new(-1.5, 2.25);
+
+        let decoded =
+            Complex::try_from_plaintext(&x.try_into_plaintext(&params).unwrap(), &params).unwrap();
+
+        assert!((decoded.re - x.re).abs() < 1e-6);
+        assert!((decoded.im - x.im).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_bfv_params() {
+        let params = Params {
+            scheme_type: SchemeType::Bfv,
+            plain_modulus: 1024,
+            ..params()
+        };
+
+        assert!(Complex::new(1., 0.).try_into_plaintext(&params).is_err());
+    }
+}
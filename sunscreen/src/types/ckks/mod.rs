@@ -0,0 +1,3 @@
+mod complex;
+
+pub use complex::*;
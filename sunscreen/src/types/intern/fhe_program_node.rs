@@ -1,8 +1,8 @@
 use crate::{
     fhe::with_fhe_ctx,
     types::{
-        intern::FheLiteral, ops::*, Cipher, FheType, LaneCount, NumCiphertexts, SwapRows, Type,
-        TypeName,
+        bfv::Signed, intern::FheLiteral, ops::*, Cipher, FheType, LaneCount, NumCiphertexts,
+        Rotate, SwapRows, Type, TypeName,
     },
     INDEX_ARENA,
 };
@@ -10,7 +10,7 @@ use paste::paste;
 use petgraph::stable_graph::NodeIndex;
 use sunscreen_runtime::TypeNameInstance;
 
-use std::ops::{Add, Div, Mul, Neg, Shl, Shr, Sub};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Shl, Shr, Sub};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 /**
@@ -463,6 +463,54 @@ where
     }
 }
 
+// cipher & cipher
+impl<T> BitAnd for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherBitAnd<Left = T, Right = T>,
+{
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        T::graph_cipher_bitand(self, rhs)
+    }
+}
+
+// cipher | cipher
+impl<T> BitOr for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherBitOr<Left = T, Right = T>,
+{
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        T::graph_cipher_bitor(self, rhs)
+    }
+}
+
+// cipher ^ cipher
+impl<T> BitXor for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherBitXor<Left = T, Right = T>,
+{
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        T::graph_cipher_bitxor(self, rhs)
+    }
+}
+
+// !ciphertext
+impl<T> Not for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherNot<Val = T>,
+{
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        T::graph_cipher_not(self)
+    }
+}
+
 // ciphertext
 impl<T> SwapRows for FheProgramNode<Cipher<T>>
 where
@@ -506,6 +554,120 @@ where
     }
 }
 
+// Shifting uses i32 amounts so literals like `a >> 2` don't conflict
+// with rotation's u64 amounts.
+impl Shl<i32> for FheProgramNode<Cipher<Signed>> {
+    type Output = Self;
+
+    fn shl(self, x: i32) -> Self {
+        Signed::graph_cipher_shl(self, x)
+    }
+}
+
+impl Shr<i32> for FheProgramNode<Cipher<Signed>> {
+    type Output = Self;
+
+    fn shr(self, x: i32) -> Self {
+        Signed::graph_cipher_shr(self, x)
+    }
+}
+
+impl<T> FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherRotateLeft,
+{
+    /**
+     * Rotates each row `n` places to the left. Equivalent to `self << n`.
+     */
+    pub fn rotate_left(self, n: u64) -> Self {
+        T::graph_cipher_rotate_left(self, n)
+    }
+}
+
+impl<T> FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherRotateRight,
+{
+    /**
+     * Rotates each row `n` places to the right. Equivalent to `self >> n`.
+     */
+    pub fn rotate_right(self, n: u64) -> Self {
+        T::graph_cipher_rotate_right(self, n)
+    }
+}
+
+impl<T> FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherConstPow,
+{
+    /**
+     * Raises `self` to the constant power `n`. See
+     * [`GraphCipherConstPow`] for the types that support this and how
+     * many multiplications it costs.
+     */
+    pub fn pow(self, n: u32) -> Self {
+        T::graph_cipher_const_pow(self, n)
+    }
+}
+
+impl<T> FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherEq,
+{
+    /**
+     * Returns an encryption of 1 where `self` and `other` are equal and
+     * 0 elsewhere. See [`GraphCipherEq`] for the types that support
+     * this and any restrictions on the parameters.
+     */
+    pub fn equals(self, other: Self) -> Self {
+        T::graph_cipher_eq(self, other)
+    }
+}
+
+impl<T> FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherMask,
+{
+    /**
+     * Zeroes the slots `pattern` doesn't select, preserving the rest.
+     * The pattern is public and costs a single plaintext
+     * multiplication. See [`GraphCipherMask`] for the types that
+     * support this.
+     */
+    pub fn mask(self, pattern: &T::Pattern) -> Self {
+        T::graph_cipher_mask(self, pattern)
+    }
+}
+
+impl<T> FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherConstVecMul,
+{
+    /**
+     * Multiplies each slot by the corresponding public scalar in a
+     * single plaintext multiplication. See [`GraphCipherConstVecMul`]
+     * for the types that support this and how `scalars` maps to slots.
+     */
+    pub fn mul_slots(self, scalars: &[i64]) -> Self {
+        T::graph_cipher_const_vec_mul(self, scalars)
+    }
+}
+
+impl<T> Rotate for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherRotateLeft + GraphCipherRotateRight,
+{
+    type Output = Self;
+
+    fn rotate(self, n: i32) -> Self {
+        if n >= 0 {
+            T::graph_cipher_rotate_left(self, n as u64)
+        } else {
+            T::graph_cipher_rotate_right(self, n.unsigned_abs() as u64)
+        }
+    }
+}
+
 impl<T, S> NumCiphertexts for FheProgramNode<T, S>
 where
     T: NumCiphertexts,
@@ -20,6 +20,10 @@
  * allows for somewhat efficiently representing integers, but has unusual
  * overflow semantics developers need to understand. This type supports
  * addition, subtraction, multiplication, and negation.
+ * * The [`UnsignedN`](crate::types::bfv::UnsignedN) type encodes unsigned
+ * integers like [`Unsigned`](crate::types::bfv::Unsigned), but limited to a
+ * given bit width, so small values use fewer coefficients. This type
+ * supports addition and multiplication.
  * * The [`Fractional`](crate::types::bfv::Fractional) type is a quasi fixed-point
  * value. It allows you to homomorphically compute decimal values as
  * efficiently as the [`Signed`](crate::types::bfv::Signed) type. This type has complex overflow
@@ -34,6 +38,21 @@
  * requires 2 multiplications for addition and subtraction. Unlike other types,
  * [`Rational`](crate::types::bfv::Rational) supports ciphertext-ciphertext
  * division.
+ * * The [`Fixed`](crate::types::bfv::Fixed) type is a fixed-point value with
+ * a fixed number of integer and fractional bits. It shares
+ * [`Fractional`](crate::types::bfv::Fractional)'s encoding, but rounds to
+ * its fractional precision when decrypted.
+ * * The [`ChecksummedSigned`](crate::types::bfv::ChecksummedSigned) type
+ * encodes like [`Signed`](crate::types::bfv::Signed), but spends an extra
+ * coefficient on a checksum so decryption can detect results noise
+ * corrupted. This type supports addition, subtraction, and negation.
+ * * The [`Boolean`](crate::types::bfv::Boolean) type is a single
+ * encrypted bit. This type supports `&`, `|`, `^`, and `!`, and works with
+ * a plain modulus as small as 2.
+ * * The [`EncryptedPolynomial`](crate::types::bfv::EncryptedPolynomial) type
+ * holds a polynomial with each coefficient in its own ciphertext. This type
+ * supports coefficient-wise addition and multiplication and evaluation at an
+ * encrypted point.
  * * The [`Batched`](crate::types::bfv::Batched) type packs thousands of signed integers
  * into lanes by exploiting the Chinese remainder theorem for cyclotomic polynomials.
  * Arithmetic operations semantically execute per-lane, enabling high-throughput;
@@ -53,9 +72,32 @@
  * example, comparisons, modulus, transcendentals, are generally very difficult
  * and are often infeasible depending on scheme parameters and noise budget.
  * One can sometimes *approximate* operations using Lagrange interpolation.
+ *
+ * These types also work under the experimental BGV scheme, which shares
+ * BFV's plaintexts. FHE programs using BGV must be compiled with manually
+ * chosen parameters whose scheme type is
+ * [`SchemeType::Bgv`](crate::SchemeType::Bgv).
  */
 pub mod bfv;
 
+/**
+ * This module contains types you can use as inputs and outputs from FHE
+ * programs using the experimental CKKS scheme.
+ *
+ * # CKKS Scheme types
+ * The CKKS scheme computes approximately on real and complex numbers.
+ * Results carry small errors that grow with each operation, so compare
+ * them against an epsilon rather than for equality.
+ *
+ * * The [`Complex`](crate::types::ckks::Complex) type represents a single
+ * complex number. This type supports addition and multiplication.
+ *
+ * # Remarks
+ * FHE programs using CKKS must be compiled with manually chosen
+ * parameters whose scheme type is [`SchemeType::Ckks`](crate::SchemeType::Ckks).
+ */
+pub mod ckks;
+
 /**
  * This module contains implementation details used to support
  * Sunscreen's domain specific language under the
@@ -77,7 +119,7 @@ pub mod zkp;
 use crate::types::ops::*;
 
 pub use sunscreen_runtime::{
-    BfvType, FheType, NumCiphertexts, TryFromPlaintext, TryIntoPlaintext, Type, TypeName,
+    BfvType, CkksType, FheType, NumCiphertexts, TryFromPlaintext, TryIntoPlaintext, Type, TypeName,
     TypeNameInstance, Version,
 };
 
@@ -96,6 +138,22 @@ pub trait SwapRows {
     fn swap_rows(self) -> Self::Output;
 }
 
+/**
+ * A trait that allows data types to rotate their lanes. E.g. [`Batched`](crate::types::bfv::Batched)
+ */
+pub trait Rotate {
+    /**
+     * The result type. Typically, this should just be `Self`.
+     */
+    type Output;
+
+    /**
+     * Rotates each row `n` places. Positive `n` rotates left while
+     * negative `n` rotates right.
+     */
+    fn rotate(self, n: i32) -> Self::Output;
+}
+
 /**
  * On Batched types, returns the number of Batched lanes.
  */
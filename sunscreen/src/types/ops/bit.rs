@@ -0,0 +1,97 @@
+use crate::types::{intern::FheProgramNode, Cipher, FheType};
+
+/**
+ * Called when an Fhe Program encounters a & operation on two encrypted types.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherBitAnd {
+    /**
+     * The type of the left operand
+     */
+    type Left: FheType;
+
+    /**
+     * The type of the right operand
+     */
+    type Right: FheType;
+
+    /**
+     * Process the & operation
+     */
+    fn graph_cipher_bitand(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>>;
+}
+
+/**
+ * Called when an Fhe Program encounters a | operation on two encrypted types.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherBitOr {
+    /**
+     * The type of the left operand
+     */
+    type Left: FheType;
+
+    /**
+     * The type of the right operand
+     */
+    type Right: FheType;
+
+    /**
+     * Process the | operation
+     */
+    fn graph_cipher_bitor(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>>;
+}
+
+/**
+ * Called when an Fhe Program encounters a ^ operation on two encrypted types.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherBitXor {
+    /**
+     * The type of the left operand
+     */
+    type Left: FheType;
+
+    /**
+     * The type of the right operand
+     */
+    type Right: FheType;
+
+    /**
+     * Process the ^ operation
+     */
+    fn graph_cipher_bitxor(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>>;
+}
+
+/**
+ * Called when the user performs logical negation (!) on a ciphertext.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherNot {
+    /**
+     * The unary type.
+     */
+    type Val: FheType;
+
+    /**
+     * Negates the given ciphertext (e.g. !x).
+     */
+    fn graph_cipher_not(a: FheProgramNode<Cipher<Self::Val>>) -> FheProgramNode<Cipher<Self::Val>>;
+}
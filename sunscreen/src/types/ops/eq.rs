@@ -0,0 +1,22 @@
+use crate::types::{intern::FheProgramNode, Cipher, FheType};
+
+/**
+ * Called when an Fhe Program compares two encrypted values for
+ * equality.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherEq
+where
+    Self: FheType,
+{
+    /**
+     * Returns an encryption of 1 where `a` and `b` are equal and 0
+     * elsewhere.
+     */
+    fn graph_cipher_eq(
+        a: FheProgramNode<Cipher<Self>>,
+        b: FheProgramNode<Cipher<Self>>,
+    ) -> FheProgramNode<Cipher<Self>>;
+}
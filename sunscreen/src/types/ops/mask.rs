@@ -0,0 +1,26 @@
+use crate::types::{intern::FheProgramNode, Cipher, FheType};
+
+/**
+ * Called when an Fhe Program zeroes selected slots of a ciphertext
+ * according to a public pattern.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherMask
+where
+    Self: FheType,
+{
+    /**
+     * The public pattern selecting which slots to keep.
+     */
+    type Pattern: ?Sized;
+
+    /**
+     * Returns `a` with the slots `pattern` doesn't select set to 0.
+     */
+    fn graph_cipher_mask(
+        a: FheProgramNode<Cipher<Self>>,
+        pattern: &Self::Pattern,
+    ) -> FheProgramNode<Cipher<Self>>;
+}
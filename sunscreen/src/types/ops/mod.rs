@@ -1,15 +1,25 @@
 mod add;
+mod bit;
 mod div;
+mod eq;
 mod insert;
+mod mask;
 mod mul;
 mod neg;
+mod pow;
 mod rotate;
+mod shift;
 mod sub;
 
 pub use add::*;
+pub use bit::*;
 pub use div::*;
+pub use eq::*;
 pub use insert::*;
+pub use mask::*;
 pub use mul::*;
 pub use neg::*;
+pub use pow::*;
 pub use rotate::*;
+pub use shift::*;
 pub use sub::*;
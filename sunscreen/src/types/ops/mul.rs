@@ -82,3 +82,23 @@ pub trait GraphCipherConstMul {
         b: Self::Right,
     ) -> FheProgramNode<Cipher<Self::Left>>;
 }
+
+/**
+ * Called when an Fhe Program multiplies each slot of an encrypted
+ * batched type by its own literal.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherConstVecMul
+where
+    Self: FheType,
+{
+    /**
+     * Multiplies each slot of `a` by the corresponding element of `b`.
+     */
+    fn graph_cipher_const_vec_mul(
+        a: FheProgramNode<Cipher<Self>>,
+        b: &[i64],
+    ) -> FheProgramNode<Cipher<Self>>;
+}
@@ -0,0 +1,20 @@
+use crate::types::{intern::FheProgramNode, Cipher, FheType};
+
+/**
+ * Raises the value in the given ciphertext to a constant power.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherConstPow
+where
+    Self: FheType,
+{
+    /**
+     * Compute `x^n`.
+     */
+    fn graph_cipher_const_pow(
+        x: FheProgramNode<Cipher<Self>>,
+        n: u32,
+    ) -> FheProgramNode<Cipher<Self>>;
+}
@@ -0,0 +1,28 @@
+use crate::types::{intern::FheProgramNode, Cipher, FheType};
+
+/**
+ * Shifts the bits of the value in the given ciphertext.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherShift
+where
+    Self: FheType,
+{
+    /**
+     * Shift the value left `amount` bits.
+     */
+    fn graph_cipher_shl(
+        x: FheProgramNode<Cipher<Self>>,
+        amount: i32,
+    ) -> FheProgramNode<Cipher<Self>>;
+
+    /**
+     * Shift the value right `amount` bits.
+     */
+    fn graph_cipher_shr(
+        x: FheProgramNode<Cipher<Self>>,
+        amount: i32,
+    ) -> FheProgramNode<Cipher<Self>>;
+}
@@ -46,6 +46,41 @@ fn can_add_array_elements() {
     assert_eq!(c, a + b);
 }
 
+#[test]
+fn can_sum_8_element_array() {
+    #[fhe_program(scheme = "bfv")]
+    fn sum(x: [Cipher<Signed>; 8]) -> Cipher<Signed> {
+        let mut sum = x[0];
+
+        for i in 1..8 {
+            sum = sum + x[i];
+        }
+
+        sum
+    }
+
+    let app = Compiler::new()
+        .fhe_program(sum)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let x = [1, -2, 3, 4, -5, 6, 7, 8].map(Signed::from);
+    let x_c = runtime.encrypt(x, &public_key).unwrap();
+
+    let result = runtime
+        .run(app.get_fhe_program(sum).unwrap(), vec![x_c], &public_key)
+        .unwrap();
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, Signed::from(22));
+}
+
 #[test]
 fn multidimensional_arrays() {
     fn determinant_impl<T, U, V>(x: T) -> V
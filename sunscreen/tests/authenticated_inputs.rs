@@ -0,0 +1,51 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, Runtime, RuntimeError,
+};
+
+#[fhe_program(scheme = "bfv")]
+fn double(a: Cipher<Signed>) -> Cipher<Signed> {
+    a + a
+}
+
+#[test]
+fn unauthenticated_ciphertexts_are_rejected() {
+    let app = Compiler::new().fhe_program(double).compile().unwrap();
+    let program = app.get_fhe_program(double).unwrap();
+
+    let runtime = Runtime::new_fhe(app.params())
+        .unwrap()
+        .require_authenticated_inputs(true);
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    // Locally encrypted ciphertexts and this runtime's own outputs pass.
+    let a = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+    let b = runtime.run(program, vec![a], &public_key).unwrap();
+    let c = runtime.run(program, b, &public_key).unwrap();
+
+    let c: Signed = runtime.decrypt(&c[0], &private_key).unwrap();
+    assert_eq!(c, Signed::from(12));
+
+    // A ciphertext encrypted elsewhere under the same key has no tag.
+    let external = Runtime::new_fhe(app.params()).unwrap();
+    let a = external.encrypt(Signed::from(3), &public_key).unwrap();
+
+    assert!(a.authentication_tag.is_none());
+
+    match runtime.run(program, vec![a], &public_key) {
+        Err(RuntimeError::UnauthenticatedCiphertext) => {}
+        x => panic!("Expected UnauthenticatedCiphertext, got {:?}", x.err()),
+    }
+
+    // Copying a valid tag onto different ciphertext data doesn't help.
+    let tagged = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+    let mut forged = external.encrypt(Signed::from(4), &public_key).unwrap();
+    forged.authentication_tag = tagged.authentication_tag;
+
+    assert!(matches!(
+        runtime.run(program, vec![forged], &public_key),
+        Err(RuntimeError::UnauthenticatedCiphertext)
+    ));
+}
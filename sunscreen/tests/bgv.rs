@@ -0,0 +1,161 @@
+use seal_fhe::CoefficientModulus;
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, Error, FheProgramFn, Params, Runtime, SchemeType, SecurityLevel,
+};
+use sunscreen_fhe_program::Operation;
+
+#[fhe_program(scheme = "bgv")]
+fn mul(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a * b
+}
+
+#[fhe_program(scheme = "bgv")]
+fn add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a + b
+}
+
+#[fhe_program(scheme = "bgv")]
+fn mul_add(a: Cipher<Signed>, b: Cipher<Signed>, c: Cipher<Signed>) -> Cipher<Signed> {
+    a * b + c
+}
+
+#[fhe_program(scheme = "bgv")]
+fn mul_mul(a: Cipher<Signed>, b: Cipher<Signed>, c: Cipher<Signed>) -> Cipher<Signed> {
+    (a * b) * c
+}
+
+#[fhe_program(scheme = "bgv")]
+fn mul_mul_mul(a: Cipher<Signed>, b: Cipher<Signed>, c: Cipher<Signed>) -> Cipher<Signed> {
+    ((a * b) * c) * a
+}
+
+fn params() -> Params {
+    Params {
+        lattice_dimension: 8192,
+        coeff_modulus: CoefficientModulus::create(8192, &[60, 40, 40, 60])
+            .unwrap()
+            .iter()
+            .map(|x| x.value())
+            .collect(),
+        plain_modulus: 1024,
+        scheme_type: SchemeType::Bgv,
+        security_level: SecurityLevel::TC128,
+    }
+}
+
+fn run<F>(program: F, a: i64, b: i64) -> i64
+where
+    F: FheProgramFn + Clone + 'static,
+{
+    let app = Compiler::new()
+        .fhe_program(program.clone())
+        .with_params(&params())
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(a), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(b), &public_key).unwrap();
+
+    let result = runtime
+        .run(
+            app.get_fhe_program(program.name()).unwrap(),
+            vec![a, b],
+            &public_key,
+        )
+        .unwrap();
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    c.into()
+}
+
+#[test]
+fn can_multiply_signed() {
+    assert_eq!(run(mul, 15, -5), -75);
+}
+
+#[test]
+fn can_add_signed() {
+    assert_eq!(run(add, 15, -5), 10);
+}
+
+#[test]
+fn can_combine_operands_at_different_levels() {
+    let app = Compiler::new()
+        .fhe_program(mul_add)
+        .fhe_program(mul_mul)
+        .with_params(&params())
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let encrypt = |x: i64| runtime.encrypt(Signed::from(x), &public_key).unwrap();
+
+    for (program, expected) in [(mul_add.name(), -68), (mul_mul.name(), -525)] {
+        let result = runtime
+            .run(
+                app.get_fhe_program(program).unwrap(),
+                vec![encrypt(15), encrypt(-5), encrypt(7)],
+                &public_key,
+            )
+            .unwrap();
+
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(i64::from(c), expected);
+    }
+}
+
+#[test]
+fn compiler_inserts_mod_switches() {
+    let app = Compiler::new()
+        .fhe_program(mul_add)
+        .with_params(&params())
+        .compile()
+        .unwrap();
+
+    let ir = &app.get_fhe_program(mul_add.name()).unwrap().fhe_program_fn;
+
+    // One after the product and one bringing `c` down to its level.
+    assert_eq!(
+        ir.graph
+            .node_weights()
+            .filter(|n| n.operation == Operation::ModSwitch)
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn rejects_programs_deeper_than_the_modulus_chain() {
+    let result = Compiler::new()
+        .fhe_program(mul_mul_mul)
+        .with_params(&params())
+        .compile();
+
+    assert!(matches!(result, Err(Error::InvalidParams(_))));
+}
+
+#[test]
+fn bgv_programs_require_manual_params() {
+    let result = Compiler::new().fhe_program(mul).compile();
+
+    assert!(matches!(result, Err(Error::IncorrectScheme)));
+
+    let result = Compiler::new()
+        .fhe_program(mul)
+        .with_params(&Params {
+            scheme_type: SchemeType::Bfv,
+            ..params()
+        })
+        .compile();
+
+    assert!(matches!(result, Err(Error::IncorrectScheme)));
+}
@@ -0,0 +1,60 @@
+use sunscreen::{types::bfv::Boolean, types::Cipher, *};
+
+#[test]
+fn logical_operators_match_truth_tables() {
+    #[fhe_program(scheme = "bfv")]
+    fn and(a: Cipher<Boolean>, b: Cipher<Boolean>) -> Cipher<Boolean> {
+        a & b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn or(a: Cipher<Boolean>, b: Cipher<Boolean>) -> Cipher<Boolean> {
+        a | b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn xor(a: Cipher<Boolean>, b: Cipher<Boolean>) -> Cipher<Boolean> {
+        a ^ b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn not(a: Cipher<Boolean>) -> Cipher<Boolean> {
+        !a
+    }
+
+    let app = Compiler::new()
+        .fhe_program(and)
+        .fhe_program(or)
+        .fhe_program(xor)
+        .fhe_program(not)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(2))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let run = |program: &CompiledFheProgram, args: &[bool]| -> bool {
+        let args = args
+            .iter()
+            .map(|x| runtime.encrypt(Boolean::from(*x), &public_key).unwrap())
+            .collect::<Vec<_>>();
+
+        let result = runtime.run(program, args, &public_key).unwrap();
+
+        let c: Boolean = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        c.into()
+    };
+
+    for a in [false, true] {
+        for b in [false, true] {
+            assert_eq!(run(app.get_fhe_program(and).unwrap(), &[a, b]), a & b);
+            assert_eq!(run(app.get_fhe_program(or).unwrap(), &[a, b]), a | b);
+            assert_eq!(run(app.get_fhe_program(xor).unwrap(), &[a, b]), a ^ b);
+        }
+
+        assert_eq!(run(app.get_fhe_program(not).unwrap(), &[a]), !a);
+    }
+}
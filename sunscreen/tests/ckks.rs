@@ -0,0 +1,148 @@
+use seal_fhe::CoefficientModulus;
+use sunscreen::{
+    fhe_program,
+    types::{ckks::Complex, Cipher},
+    Compiler, Error, FheProgramFn, Params, Runtime, RuntimeError, SchemeType, SecurityLevel,
+};
+
+const EPSILON: f64 = 1e-3;
+
+#[fhe_program(scheme = "ckks")]
+fn mul(a: Cipher<Complex>, b: Cipher<Complex>) -> Cipher<Complex> {
+    a * b
+}
+
+#[fhe_program(scheme = "ckks")]
+fn add(a: Cipher<Complex>, b: Cipher<Complex>) -> Cipher<Complex> {
+    a + b
+}
+
+#[fhe_program(scheme = "ckks")]
+fn square_product(a: Cipher<Complex>, b: Cipher<Complex>) -> Cipher<Complex> {
+    let c = a * b;
+
+    c * c
+}
+
+#[fhe_program(scheme = "ckks")]
+fn mul_add(a: Cipher<Complex>, b: Cipher<Complex>, c: Cipher<Complex>) -> Cipher<Complex> {
+    a * b + c
+}
+
+#[fhe_program(scheme = "ckks")]
+fn mul_mul(a: Cipher<Complex>, b: Cipher<Complex>, c: Cipher<Complex>) -> Cipher<Complex> {
+    (a * b) * c
+}
+
+fn params() -> Params {
+    Params {
+        lattice_dimension: 8192,
+        coeff_modulus: CoefficientModulus::create(8192, &[60, 40, 40, 60])
+            .unwrap()
+            .iter()
+            .map(|x| x.value())
+            .collect(),
+        // CKKS has no plaintext modulus.
+        plain_modulus: 0,
+        scheme_type: SchemeType::Ckks,
+        security_level: SecurityLevel::TC128,
+    }
+}
+
+fn run<F>(program: F, a: Complex, b: Complex) -> Complex
+where
+    F: FheProgramFn + Clone + 'static,
+{
+    let app = Compiler::new()
+        .fhe_program(program.clone())
+        .with_params(&params())
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(a, &public_key).unwrap();
+    let b = runtime.encrypt(b, &public_key).unwrap();
+
+    let result = runtime
+        .run(
+            app.get_fhe_program(program.name()).unwrap(),
+            vec![a, b],
+            &public_key,
+        )
+        .unwrap();
+
+    runtime.decrypt(&result[0], &private_key).unwrap()
+}
+
+#[test]
+fn can_multiply_complex_numbers() {
+    let c = run(mul, Complex::new(3., 4.), Complex::new(1., 0.));
+
+    assert!((c.re - 3.).abs() < EPSILON, "{c}");
+    assert!((c.im - 4.).abs() < EPSILON, "{c}");
+
+    // (3 + 4i)(1 - 2i) = 11 - 2i
+    let c = run(mul, Complex::new(3., 4.), Complex::new(1., -2.));
+
+    assert!((c.re - 11.).abs() < EPSILON, "{c}");
+    assert!((c.im + 2.).abs() < EPSILON, "{c}");
+}
+
+#[test]
+fn can_add_complex_numbers() {
+    let c = run(add, Complex::new(3., 4.), Complex::new(-0.5, 2.));
+
+    assert!((c.re - 2.5).abs() < EPSILON, "{c}");
+    assert!((c.im - 6.).abs() < EPSILON, "{c}");
+}
+
+#[test]
+fn can_multiply_products() {
+    // ((1 + i) * 2)^2 = 8i
+    let c = run(square_product, Complex::new(1., 1.), Complex::new(2., 0.));
+
+    assert!(c.re.abs() < EPSILON, "{c}");
+    assert!((c.im - 8.).abs() < EPSILON, "{c}");
+}
+
+#[test]
+fn rejects_operands_at_different_levels() {
+    let result = Compiler::new()
+        .fhe_program(mul_add)
+        .with_params(&params())
+        .compile();
+
+    assert!(matches!(result, Err(Error::Unsupported(_))));
+
+    let result = Compiler::new()
+        .fhe_program(mul_mul)
+        .with_params(&params())
+        .compile();
+
+    assert!(matches!(result, Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn ckks_programs_require_manual_params() {
+    let result = Compiler::new().fhe_program(mul).compile();
+
+    assert!(matches!(result, Err(Error::IncorrectScheme)));
+}
+
+#[test]
+fn validate_rejects_ckks() {
+    let app = Compiler::new()
+        .fhe_program(mul)
+        .with_params(&params())
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    assert_eq!(
+        runtime.validate(app.get_fhe_program(mul.name()).unwrap()),
+        Err(RuntimeError::UnsupportedNoiseEstimate(SchemeType::Ckks))
+    );
+}
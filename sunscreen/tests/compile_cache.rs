@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::PathBuf;
+
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, PlainModulusConstraint, Runtime,
+};
+
+#[fhe_program(scheme = "bfv")]
+fn square(a: Cipher<Signed>) -> Cipher<Signed> {
+    a * a
+}
+
+#[fhe_program(scheme = "bfv")]
+fn cube(a: Cipher<Signed>) -> Cipher<Signed> {
+    a * a * a
+}
+
+/**
+ * An empty directory unique to the calling test.
+ */
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "sunscreen-compile-cache-{}-{}",
+        std::process::id(),
+        name
+    ));
+
+    let _ = fs::remove_dir_all(&dir);
+
+    dir
+}
+
+fn entries(dir: &PathBuf) -> Vec<PathBuf> {
+    let mut entries = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect::<Vec<_>>();
+
+    entries.sort();
+    entries
+}
+
+#[test]
+fn second_compile_reads_cache() {
+    let dir = cache_dir("reads");
+
+    let app = Compiler::new()
+        .fhe_program(square)
+        .with_cache_dir(dir.clone())
+        .compile()
+        .unwrap();
+
+    let square_entry = entries(&dir);
+    assert_eq!(square_entry.len(), 1);
+
+    let cached = Compiler::new()
+        .fhe_program(square)
+        .with_cache_dir(dir.clone())
+        .compile()
+        .unwrap();
+
+    assert_eq!(cached.params(), app.params());
+    assert_eq!(entries(&dir), square_entry);
+
+    let runtime = Runtime::new_fhe(cached.params()).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(-7), &public_key).unwrap();
+    let result = runtime
+        .run(
+            cached.get_fhe_program(square).unwrap(),
+            vec![a],
+            &public_key,
+        )
+        .unwrap();
+    let result: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(result, Signed::from(49));
+
+    // Swap in another program's entry to prove compiling reads the cache.
+    let other = cache_dir("reads-other");
+
+    Compiler::new()
+        .fhe_program(cube)
+        .with_cache_dir(other.clone())
+        .compile()
+        .unwrap();
+
+    fs::copy(&entries(&other)[0], &square_entry[0]).unwrap();
+
+    let spied = Compiler::new()
+        .fhe_program(square)
+        .with_cache_dir(dir.clone())
+        .compile()
+        .unwrap();
+
+    assert!(spied.get_fhe_program(square).is_none());
+    assert!(spied.get_fhe_program(cube).is_some());
+
+    fs::remove_dir_all(dir).unwrap();
+    fs::remove_dir_all(other).unwrap();
+}
+
+#[test]
+fn changing_settings_invalidates_cache() {
+    let dir = cache_dir("invalidates");
+
+    let compile = |plain_modulus| {
+        Compiler::new()
+            .fhe_program(square)
+            .plain_modulus_constraint(PlainModulusConstraint::Raw(plain_modulus))
+            .with_cache_dir(dir.clone())
+            .compile()
+            .unwrap()
+    };
+
+    let a = compile(1024);
+    let b = compile(4096);
+
+    assert_eq!(a.params().plain_modulus, 1024);
+    assert_eq!(b.params().plain_modulus, 4096);
+    assert_eq!(entries(&dir).len(), 2);
+
+    fs::remove_dir_all(dir).unwrap();
+}
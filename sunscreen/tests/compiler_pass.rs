@@ -0,0 +1,195 @@
+use petgraph::{stable_graph::StableGraph, visit::EdgeRef, Direction};
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, CompilerPass, CompilerPassError, Error, Params, ParamsBuilder, Runtime,
+};
+use sunscreen_compiler_common::{EdgeInfo, GraphQuery, NodeInfo};
+use sunscreen_fhe_program::{FheProgramTrait, Operation};
+
+#[fhe_program(scheme = "bfv")]
+fn add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a + b
+}
+
+/**
+ * Replaces every `l + r` with `(l * r) * (l * r)`.
+ */
+struct AddToSquaredProduct;
+
+impl CompilerPass<Operation, Params> for AddToSquaredProduct {
+    fn name(&self) -> &str {
+        "add_to_squared_product"
+    }
+
+    fn run(
+        &self,
+        graph: &mut StableGraph<NodeInfo<Operation>, EdgeInfo>,
+        _params: &Params,
+    ) -> Result<(), CompilerPassError> {
+        let adds = graph
+            .node_indices()
+            .filter(|id| graph[*id].operation == Operation::Add)
+            .collect::<Vec<_>>();
+
+        for id in adds {
+            let (l, r) = GraphQuery::new(graph)
+                .get_binary_operands(id)
+                .map_err(|e| CompilerPassError(e.to_string()))?;
+
+            let incoming = graph
+                .edges_directed(id, Direction::Incoming)
+                .map(|e| e.id())
+                .collect::<Vec<_>>();
+
+            for e in incoming {
+                graph.remove_edge(e);
+            }
+
+            let product = graph.add_node(NodeInfo::new(Operation::Multiply));
+            graph.add_edge(l, product, EdgeInfo::Left);
+            graph.add_edge(r, product, EdgeInfo::Right);
+
+            graph.add_edge(product, id, EdgeInfo::Left);
+            graph.add_edge(product, id, EdgeInfo::Right);
+            graph[id].operation = Operation::Multiply;
+        }
+
+        Ok(())
+    }
+}
+
+struct Named(&'static str);
+
+impl CompilerPass<Operation, Params> for Named {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn run(
+        &self,
+        _graph: &mut StableGraph<NodeInfo<Operation>, EdgeInfo>,
+        _params: &Params,
+    ) -> Result<(), CompilerPassError> {
+        Ok(())
+    }
+}
+
+struct Failing;
+
+impl CompilerPass<Operation, Params> for Failing {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    fn run(
+        &self,
+        _graph: &mut StableGraph<NodeInfo<Operation>, EdgeInfo>,
+        _params: &Params,
+    ) -> Result<(), CompilerPassError> {
+        Err(CompilerPassError::new("always fails"))
+    }
+}
+
+fn params() -> Params {
+    ParamsBuilder::new()
+        .lattice_dimension(8192)
+        .plain_modulus(1024)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn user_pass_runs_during_compilation() {
+    let app = Compiler::new()
+        .fhe_program(add)
+        .with_params(&params())
+        .with_pass(AddToSquaredProduct)
+        .compile()
+        .unwrap();
+
+    let program = app.get_fhe_program(add).unwrap();
+
+    let multiplies = program
+        .fhe_program_fn
+        .graph
+        .node_weights()
+        .filter(|n| n.operation == Operation::Multiply)
+        .count();
+
+    assert_eq!(multiplies, 2);
+    assert_eq!(program.fhe_program_fn.multiplicative_depth(), 2);
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(2), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+
+    let result = runtime.run(program, vec![a, b], &public_key).unwrap();
+    let result: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(result, Signed::from(36));
+}
+
+#[test]
+fn user_passes_run_at_their_anchors() {
+    let (_, timings) = Compiler::new()
+        .fhe_program(add)
+        .with_params(&params())
+        .with_pass(Named("default_1"))
+        .with_pass_before("constant_folding", Named("first"))
+        .with_pass_after("compact", Named("last"))
+        .with_pass(Named("default_2"))
+        .with_pass_after("first", Named("second"))
+        .compile_timed()
+        .unwrap();
+
+    assert_eq!(
+        timings
+            .passes
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "first",
+            "second",
+            "constant_folding",
+            "dead_code_elimination",
+            "common_subexpression_elimination",
+            "default_1",
+            "default_2",
+            "insert_relinearizations",
+            "compact",
+            "last",
+        ]
+    );
+}
+
+#[test]
+fn unknown_anchor_is_an_error() {
+    let result = Compiler::new()
+        .fhe_program(add)
+        .with_params(&params())
+        .with_pass_before("no_such_pass", Named("orphan"))
+        .compile();
+
+    assert!(matches!(result, Err(Error::UnknownPass(p)) if *p == "no_such_pass"));
+}
+
+#[test]
+fn failing_pass_is_reported() {
+    let result = Compiler::new()
+        .fhe_program(add)
+        .with_params(&params())
+        .with_pass(Failing)
+        .compile();
+
+    match result {
+        Err(Error::CompilerPassFailed(e)) => {
+            assert_eq!(e.0, "failing");
+            assert_eq!(e.1, CompilerPassError::new("always fails"));
+        }
+        x => panic!("Expected CompilerPassFailed, got {:?}", x.err()),
+    }
+}
@@ -0,0 +1,89 @@
+use sunscreen::{
+    types::{bfv::Signed, Cipher},
+    *,
+};
+use sunscreen_fhe_program::FheProgramTrait;
+
+fn node_count(program: &CompiledFheProgram) -> usize {
+    program.fhe_program_fn.graph.node_count()
+}
+
+#[test]
+fn dead_branches_are_eliminated() {
+    #[fhe_program(scheme = "bfv")]
+    fn live(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a + b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn dead(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        let _unused = a * b * a;
+        let _also_unused = -(a - b);
+
+        a + b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(live)
+        .fhe_program(dead)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(64))
+        .compile()
+        .unwrap();
+
+    let frontend_nodes = dead.build(app.params()).unwrap().node_count();
+    let dead_nodes = node_count(app.get_fhe_program(dead).unwrap());
+    let live_nodes = node_count(app.get_fhe_program(live).unwrap());
+
+    assert!(dead_nodes < frontend_nodes);
+    assert_eq!(dead_nodes, live_nodes);
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(15), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(5), &public_key).unwrap();
+
+    let result = runtime
+        .run(app.get_fhe_program(dead).unwrap(), vec![a, b], &public_key)
+        .unwrap();
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, 20.into());
+}
+
+#[test]
+fn unused_inputs_are_kept() {
+    #[fhe_program(scheme = "bfv")]
+    fn first(a: Cipher<Signed>, b: Cipher<Signed>, c: Cipher<Signed>) -> Cipher<Signed> {
+        let _unused = b * c;
+
+        a
+    }
+
+    let app = Compiler::new()
+        .fhe_program(first)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(64))
+        .compile()
+        .unwrap();
+
+    let program = app.get_fhe_program(first).unwrap();
+
+    assert_eq!(program.fhe_program_fn.num_inputs(), 3);
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let args = [1, 2, 3]
+        .iter()
+        .map(|x| runtime.encrypt(Signed::from(*x), &public_key).unwrap())
+        .collect::<Vec<_>>();
+
+    let result = runtime.run(program, args, &public_key).unwrap();
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, 1.into());
+}
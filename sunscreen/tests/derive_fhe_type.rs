@@ -0,0 +1,143 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher, NumCiphertexts},
+    Compiler, FheType, ParamsBuilder, PlainModulusConstraint, Runtime, TypeName,
+};
+
+#[derive(Clone, Debug, PartialEq, TypeName, FheType)]
+struct Point {
+    x: Signed,
+    y: Signed,
+}
+
+#[derive(Clone, Debug, PartialEq, TypeName, FheType)]
+struct Segment {
+    start: Point,
+    end: Point,
+}
+
+#[derive(Clone, Debug, PartialEq, TypeName, FheType)]
+struct Record {
+    age: Signed,
+    score: Signed,
+}
+
+#[test]
+fn derived_type_counts_field_ciphertexts() {
+    assert_eq!(Point::NUM_CIPHERTEXTS, 2);
+    assert_eq!(Segment::NUM_CIPHERTEXTS, 4);
+}
+
+#[test]
+fn can_encrypt_decrypt_derived_type() {
+    let params = ParamsBuilder::new()
+        .lattice_dimension(4096)
+        .plain_modulus(1024)
+        .build()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(&params).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let segment = Segment {
+        start: Point {
+            x: Signed::from(-3),
+            y: Signed::from(7),
+        },
+        end: Point {
+            x: Signed::from(12),
+            y: Signed::from(-40),
+        },
+    };
+
+    let ciphertext = runtime.encrypt(segment.clone(), &public_key).unwrap();
+    let decrypted: Segment = runtime.decrypt(&ciphertext, &private_key).unwrap();
+
+    assert_eq!(decrypted, segment);
+
+    // The ciphertext is tagged with the outer type.
+    assert!(runtime.decrypt::<Point>(&ciphertext, &private_key).is_err());
+}
+
+#[test]
+fn can_run_program_over_derived_struct() {
+    #[fhe_program(scheme = "bfv")]
+    fn identity(record: Cipher<Record>) -> Cipher<Record> {
+        record
+    }
+
+    let app = Compiler::new()
+        .fhe_program(identity)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let record = Record {
+        age: Signed::from(42),
+        score: Signed::from(-17),
+    };
+
+    assert_eq!(Record::NUM_CIPHERTEXTS, 2);
+
+    let ciphertext = runtime.encrypt(record.clone(), &public_key).unwrap();
+
+    let result = runtime
+        .run(
+            app.get_fhe_program(identity).unwrap(),
+            vec![ciphertext],
+            &public_key,
+        )
+        .unwrap();
+
+    let decrypted: Record = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(decrypted, record);
+}
+
+#[test]
+fn can_decrypt_separately_encrypted_fields() {
+    let params = ParamsBuilder::new()
+        .lattice_dimension(4096)
+        .plain_modulus(1024)
+        .build()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(&params).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let x = runtime.encrypt(Signed::from(-3), &public_key).unwrap();
+    let y = runtime.encrypt(Signed::from(7), &public_key).unwrap();
+
+    let point: Point = runtime
+        .decrypt_vec(&[x.clone(), y.clone()], &private_key)
+        .unwrap();
+
+    assert_eq!(
+        point,
+        Point {
+            x: Signed::from(-3),
+            y: Signed::from(7),
+        }
+    );
+
+    // Fields are assigned in the order the ciphertexts are given.
+    let swapped: Point = runtime
+        .decrypt_vec(&[y.clone(), x.clone()], &private_key)
+        .unwrap();
+
+    assert_eq!(
+        swapped,
+        Point {
+            x: Signed::from(7),
+            y: Signed::from(-3),
+        }
+    );
+
+    assert_eq!(
+        runtime.decrypt_vec::<Point>(&[x], &private_key),
+        Err(sunscreen::RuntimeError::IncorrectCiphertextCount)
+    );
+}
@@ -29,3 +29,301 @@ fn can_encrypt_decrypt() {
 
     assert_eq!(c, 20.into());
 }
+
+#[test]
+fn can_encrypt_decrypt_i64_min() {
+    #[fhe_program(scheme = "bfv")]
+    fn foo(a: Cipher<Signed>) -> Cipher<Signed> {
+        a
+    }
+
+    let app = Compiler::new()
+        .fhe_program(foo)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime
+        .encrypt(Signed::from(i64::MIN), &public_key)
+        .unwrap();
+
+    let a_dec: Signed = runtime.decrypt(&a, &private_key).unwrap();
+    assert_eq!(a_dec, i64::MIN.into());
+
+    let result = runtime
+        .run(app.get_fhe_program(foo).unwrap(), vec![a], &public_key)
+        .unwrap();
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, i64::MIN.into());
+}
+
+#[test]
+fn noise_budget_drops_after_multiply() {
+    #[fhe_program(scheme = "bfv")]
+    fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(simple_multiply)
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(15), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(5), &public_key).unwrap();
+
+    let before = runtime.noise_budget(&a, &private_key).unwrap();
+
+    let result = runtime
+        .run(
+            app.get_fhe_program(simple_multiply).unwrap(),
+            vec![a, b],
+            &public_key,
+        )
+        .unwrap();
+
+    let after = runtime.noise_budget(&result[0], &private_key).unwrap();
+
+    assert!(after > 0);
+    assert!(after < before);
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, 75.into());
+}
+
+#[test]
+fn decrypt_with_stats_counts_significant_coefficients() {
+    #[fhe_program(scheme = "bfv")]
+    fn mul(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * b
+    }
+
+    let app = Compiler::new().fhe_program(mul).compile().unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let run = |a: i64, b: i64| {
+        let a = runtime.encrypt(Signed::from(a), &public_key).unwrap();
+        let b = runtime.encrypt(Signed::from(b), &public_key).unwrap();
+
+        let result = runtime
+            .run(app.get_fhe_program(mul).unwrap(), vec![a, b], &public_key)
+            .unwrap();
+
+        runtime
+            .decrypt_with_stats::<Signed>(&result[0], &private_key)
+            .unwrap()
+    };
+
+    // 1 encodes as the polynomial 1, so 1 * 1 = 1.
+    let (c, stats) = run(1, 1);
+
+    assert_eq!(c, 1.into());
+    assert_eq!(stats.significant_coefficients, 1);
+
+    // 3 encodes as 1 + x, so 3 * 3 = 1 + 2x + x^2.
+    let (c, stats) = run(3, 3);
+
+    assert_eq!(c, 9.into());
+    assert_eq!(stats.significant_coefficients, 3);
+
+    // 3 * 1 stays 1 + x.
+    let (_, stats) = run(3, 1);
+
+    assert_eq!(stats.significant_coefficients, 2);
+}
+
+#[test]
+fn concurrently_generated_keys_encrypt_decrypt_and_relinearize() {
+    #[fhe_program(scheme = "bfv")]
+    fn square(a: Cipher<Signed>) -> Cipher<Signed> {
+        a * a
+    }
+
+    let app = Compiler::new().fhe_program(square).compile().unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let single_thread = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+
+    // Key generation is concurrent on the default pool and sequential on
+    // a single thread. Both must produce working keys.
+    let keys = [
+        runtime.generate_keys().unwrap(),
+        single_thread.install(|| runtime.generate_keys()).unwrap(),
+    ];
+
+    for (public_key, private_key) in keys {
+        assert!(public_key.relin_key.is_some());
+
+        let a = runtime.encrypt(Signed::from(-12), &public_key).unwrap();
+
+        let a_dec: Signed = runtime.decrypt(&a, &private_key).unwrap();
+        assert_eq!(a_dec, (-12).into());
+
+        let result = runtime
+            .run(app.get_fhe_program(square).unwrap(), vec![a], &public_key)
+            .unwrap();
+
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+        assert_eq!(c, 144.into());
+    }
+}
+
+#[test]
+fn can_run_symmetrically_encrypted_inputs() {
+    #[fhe_program(scheme = "bfv")]
+    fn simple_add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a + b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(simple_add)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime
+        .encrypt_symmetric(Signed::from(-15), &private_key)
+        .unwrap();
+    let b = runtime.encrypt(Signed::from(5), &public_key).unwrap();
+
+    let result = runtime
+        .run(
+            app.get_fhe_program(simple_add).unwrap(),
+            vec![a, b],
+            &public_key,
+        )
+        .unwrap();
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, (-10).into());
+}
+
+#[test]
+fn batch_encryption_matches_serial_encryption() {
+    let params = Params {
+        lattice_dimension: 4096,
+        plain_modulus: 1024,
+        coeff_modulus: vec![0xffffee001, 0xffffc4001, 0x1ffffe0001],
+        scheme_type: SchemeType::Bfv,
+        security_level: SecurityLevel::TC128,
+    };
+
+    let runtime = FheRuntime::new(&params).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let values = (-50..50).map(Signed::from).collect::<Vec<_>>();
+
+    let batch = runtime.encrypt_many(&values, &public_key).unwrap();
+    let serial = values
+        .iter()
+        .map(|x| runtime.encrypt(*x, &public_key).unwrap())
+        .collect::<Vec<_>>();
+
+    let batch_decrypted: Vec<Signed> = runtime.decrypt_many(&batch, &private_key).unwrap();
+    let serial_decrypted: Vec<Signed> = serial
+        .iter()
+        .map(|c| runtime.decrypt(c, &private_key).unwrap())
+        .collect();
+
+    assert_eq!(batch_decrypted, values);
+    assert_eq!(serial_decrypted, values);
+    assert_eq!(
+        runtime
+            .decrypt_many::<Signed>(&serial, &private_key)
+            .unwrap(),
+        values
+    );
+}
+
+#[test]
+fn rejects_inputs_encrypted_under_other_params() {
+    #[fhe_program(scheme = "bfv")]
+    fn simple_add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a + b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(simple_add)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let other_runtime = Runtime::new_fhe(&Params {
+        plain_modulus: 1024,
+        ..app.params().clone()
+    })
+    .unwrap();
+
+    let (public_key, _) = runtime.generate_keys().unwrap();
+    let (other_public_key, _) = other_runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(5), &public_key).unwrap();
+    let b = other_runtime
+        .encrypt(Signed::from(7), &other_public_key)
+        .unwrap();
+
+    let result = runtime.run(
+        app.get_fhe_program(simple_add).unwrap(),
+        vec![a, b],
+        &public_key,
+    );
+
+    assert!(matches!(result, Err(RuntimeError::ParameterMismatch)));
+}
+
+#[test]
+fn can_roundtrip_through_seal_ciphertexts() {
+    #[fhe_program(scheme = "bfv")]
+    fn negate(a: Cipher<Signed>) -> Cipher<Signed> {
+        -a
+    }
+
+    let app = Compiler::new()
+        .fhe_program(negate)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(-42), &public_key).unwrap();
+    let data_type = a.data_type.clone();
+
+    let seal_ciphertexts = a.into_seal();
+
+    assert_eq!(seal_ciphertexts.len(), 1);
+    assert_eq!(&seal_ciphertexts[0].params, app.params());
+
+    let a = Ciphertext::from_seal(seal_ciphertexts, data_type);
+
+    let c: Signed = runtime.decrypt(&a, &private_key).unwrap();
+
+    assert_eq!(c, (-42).into());
+}
@@ -1,12 +1,14 @@
 use petgraph::stable_graph::node_index;
 use sunscreen::{
-    fhe::{FheFrontendCompilation, FheOperation, Literal, CURRENT_FHE_CTX},
+    fhe::{FheCompile, FheFrontendCompilation, FheOperation, Literal, CURRENT_FHE_CTX},
     fhe_program, fhe_var,
     types::{bfv::Signed, Cipher, TypeName},
-    CallSignature, FheProgramFn, Params, SchemeType, SecurityLevel,
+    CallSignature, Compiler, FheProgramFn, Params, ProgramInputInfo, ProgramOutputInfo, SchemeType,
+    SecurityLevel,
 };
 
 use serde_json::json;
+use sunscreen_fhe_program::{FheProgram, FheProgramTrait};
 
 fn get_params() -> Params {
     Params {
@@ -465,3 +467,121 @@ fn coercion_supports_arbitrarily_nested_outputs() {
         ([sum; 2], [[sum; 3]; 2])
     }
 }
+
+#[test]
+fn macro_program_is_structurally_equal_to_hand_built() {
+    #[fhe_program(scheme = "bfv")]
+    fn simple_add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a + b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * b
+    }
+
+    let add = simple_add.build(&get_params()).unwrap().compile();
+    let mul = simple_multiply.build(&get_params()).unwrap().compile();
+
+    // Insert nodes in a different order than the macro does.
+    let mut expected = FheProgram::new(SchemeType::Bfv);
+
+    let b = expected.add_input_ciphertext(1);
+    let a = expected.add_input_ciphertext(0);
+    let sum = expected.add_add(a, b);
+    expected.add_output_ciphertext(sum);
+
+    assert!(add.structurally_eq(&expected));
+    assert!(expected.structurally_eq(&add));
+    assert!(!mul.structurally_eq(&expected));
+}
+
+#[test]
+fn compiled_program_describes_its_inputs_and_outputs() {
+    #[fhe_program(scheme = "bfv")]
+    fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn scale(value: Cipher<Signed>, factor: Signed) -> Cipher<Signed> {
+        value * factor
+    }
+
+    let app = Compiler::new()
+        .fhe_program(simple_multiply)
+        .fhe_program(scale)
+        .compile()
+        .unwrap();
+
+    let signed = Signed::type_name().name;
+    let program = app.get_fhe_program(simple_multiply).unwrap();
+
+    assert_eq!(
+        program.inputs(),
+        vec![
+            ProgramInputInfo {
+                name: "a".to_owned(),
+                type_name: signed.clone(),
+                is_cipher: true,
+                index: 0,
+            },
+            ProgramInputInfo {
+                name: "b".to_owned(),
+                type_name: signed.clone(),
+                is_cipher: true,
+                index: 1,
+            },
+        ]
+    );
+
+    assert_eq!(
+        program.outputs(),
+        vec![ProgramOutputInfo {
+            name: "0".to_owned(),
+            type_name: signed.clone(),
+            is_cipher: true,
+            index: 0,
+        }]
+    );
+
+    assert_eq!(
+        app.get_signature(simple_multiply),
+        Some(&program.metadata.signature)
+    );
+
+    let inputs = app.get_fhe_program(scale).unwrap().inputs();
+
+    assert_eq!(
+        inputs
+            .iter()
+            .map(|i| (i.name.as_str(), i.is_cipher))
+            .collect::<Vec<_>>(),
+        vec![("value", true), ("factor", false)]
+    );
+}
+
+#[test]
+fn compiled_program_renders_as_dot() {
+    #[fhe_program(scheme = "bfv")]
+    fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(simple_multiply)
+        .compile()
+        .unwrap();
+
+    let program = app.get_fhe_program(simple_multiply).unwrap();
+    let dot = program.to_dot();
+
+    assert!(dot.trim().starts_with("digraph {"));
+    assert!(dot.contains("label=\"Multiply\""));
+    assert!(dot.contains("label=\"Input[0]\""));
+    assert!(dot.contains("label=\"Output[0]\""));
+    assert_eq!(
+        dot.matches(" -> ").count(),
+        program.fhe_program_fn.graph.edge_count()
+    );
+}
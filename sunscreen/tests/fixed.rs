@@ -0,0 +1,118 @@
+#![allow(clippy::approx_constant)]
+
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Fixed, Cipher},
+    Compiler, FheProgramInput, PlainModulusConstraint, Runtime,
+};
+
+type Fixed16 = Fixed<32, 16>;
+
+#[test]
+fn can_add_sub_mul() {
+    #[fhe_program(scheme = "bfv")]
+    fn add(a: Cipher<Fixed16>, b: Cipher<Fixed16>) -> Cipher<Fixed16> {
+        a + b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn sub(a: Cipher<Fixed16>, b: Cipher<Fixed16>) -> Cipher<Fixed16> {
+        a - b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn mul(a: Cipher<Fixed16>, b: Cipher<Fixed16>) -> Cipher<Fixed16> {
+        a * b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(add)
+        .fhe_program(sub)
+        .fhe_program(mul)
+        .additional_noise_budget(30)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Fixed16::from(3.14);
+    let b = Fixed16::from(-0.5);
+
+    let a_c = runtime.encrypt(a, &public_key).unwrap();
+    let b_c = runtime.encrypt(b, &public_key).unwrap();
+
+    let run = |program| {
+        let args: Vec<FheProgramInput> = vec![a_c.clone().into(), b_c.clone().into()];
+
+        let result = runtime.run(program, args, &public_key).unwrap();
+
+        let c: Fixed16 = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        c
+    };
+
+    assert_eq!(run(app.get_fhe_program(add).unwrap()), a + b);
+    assert_eq!(run(app.get_fhe_program(sub).unwrap()), a - b);
+
+    let product = run(app.get_fhe_program(mul).unwrap());
+
+    assert_eq!(product, a * b);
+    assert!((f64::from(product) - 3.14 * -0.5).abs() <= (-16f64).exp2());
+}
+
+#[test]
+fn can_mul_by_cipher_and_constant() {
+    #[fhe_program(scheme = "bfv")]
+    fn mul(a: Cipher<Fixed16>, b: Cipher<Fixed16>) -> Cipher<Fixed16> {
+        a * b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn mul_const(a: Cipher<Fixed16>) -> Cipher<Fixed16> {
+        a * 2.0
+    }
+
+    let app = Compiler::new()
+        .fhe_program(mul)
+        .fhe_program(mul_const)
+        .additional_noise_budget(30)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a_c = runtime.encrypt(Fixed16::from(1.5), &public_key).unwrap();
+    let b_c = runtime.encrypt(Fixed16::from(2.0), &public_key).unwrap();
+
+    let decrypt = |result: Vec<_>| -> f64 {
+        let c: Fixed16 = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        c.into()
+    };
+
+    let product = runtime
+        .run(
+            app.get_fhe_program(mul).unwrap(),
+            vec![a_c.clone(), b_c],
+            &public_key,
+        )
+        .unwrap();
+
+    let const_product = runtime
+        .run(
+            app.get_fhe_program(mul_const).unwrap(),
+            vec![a_c],
+            &public_key,
+        )
+        .unwrap();
+
+    for result in [product, const_product] {
+        assert!((decrypt(result) - 3.0).abs() <= (-16f64).exp2());
+    }
+}
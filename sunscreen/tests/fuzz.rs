@@ -0,0 +1,37 @@
+mod test_util;
+
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler,
+};
+use test_util::fuzz_program;
+
+const SEED: u64 = 0x5EED_1234;
+
+#[test]
+fn fuzz_simple_add() {
+    #[fhe_program(scheme = "bfv")]
+    fn simple_add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a + b
+    }
+
+    let app = Compiler::new().fhe_program(simple_add).compile().unwrap();
+
+    fuzz_program(app.get_fhe_program(simple_add).unwrap(), 16, SEED);
+}
+
+#[test]
+fn fuzz_simple_multiply() {
+    #[fhe_program(scheme = "bfv")]
+    fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(simple_multiply)
+        .compile()
+        .unwrap();
+
+    fuzz_program(app.get_fhe_program(simple_multiply).unwrap(), 16, SEED);
+}
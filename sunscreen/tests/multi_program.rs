@@ -113,3 +113,80 @@ fn cant_compile_multiple_programs_with_same_name() {
         }
     };
 }
+
+#[fhe_program(scheme = "bfv")]
+fn add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a + b
+}
+
+#[fhe_program(scheme = "bfv")]
+fn mul(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a * b
+}
+
+fn merge_params() -> Params {
+    ParamsBuilder::new()
+        .lattice_dimension(4096)
+        .plain_modulus(1024)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn can_merge_applications() {
+    let add_app = Compiler::new()
+        .fhe_program(add)
+        .with_params(&merge_params())
+        .compile()
+        .unwrap();
+
+    let mul_app = Compiler::new()
+        .fhe_program(mul)
+        .with_params(&merge_params())
+        .compile()
+        .unwrap();
+
+    let app = add_app.merge(mul_app).unwrap();
+
+    assert_eq!(app.get_fhe_programs().count(), 2);
+    assert_eq!(*app.params(), merge_params());
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    for (program, expected) in [(add.name(), 5), (mul.name(), 6)] {
+        let a = runtime.encrypt(Signed::from(2), &public_key).unwrap();
+        let b = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+
+        let result = runtime
+            .run(
+                app.get_fhe_program(program).unwrap(),
+                vec![a, b],
+                &public_key,
+            )
+            .unwrap();
+        let result: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(result, Signed::from(expected));
+    }
+}
+
+#[test]
+fn merging_rejects_name_collisions_and_mismatched_params() {
+    let compile = |params: &Params| {
+        Compiler::new()
+            .fhe_program(add)
+            .with_params(params)
+            .compile()
+            .unwrap()
+    };
+
+    let result = compile(&merge_params()).merge(compile(&merge_params()));
+
+    assert!(matches!(result, Err(Error::NameCollision)));
+
+    let mul_app = Compiler::new().fhe_program(mul).compile().unwrap();
+    let result = compile(&merge_params()).merge(mul_app);
+
+    assert!(matches!(result, Err(Error::ParamsMismatch)));
+}
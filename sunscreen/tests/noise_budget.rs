@@ -0,0 +1,186 @@
+use seal_fhe::CoefficientModulus;
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, Error, Params, Runtime, RuntimeError, SchemeType, SecurityLevel,
+};
+
+#[fhe_program(scheme = "bfv")]
+fn square(a: Cipher<Signed>) -> Cipher<Signed> {
+    a * a
+}
+
+#[fhe_program(scheme = "bfv")]
+fn power_256(a: Cipher<Signed>) -> Cipher<Signed> {
+    let mut x = a;
+
+    for _ in 0..8 {
+        x = x * x;
+    }
+
+    x
+}
+
+#[fhe_program(scheme = "bfv")]
+fn power_8(a: Cipher<Signed>) -> Cipher<Signed> {
+    let mut x = a;
+
+    for _ in 0..3 {
+        x = x * x;
+    }
+
+    x
+}
+
+fn params(lattice_dimension: u64) -> Params {
+    Params {
+        lattice_dimension,
+        plain_modulus: 1024,
+        coeff_modulus: CoefficientModulus::bfv_default(lattice_dimension, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|x| x.value())
+            .collect(),
+        scheme_type: SchemeType::Bfv,
+        security_level: SecurityLevel::TC128,
+    }
+}
+
+fn small_params() -> Params {
+    params(4096)
+}
+
+#[test]
+fn estimate_reports_consumed_budget() {
+    let app = Compiler::new()
+        .fhe_program(square)
+        .with_params(&small_params())
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let estimate = runtime
+        .estimate_noise_budget(app.get_fhe_program(square).unwrap())
+        .unwrap();
+
+    assert!(estimate.will_decrypt_correctly);
+    assert!(estimate.consumed > 0);
+    assert_eq!(
+        estimate.initial_budget,
+        estimate.consumed + estimate.remaining
+    );
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+    let a = runtime.encrypt(Signed::from(-3), &public_key).unwrap();
+
+    let result = runtime
+        .run_checked(app.get_fhe_program(square).unwrap(), vec![a], &public_key)
+        .unwrap();
+    let result: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(result, Signed::from(9));
+}
+
+#[test]
+fn deep_multiplication_chain_exhausts_budget() {
+    let app = Compiler::new()
+        .fhe_program(power_256)
+        .with_params(&small_params())
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let program = app.get_fhe_program(power_256).unwrap();
+
+    let estimate = runtime.estimate_noise_budget(program).unwrap();
+
+    assert!(!estimate.will_decrypt_correctly);
+    assert_eq!(estimate.remaining, 0);
+    assert_eq!(estimate.consumed, estimate.initial_budget);
+
+    let (public_key, _) = runtime.generate_keys().unwrap();
+    let a = runtime.encrypt(Signed::from(1), &public_key).unwrap();
+
+    assert!(matches!(
+        runtime.run_checked(program, vec![a], &public_key),
+        Err(RuntimeError::InsufficientNoiseBudget)
+    ));
+}
+
+#[test]
+fn validate_flags_depth_3_chain_under_small_params() {
+    let app = Compiler::new()
+        .fhe_program(power_8)
+        .with_params(&small_params())
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let report = runtime
+        .validate(app.get_fhe_program(power_8).unwrap())
+        .unwrap();
+
+    assert!(report.is_risky());
+    assert_eq!(report.multiplicative_depth, 3);
+    assert_eq!(report.remaining_budget, 0.);
+    assert!(report.initial_budget > 0.);
+}
+
+#[test]
+fn validate_accepts_depth_3_chain_under_larger_params() {
+    let app = Compiler::new()
+        .fhe_program(power_8)
+        .with_params(&params(8192))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let report = runtime
+        .validate(app.get_fhe_program(power_8).unwrap())
+        .unwrap();
+
+    assert!(!report.is_risky());
+    assert_eq!(report.multiplicative_depth, 3);
+    assert!(report.remaining_budget < report.initial_budget);
+}
+
+#[test]
+fn pinned_params_must_leave_outputs_decryptable() {
+    let result = Compiler::new()
+        .fhe_program(power_256)
+        .with_params(&small_params())
+        .with_decryptability_check(true)
+        .compile();
+
+    assert!(matches!(result, Err(Error::OutputUndecryptable { .. })));
+}
+
+#[test]
+fn fresh_ciphertext_budget_is_near_maximum() {
+    let params = small_params();
+    let runtime = Runtime::new_fhe(&params).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let (ciphertext, budget) = runtime
+        .encrypt_with_budget(Signed::from(42), &public_key, &private_key)
+        .unwrap();
+
+    // The budget can't exceed log2(q / t), where q excludes the modulus
+    // SEAL reserves for key switching. Encryption noise only consumes
+    // a few bits of it.
+    let (_, data_moduli) = params.coeff_modulus.split_last().unwrap();
+    let log_q: f64 = data_moduli.iter().map(|q| (*q as f64).log2()).sum();
+    let maximum = log_q - (params.plain_modulus as f64).log2();
+
+    assert!(budget as f64 <= maximum);
+    assert!(budget as f64 >= maximum - 16.);
+
+    assert_eq!(
+        budget,
+        runtime.noise_budget(&ciphertext, &private_key).unwrap()
+    );
+
+    let decrypted: Signed = runtime.decrypt(&ciphertext, &private_key).unwrap();
+    assert_eq!(decrypted, Signed::from(42));
+}
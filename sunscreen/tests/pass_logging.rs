@@ -0,0 +1,71 @@
+use std::sync::Mutex;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler,
+};
+
+/**
+ * Captures every record logged at debug level or above.
+ */
+struct CaptureLogger {
+    messages: Mutex<Vec<String>>,
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.messages
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CaptureLogger = CaptureLogger {
+    messages: Mutex::new(vec![]),
+};
+
+#[fhe_program(scheme = "bfv")]
+fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a * b
+}
+
+#[test]
+fn compiling_logs_each_pass() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Debug);
+
+    Compiler::new()
+        .fhe_program(simple_multiply)
+        .compile()
+        .unwrap();
+
+    let messages = LOGGER.messages.lock().unwrap();
+
+    for pass in [
+        "validation",
+        "param_selection",
+        "lowering",
+        "constant_folding",
+        "insert_relinearizations",
+        "compact",
+    ] {
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.starts_with(&format!("Pass {}:", pass))),
+            "No record for pass {}",
+            pass
+        );
+    }
+}
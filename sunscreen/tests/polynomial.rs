@@ -0,0 +1,89 @@
+use sunscreen::{
+    types::{
+        bfv::{EncryptedPolynomial, Signed},
+        Cipher,
+    },
+    *,
+};
+
+#[test]
+fn evaluating_encrypted_polynomial_matches_plaintext() {
+    #[fhe_program(scheme = "bfv")]
+    fn evaluate(p: Cipher<EncryptedPolynomial<4>>, x: Cipher<Signed>) -> Cipher<Signed> {
+        p.evaluate_at(x)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(evaluate)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(100_000))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let coefficients = [5, -3, 0, 2];
+
+    let p = runtime
+        .encrypt(EncryptedPolynomial::from(coefficients), &public_key)
+        .unwrap();
+
+    for x in [-2i64, 0, 3] {
+        let expected = coefficients.iter().rev().fold(0, |acc, c| acc * x + c);
+
+        let x_enc = runtime.encrypt(Signed::from(x), &public_key).unwrap();
+
+        let result = runtime
+            .run(
+                app.get_fhe_program(evaluate).unwrap(),
+                vec![p.clone(), x_enc],
+                &public_key,
+            )
+            .unwrap();
+
+        let y: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(y, expected.into(), "x = {x}");
+    }
+}
+
+#[test]
+fn can_add_and_multiply_coefficient_wise() {
+    #[fhe_program(scheme = "bfv")]
+    fn mul_add(
+        a: Cipher<EncryptedPolynomial<3>>,
+        b: Cipher<EncryptedPolynomial<3>>,
+    ) -> Cipher<EncryptedPolynomial<3>> {
+        a * b + a
+    }
+
+    let app = Compiler::new()
+        .fhe_program(mul_add)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(100_000))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime
+        .encrypt(EncryptedPolynomial::from([1, -2, 3]), &public_key)
+        .unwrap();
+    let b = runtime
+        .encrypt(EncryptedPolynomial::from([4, 5, -6]), &public_key)
+        .unwrap();
+
+    let result = runtime
+        .run(
+            app.get_fhe_program(mul_add).unwrap(),
+            vec![a, b],
+            &public_key,
+        )
+        .unwrap();
+
+    let c: EncryptedPolynomial<3> = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c.coefficients(), [5, -12, -15]);
+}
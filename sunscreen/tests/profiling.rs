@@ -0,0 +1,68 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, Runtime,
+};
+use sunscreen_fhe_program::Operation;
+
+#[fhe_program(scheme = "bfv")]
+fn simple_multiply(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a * b
+}
+
+#[test]
+fn profiles_each_operation() {
+    let app = Compiler::new()
+        .fhe_program(simple_multiply)
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(6), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(-7), &public_key).unwrap();
+
+    let (result, report) = runtime
+        .run_with_profiling(
+            app.get_fhe_program(simple_multiply).unwrap(),
+            vec![a, b],
+            &public_key,
+        )
+        .unwrap();
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+    assert_eq!(c, (-42).into());
+
+    assert_eq!(
+        report
+            .operations
+            .iter()
+            .map(|o| (o.operation.clone(), o.depth))
+            .collect::<Vec<_>>(),
+        vec![
+            (Operation::InputCiphertext(0), 0),
+            (Operation::InputCiphertext(1), 0),
+            (Operation::Multiply, 1),
+            (Operation::Relinearize, 1),
+            (Operation::OutputCiphertext, 1),
+        ]
+    );
+
+    // Every operation but one of the inputs lies on the critical path.
+    let path = report.critical_path();
+
+    assert_eq!(path.len(), 4);
+    assert_eq!(
+        path[1..]
+            .iter()
+            .map(|o| o.operation.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Operation::Multiply,
+            Operation::Relinearize,
+            Operation::OutputCiphertext
+        ]
+    );
+}
@@ -779,3 +779,52 @@ fn can_neg_cipher() {
 fn can_create_default() {
     assert_eq!(Into::<f64>::into(Rational::default()), 0.0f64);
 }
+
+#[test]
+fn can_add_and_mul_rational_pairs() {
+    #[fhe_program(scheme = "bfv")]
+    fn add(a: CipherRational, b: CipherRational) -> CipherRational {
+        a + b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn mul(a: CipherRational, b: CipherRational) -> CipherRational {
+        a * b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(add)
+        .fhe_program(mul)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let run = |program: &str, a: (i64, i64), b: (i64, i64)| {
+        let a_c = runtime.encrypt(Rational::from(a), &public_key).unwrap();
+        let b_c = runtime.encrypt(Rational::from(b), &public_key).unwrap();
+
+        let result = runtime
+            .run(
+                app.get_fhe_program(program).unwrap(),
+                vec![a_c, b_c],
+                &public_key,
+            )
+            .unwrap();
+
+        let c: Rational = runtime.decrypt(&result[0], &private_key).unwrap();
+        let c = c.reduce();
+
+        (c.numerator(), c.denominator())
+    };
+
+    // 1/2 + 1/3 = 5/6
+    assert_eq!(run("add", (1, 2), (1, 3)), (5, 6));
+
+    // 2/3 * 3/4 = 1/2
+    assert_eq!(run("mul", (2, 3), (3, 4)), (1, 2));
+}
@@ -0,0 +1,64 @@
+use sunscreen::{
+    types::{bfv::Signed, Cipher},
+    *,
+};
+use sunscreen_fhe_program::Operation;
+
+#[fhe_program(scheme = "bfv")]
+fn dot(
+    a: Cipher<Signed>,
+    b: Cipher<Signed>,
+    c: Cipher<Signed>,
+    d: Cipher<Signed>,
+) -> Cipher<Signed> {
+    a * b + c * d
+}
+
+fn compile(relin_optimization: bool) -> FheApplication {
+    Compiler::new()
+        .fhe_program(dot)
+        .with_relin_optimization(relin_optimization)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(64))
+        .compile()
+        .unwrap()
+}
+
+fn count_relins(program: &CompiledFheProgram) -> usize {
+    program
+        .fhe_program_fn
+        .graph
+        .node_weights()
+        .filter(|n| n.operation == Operation::Relinearize)
+        .count()
+}
+
+#[test]
+fn relin_optimization_removes_redundant_relinearizations() {
+    let eager = compile(false);
+    let deferred = compile(true);
+
+    let eager_program = eager.get_fhe_program(dot).unwrap();
+    let deferred_program = deferred.get_fhe_program(dot).unwrap();
+
+    assert_eq!(count_relins(eager_program), 2);
+    assert_eq!(count_relins(deferred_program), 1);
+    assert!(
+        deferred_program.fhe_program_fn.graph.node_count()
+            < eager_program.fhe_program_fn.graph.node_count()
+    );
+
+    let runtime = Runtime::new_fhe(deferred.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let args = [2, 3, 4, 5]
+        .iter()
+        .map(|x| runtime.encrypt(Signed::from(*x), &public_key).unwrap())
+        .collect::<Vec<_>>();
+
+    let result = runtime.run(deferred_program, args, &public_key).unwrap();
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, 26.into());
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "async")]
+
+use std::sync::Arc;
+
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, Runtime,
+};
+
+#[fhe_program(scheme = "bfv")]
+fn square(a: Cipher<Signed>) -> Cipher<Signed> {
+    a * a
+}
+
+#[test]
+fn concurrent_run_async_calls_produce_correct_results() {
+    let app = Compiler::new().fhe_program(square).compile().unwrap();
+
+    let runtime = Arc::new(Runtime::new_fhe(app.params()).unwrap());
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let program = app.get_fhe_program(square).unwrap();
+
+    let executor = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let results = executor.block_on(async {
+        // Start every evaluation before awaiting any of them.
+        let futures = (0..4)
+            .map(|i| {
+                let a = runtime.encrypt(Signed::from(i), &public_key).unwrap();
+
+                runtime.run_async(program, vec![a], &public_key)
+            })
+            .collect::<Vec<_>>();
+
+        let mut results = vec![];
+
+        for f in futures {
+            results.push(f.await.unwrap());
+        }
+
+        results
+    });
+
+    for (i, result) in results.iter().enumerate() {
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, Signed::from((i * i) as i64));
+    }
+}
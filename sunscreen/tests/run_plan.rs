@@ -0,0 +1,158 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, Params, Runtime, RuntimeError,
+};
+
+#[fhe_program(scheme = "bfv")]
+fn square_plus(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a * a + b
+}
+
+#[test]
+fn run_plan_matches_run() {
+    let app = Compiler::new().fhe_program(square_plus).compile().unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let program = app.get_fhe_program(square_plus).unwrap();
+    let plan = runtime.prepare(program).unwrap();
+
+    for i in -5..5 {
+        let a = runtime.encrypt(Signed::from(i), &public_key).unwrap();
+        let b = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+
+        let planned = runtime
+            .run_plan(&plan, vec![a.clone(), b.clone()], &public_key)
+            .unwrap();
+        let direct = runtime.run(program, vec![a, b], &public_key).unwrap();
+
+        let planned: Signed = runtime.decrypt(&planned[0], &private_key).unwrap();
+        let direct: Signed = runtime.decrypt(&direct[0], &private_key).unwrap();
+
+        assert_eq!(planned, Signed::from(i * i + 3));
+        assert_eq!(planned, direct);
+    }
+}
+
+#[test]
+fn run_plan_rejects_plan_from_other_params() {
+    let app = Compiler::new().fhe_program(square_plus).compile().unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, _) = runtime.generate_keys().unwrap();
+
+    let other = Runtime::new_fhe(&Params {
+        plain_modulus: 1024,
+        ..app.params().clone()
+    })
+    .unwrap();
+
+    let plan = other
+        .prepare(app.get_fhe_program(square_plus).unwrap())
+        .unwrap();
+
+    let a = runtime.encrypt(Signed::from(2), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+
+    assert!(matches!(
+        runtime.run_plan(&plan, vec![a, b], &public_key),
+        Err(RuntimeError::ParameterMismatch)
+    ));
+}
+
+#[test]
+fn run_many_runs_each_input_set() {
+    #[fhe_program(scheme = "bfv")]
+    fn simple_add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a + b
+    }
+
+    let app = Compiler::new().fhe_program(simple_add).compile().unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let inputs = (0..100)
+        .map(|i| {
+            vec![
+                runtime.encrypt(Signed::from(i), &public_key).unwrap(),
+                runtime.encrypt(Signed::from(2 * i), &public_key).unwrap(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let results = runtime
+        .run_many(
+            app.get_fhe_program(simple_add).unwrap(),
+            inputs,
+            &public_key,
+        )
+        .unwrap();
+
+    assert_eq!(results.len(), 100);
+
+    for (i, result) in results.iter().enumerate() {
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, Signed::from(3 * i as i64));
+    }
+}
+
+#[test]
+fn run_batched_matches_run_many() {
+    let app = Compiler::new().fhe_program(square_plus).compile().unwrap();
+    let program = app.get_fhe_program(square_plus).unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let inputs = (-10..10)
+        .map(|i| {
+            vec![
+                runtime.encrypt(Signed::from(i), &public_key).unwrap(),
+                runtime.encrypt(Signed::from(i + 1), &public_key).unwrap(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let batched = runtime
+        .run_batched(program, inputs.clone(), &public_key)
+        .unwrap();
+    let serial = runtime.run_many(program, inputs, &public_key).unwrap();
+
+    assert_eq!(batched.len(), 20);
+
+    for ((i, b), s) in (-10i64..10).zip(batched.iter()).zip(serial.iter()) {
+        let b: Signed = runtime.decrypt(&b[0], &private_key).unwrap();
+        let s: Signed = runtime.decrypt(&s[0], &private_key).unwrap();
+
+        assert_eq!(b, Signed::from(i * i + i + 1));
+        assert_eq!(b, s);
+    }
+}
+
+#[test]
+fn run_batched_rejects_ragged_inputs() {
+    let app = Compiler::new().fhe_program(square_plus).compile().unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, _) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(2), &public_key).unwrap();
+
+    let result = runtime.run_batched(
+        app.get_fhe_program(square_plus).unwrap(),
+        vec![vec![a.clone(), a.clone()], vec![a]],
+        &public_key,
+    );
+
+    assert!(matches!(
+        result,
+        Err(RuntimeError::IncorrectCiphertextCount)
+    ));
+}
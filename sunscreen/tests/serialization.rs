@@ -1,7 +1,118 @@
 use seal_fhe::{CoefficientModulus, SecurityLevel};
-use sunscreen::types::bfv::Signed;
-use sunscreen_fhe_program::SchemeType;
-use sunscreen_runtime::{Ciphertext, Params, Runtime};
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    CompiledFheProgram, Compiler, Error, FheApplication, PlainModulusConstraint,
+};
+use sunscreen_fhe_program::{FheProgram, FheProgramTrait, SchemeType, FORMAT_VERSION};
+use sunscreen_runtime::{Ciphertext, Params, PrivateKey, PublicKey, Runtime};
+
+#[fhe_program(scheme = "bfv")]
+fn simple_add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a + b
+}
+
+fn compile_simple_add() -> FheApplication {
+    Compiler::new()
+        .fhe_program(simple_add)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap()
+}
+
+/**
+ * Runs `program` on 15 and 5, returning the decrypted sum.
+ */
+fn run_simple_add(program: &CompiledFheProgram) -> Signed {
+    let runtime = Runtime::new_fhe(&program.metadata.params).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(15), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(5), &public_key).unwrap();
+
+    let result = runtime.run(program, vec![a, b], &public_key).unwrap();
+
+    runtime.decrypt(&result[0], &private_key).unwrap()
+}
+
+#[test]
+fn can_roundtrip_fhe_program_bincode_and_json() {
+    let app = compile_simple_add();
+    let program = app.get_fhe_program(simple_add).unwrap();
+
+    let from_bincode =
+        FheProgram::from_bincode(&program.fhe_program_fn.to_bincode().unwrap()).unwrap();
+    let from_json = FheProgram::from_json(&program.fhe_program_fn.to_json().unwrap()).unwrap();
+
+    for fhe_program_fn in [from_bincode, from_json] {
+        assert!(fhe_program_fn.structurally_eq(&program.fhe_program_fn));
+
+        let restored = CompiledFheProgram {
+            fhe_program_fn,
+            metadata: program.metadata.clone(),
+        };
+
+        assert_eq!(run_simple_add(&restored), run_simple_add(program));
+        assert_eq!(run_simple_add(&restored), Signed::from(20));
+    }
+}
+
+#[test]
+fn can_save_and_load_application() {
+    let path =
+        std::env::temp_dir().join(format!("sunscreen-application-{}.bin", std::process::id()));
+
+    let app = compile_simple_add();
+    app.save(&path).unwrap();
+
+    let loaded = FheApplication::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.params(), app.params());
+    assert_eq!(
+        run_simple_add(loaded.get_fhe_program(simple_add).unwrap()),
+        Signed::from(20)
+    );
+
+    assert!(matches!(
+        FheApplication::load(&path),
+        Err(Error::IoError(_))
+    ));
+}
+
+#[test]
+fn load_rejects_other_format_versions() {
+    let path = std::env::temp_dir().join(format!(
+        "sunscreen-application-version-{}.bin",
+        std::process::id()
+    ));
+
+    compile_simple_add().save(&path).unwrap();
+
+    // bincode writes the version first.
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes[0..4].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    std::fs::write(&path, bytes).unwrap();
+
+    let result = FheApplication::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(
+        result,
+        Err(Error::UnsupportedFormatVersion(v)) if v == FORMAT_VERSION + 1
+    ));
+}
+
+#[test]
+fn lookup_by_name_matches_lookup_by_program() {
+    let app = compile_simple_add();
+
+    let by_program = app.get_fhe_program(simple_add).unwrap();
+    let by_name = app.get_fhe_program("simple_add").unwrap();
+
+    assert!(std::ptr::eq(by_program, by_name));
+    assert!(app.get_fhe_program("simple_multiply").is_none());
+}
 
 #[test]
 fn can_roundtrip_ciphertexts_bincode() {
@@ -62,3 +173,155 @@ fn can_roundtrip_ciphertexts_json() {
     let actual: i64 = v.into();
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn can_roundtrip_ciphertexts_to_bytes() {
+    let params = Params {
+        lattice_dimension: 8192,
+        plain_modulus: 1024,
+        coeff_modulus: CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|c| c.value())
+            .collect(),
+        security_level: SecurityLevel::TC128,
+        scheme_type: SchemeType::Bfv,
+    };
+
+    let runtime = Runtime::new_fhe(&params).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let expected: i64 = 42;
+
+    let c = runtime
+        .encrypt(Signed::from(expected), &public_key)
+        .unwrap();
+
+    let bytes = c.to_bytes().unwrap();
+
+    // Omitting the params makes this smaller than plain bincode.
+    assert!(bytes.len() < bincode::serialize(&c).unwrap().len());
+    assert!(bytes.len() <= c.serialized_size());
+
+    // The bytes only load under the params they were encrypted with.
+    let other_params = Params {
+        lattice_dimension: 4096,
+        coeff_modulus: CoefficientModulus::bfv_default(4096, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|c| c.value())
+            .collect(),
+        ..params.clone()
+    };
+    assert!(Ciphertext::from_bytes(&bytes, &other_params).is_err());
+
+    let c = Ciphertext::from_bytes(&bytes, &params).unwrap();
+
+    let v: Signed = runtime.decrypt(&c, &private_key).unwrap();
+    assert_eq!(i64::from(v), expected);
+}
+
+#[test]
+fn keys_from_bytes_encrypt_and_decrypt() {
+    let params = Params {
+        lattice_dimension: 8192,
+        plain_modulus: 1024,
+        coeff_modulus: CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|c| c.value())
+            .collect(),
+        security_level: SecurityLevel::TC128,
+        scheme_type: SchemeType::Bfv,
+    };
+
+    let runtime = Runtime::new_fhe(&params).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let public_2 = PublicKey::from_bytes(&public_key.to_bytes().unwrap(), &params).unwrap();
+    let private_2 = PrivateKey::from_bytes(&private_key.to_bytes().unwrap(), &params).unwrap();
+
+    let expected: i64 = 42;
+
+    // A key loaded from bytes encrypts values the original private key
+    // decrypts, and vice versa.
+    let c = runtime.encrypt(Signed::from(expected), &public_2).unwrap();
+    let v: Signed = runtime.decrypt(&c, &private_key).unwrap();
+    assert_eq!(i64::from(v), expected);
+
+    let c = runtime
+        .encrypt(Signed::from(expected), &public_key)
+        .unwrap();
+    let v: Signed = runtime.decrypt(&c, &private_2).unwrap();
+    assert_eq!(i64::from(v), expected);
+}
+
+#[test]
+fn keys_from_json_encrypt_and_decrypt() {
+    let params = Params {
+        lattice_dimension: 8192,
+        plain_modulus: 1024,
+        coeff_modulus: CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|c| c.value())
+            .collect(),
+        security_level: SecurityLevel::TC128,
+        scheme_type: SchemeType::Bfv,
+    };
+
+    let runtime = Runtime::new_fhe(&params).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let public_key: PublicKey =
+        serde_json::from_str(&serde_json::to_string(&public_key).unwrap()).unwrap();
+    let private_key: PrivateKey =
+        serde_json::from_str(&serde_json::to_string(&private_key).unwrap()).unwrap();
+
+    let expected: i64 = 42;
+
+    let c = runtime
+        .encrypt(Signed::from(expected), &public_key)
+        .unwrap();
+    let v: Signed = runtime.decrypt(&c, &private_key).unwrap();
+    assert_eq!(i64::from(v), expected);
+}
+
+#[cfg(feature = "deterministic")]
+#[test]
+fn key_from_bytes_produces_same_encryption() {
+    let params = Params {
+        lattice_dimension: 8192,
+        plain_modulus: 1024,
+        coeff_modulus: CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|c| c.value())
+            .collect(),
+        security_level: SecurityLevel::TC128,
+        scheme_type: SchemeType::Bfv,
+    };
+
+    let runtime = Runtime::new_fhe(&params).unwrap();
+
+    let (public_key, _) = runtime.generate_keys().unwrap();
+
+    let public_2 = PublicKey::from_bytes(&public_key.to_bytes().unwrap(), &params).unwrap();
+
+    let seed = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    let c_1 = runtime
+        .encrypt_deterministic(Signed::from(42), &public_key, &seed)
+        .unwrap();
+    let c_2 = runtime
+        .encrypt_deterministic(Signed::from(42), &public_2, &seed)
+        .unwrap();
+
+    assert_eq!(
+        bincode::serialize(&c_1).unwrap(),
+        bincode::serialize(&c_2).unwrap()
+    );
+}
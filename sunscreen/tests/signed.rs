@@ -1,8 +1,9 @@
 use sunscreen::{
     fhe_program,
     types::{bfv::Signed, Cipher},
-    Compiler, FheProgramInput, PlainModulusConstraint, Runtime,
+    Compiler, Error, FheProgramInput, PlainModulusConstraint, Runtime, RuntimeError,
 };
+use sunscreen_fhe_program::{FheProgramTrait, Literal, Operation};
 
 use std::ops::*;
 
@@ -513,7 +514,457 @@ fn can_mul_literal_cipher() {
     assert_eq!(c, mul_fn(-4, a));
 }
 
+#[test]
+fn can_use_bare_literals_in_either_operand_order() {
+    #[fhe_program(scheme = "bfv")]
+    fn literal_ops(
+        a: Cipher<Signed>,
+    ) -> (
+        Cipher<Signed>,
+        Cipher<Signed>,
+        Cipher<Signed>,
+        Cipher<Signed>,
+    ) {
+        (a + 5, 5 + a, a * 2, 2 * a)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(literal_ops)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a_c = runtime.encrypt(Signed::from(-3), &public_key).unwrap();
+    let args: Vec<FheProgramInput> = vec![a_c.into()];
+
+    let result = runtime
+        .run(app.get_fhe_program(literal_ops).unwrap(), args, &public_key)
+        .unwrap();
+
+    let result = result
+        .iter()
+        .map(|c| runtime.decrypt::<Signed>(c, &private_key).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(result, [2, 2, -6, -6].map(Signed::from).to_vec());
+}
+
+#[test]
+fn mixed_subtraction_respects_operand_order() {
+    #[fhe_program(scheme = "bfv")]
+    fn mixed_sub(
+        x: Cipher<Signed>,
+        p: Signed,
+    ) -> (
+        Cipher<Signed>,
+        Cipher<Signed>,
+        Cipher<Signed>,
+        Cipher<Signed>,
+    ) {
+        (x - p, p - x, x - 5, 5 - x)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(mixed_sub)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    for x in [-7, 0, 2, 12] {
+        let x_c = runtime.encrypt(Signed::from(x), &public_key).unwrap();
+        let args: Vec<FheProgramInput> = vec![x_c.into(), Signed::from(5).into()];
+
+        let result = runtime
+            .run(app.get_fhe_program(mixed_sub).unwrap(), args, &public_key)
+            .unwrap();
+
+        let result = result
+            .iter()
+            .map(|c| runtime.decrypt::<Signed>(c, &private_key).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            result,
+            [x - 5, 5 - x, x - 5, 5 - x].map(Signed::from).to_vec(),
+            "x = {x}"
+        );
+    }
+}
+
+#[test]
+fn literal_chains_fold_into_one_literal() {
+    #[fhe_program(scheme = "bfv")]
+    fn chained(a: Cipher<Signed>) -> Cipher<Signed> {
+        a + 5 + 3
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn direct(a: Cipher<Signed>) -> Cipher<Signed> {
+        a + 8
+    }
+
+    let app = Compiler::new()
+        .fhe_program(chained)
+        .fhe_program(direct)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    // The folded literal encodes 8 with different coefficients than
+    // encoding 8 directly, so compare the graphs without literal values.
+    let shape = |name: &str| {
+        let mut ir = app.get_fhe_program(name).unwrap().fhe_program_fn.clone();
+
+        for n in ir.graph.node_weights_mut() {
+            if let Operation::Literal(Literal::Plaintext(p)) = &mut n.operation {
+                p.clear();
+            }
+        }
+
+        ir.deterministic_hash()
+    };
+
+    assert_eq!(shape("chained"), shape("direct"));
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    for program in ["chained", "direct"] {
+        let a_c = runtime.encrypt(Signed::from(-20), &public_key).unwrap();
+
+        let result = runtime
+            .run(
+                app.get_fhe_program(program).unwrap(),
+                vec![a_c],
+                &public_key,
+            )
+            .unwrap();
+
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, Signed::from(-12));
+    }
+}
+
 #[test]
 fn can_create_default() {
     assert_eq!(Into::<i64>::into(Signed::default()), 0);
 }
+
+#[test]
+fn encrypted_zero_is_additive_identity_and_annihilator() {
+    #[fhe_program(scheme = "bfv")]
+    fn zero_ops(
+        zero: Cipher<Signed>,
+        x: Cipher<Signed>,
+    ) -> (Cipher<Signed>, Cipher<Signed>, Cipher<Signed>) {
+        (zero + x, zero * x, x * zero + zero)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(zero_ops)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    for x in [0, 7, -7] {
+        let zero_c = runtime.encrypt(Signed::from(0), &public_key).unwrap();
+        let x_c = runtime.encrypt(Signed::from(x), &public_key).unwrap();
+
+        let args: Vec<FheProgramInput> = vec![zero_c.into(), x_c.into()];
+
+        let result = runtime
+            .run(app.get_fhe_program(zero_ops).unwrap(), args, &public_key)
+            .unwrap();
+
+        let sum: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+        let product: Signed = runtime.decrypt(&result[1], &private_key).unwrap();
+        let product_sum: Signed = runtime.decrypt(&result[2], &private_key).unwrap();
+
+        assert_eq!(sum, x.into());
+        assert_eq!(product, 0.into());
+        assert_eq!(product_sum, 0.into());
+    }
+}
+
+#[test]
+fn can_add_plaintext_zero() {
+    #[fhe_program(scheme = "bfv")]
+    fn add_zero(x: Cipher<Signed>, zero: Signed) -> Cipher<Signed> {
+        x + zero
+    }
+
+    let app = Compiler::new()
+        .fhe_program(add_zero)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let x_c = runtime.encrypt(Signed::from(7), &public_key).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![x_c.into(), Signed::from(0).into()];
+
+    let result = runtime
+        .run(app.get_fhe_program(add_zero).unwrap(), args, &public_key)
+        .unwrap();
+
+    let sum: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(sum, 7.into());
+}
+
+// Multiplying by a plaintext zero produces a transparent ciphertext.
+#[cfg(feature = "transparent-ciphertexts")]
+#[test]
+fn can_mul_plaintext_zero() {
+    #[fhe_program(scheme = "bfv")]
+    fn mul_zero(x: Cipher<Signed>, zero: Signed) -> Cipher<Signed> {
+        x * zero
+    }
+
+    let app = Compiler::new()
+        .fhe_program(mul_zero)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let x_c = runtime.encrypt(Signed::from(7), &public_key).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![x_c.into(), Signed::from(0).into()];
+
+    let result = runtime
+        .run(app.get_fhe_program(mul_zero).unwrap(), args, &public_key)
+        .unwrap();
+
+    let product: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(product, 0.into());
+}
+
+#[test]
+fn warns_when_additions_may_overflow_plain_modulus() {
+    #[fhe_program(scheme = "bfv")]
+    fn sum_40(a: Cipher<Signed>) -> Cipher<Signed> {
+        let mut sum = a;
+
+        for _ in 0..40 {
+            sum = sum + a;
+        }
+
+        sum
+    }
+
+    let compile = |plain_modulus| {
+        Compiler::new()
+            .fhe_program(sum_40)
+            .plain_modulus_constraint(PlainModulusConstraint::Raw(plain_modulus))
+            .compile()
+            .unwrap()
+    };
+
+    let app = compile(64);
+    let warnings = app.overflow_warnings(sum_40).unwrap();
+
+    // The 31st addition's coefficients may reach 32, which a plain
+    // modulus of 64 can't distinguish from -32.
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].bound.max_abs, 32);
+
+    let program = app.get_fhe_program(sum_40).unwrap();
+    assert_eq!(
+        program.fhe_program_fn.graph[warnings[0].node].operation,
+        Operation::Add
+    );
+
+    let app = compile(500);
+    assert!(app.overflow_warnings(sum_40).unwrap().is_empty());
+}
+
+#[test]
+fn can_shift_cipher() {
+    #[fhe_program(scheme = "bfv")]
+    fn shift(a: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>) {
+        (a >> 2, a << 3)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(shift)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    for (x, shr, shl) in [(8, 2, 64), (-8, -2, -64), (7, 1, 56), (1, 0, 8)] {
+        let a_c = runtime.encrypt(Signed::from(x), &public_key).unwrap();
+
+        let result = runtime
+            .run(app.get_fhe_program(shift).unwrap(), vec![a_c], &public_key)
+            .unwrap();
+
+        let right: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+        let left: Signed = runtime.decrypt(&result[1], &private_key).unwrap();
+
+        assert_eq!(right, shr.into(), "{} >> 2", x);
+        assert_eq!(left, shl.into(), "{} << 3", x);
+    }
+}
+
+#[test]
+fn shift_right_rejects_computed_values() {
+    #[fhe_program(scheme = "bfv")]
+    fn double_then_halve(a: Cipher<Signed>) -> Cipher<Signed> {
+        (a + a) >> 1
+    }
+
+    let result = Compiler::new()
+        .fhe_program(double_then_halve)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile();
+
+    assert!(matches!(result, Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn can_pow_cipher() {
+    #[fhe_program(scheme = "bfv")]
+    fn pow_4(a: Cipher<Signed>) -> Cipher<Signed> {
+        a.pow(4)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(pow_4)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let program = app.get_fhe_program(pow_4).unwrap();
+
+    assert_eq!(program.operation_count().multiplications, 2);
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a_c = runtime.encrypt(Signed::from(-3), &public_key).unwrap();
+
+    let result = runtime.run(program, vec![a_c], &public_key).unwrap();
+
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, 81.into());
+}
+
+#[test]
+fn rejects_pow_0() {
+    #[fhe_program(scheme = "bfv")]
+    fn pow_0(a: Cipher<Signed>) -> Cipher<Signed> {
+        a.pow(0)
+    }
+
+    let result = Compiler::new().fhe_program(pow_0).compile();
+
+    assert!(matches!(result, Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn rejects_out_of_range_shift() {
+    #[fhe_program(scheme = "bfv")]
+    fn shift_63(a: Cipher<Signed>) -> Cipher<Signed> {
+        a << 63
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn shift_negative(a: Cipher<Signed>) -> Cipher<Signed> {
+        a >> -1
+    }
+
+    for result in [
+        Compiler::new().fhe_program(shift_63).compile(),
+        Compiler::new().fhe_program(shift_negative).compile(),
+    ] {
+        assert!(matches!(result, Err(Error::Unsupported(_))));
+    }
+}
+
+#[test]
+fn decrypt_checked_detects_overflow() {
+    #[fhe_program(scheme = "bfv")]
+    fn sum_n(a: Cipher<Signed>, b: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>) {
+        let mut small = a;
+        let mut large = b;
+
+        for _ in 0..10 {
+            small = small + a;
+        }
+
+        for _ in 0..40 {
+            large = large + b;
+        }
+
+        (small, large)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(sum_n)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(64))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(1), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(1), &public_key).unwrap();
+
+    let result = runtime
+        .run(app.get_fhe_program(sum_n).unwrap(), vec![a, b], &public_key)
+        .unwrap();
+
+    let small: Signed = runtime.decrypt_checked(&result[0], &private_key).unwrap();
+    assert_eq!(small, Signed::from(11));
+
+    // 41 wraps modulo 64 to -23.
+    let wrapped: Signed = runtime.decrypt(&result[1], &private_key).unwrap();
+    assert_eq!(wrapped, Signed::from(-23));
+
+    assert_eq!(
+        runtime.decrypt_checked::<Signed>(&result[1], &private_key),
+        Err(RuntimeError::OverflowDetected {
+            recovered_value: -23
+        })
+    );
+
+    assert!(Signed::is_in_range(16, app.params()));
+    assert!(!Signed::is_in_range(-41, app.params()));
+}
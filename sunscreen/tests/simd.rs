@@ -1,7 +1,9 @@
+use seal_fhe::CoefficientModulus;
 use sunscreen::{
     fhe_program,
-    types::{bfv::Batched, Cipher, SwapRows},
-    Compiler, FheProgramInput, PlainModulusConstraint, Runtime,
+    types::{bfv::Batched, Cipher, Rotate, SwapRows},
+    Compiler, Error, FheProgramInput, Params, PlainModulusConstraint, RequiredKeys, Runtime,
+    SchemeType, SecurityLevel,
 };
 
 use std::ops::*;
@@ -132,6 +134,257 @@ fn can_rotate_right_cipher() {
     assert_eq!(c, shr_impl(a, 1));
 }
 
+#[test]
+fn can_rotate_cipher_by_power_of_two() {
+    fn rotate_impl<T>(x: T, n: i32) -> T
+    where
+        T: Rotate<Output = T>,
+    {
+        x.rotate(n)
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn rotate(a: Cipher<Batched<4>>) -> (Cipher<Batched<4>>, Cipher<Batched<4>>) {
+        (rotate_impl(a, 2), rotate_impl(a, -2))
+    }
+
+    let app = Compiler::new()
+        .fhe_program(rotate)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let program = app.get_fhe_program(rotate).unwrap();
+
+    assert!(program
+        .metadata
+        .required_keys
+        .contains(&RequiredKeys::Galois));
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let data = [vec![1, 2, 3, 4], vec![5, 6, 7, 8]];
+
+    let a = Batched::<4>::try_from(data).unwrap();
+    let a_c = runtime.encrypt(a, &public_key).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![a_c.into()];
+
+    let result = runtime.run(program, args, &public_key).unwrap();
+
+    let left: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+    let right: Batched<4> = runtime.decrypt(&result[1], &private_key).unwrap();
+
+    assert_eq!(left, rotate_impl(a, 2));
+    assert_eq!(right, rotate_impl(a, -2));
+    assert_eq!(left, [[3, 4, 1, 2], [7, 8, 5, 6]].into());
+}
+
+#[test]
+fn can_rotate_left_and_right_by_method() {
+    #[fhe_program(scheme = "bfv")]
+    fn rotate(a: Cipher<Batched<4>>) -> (Cipher<Batched<4>>, Cipher<Batched<4>>) {
+        (a.rotate_left(1), a.rotate_right(3))
+    }
+
+    let app = Compiler::new()
+        .fhe_program(rotate)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+    let a_c = runtime.encrypt(a, &public_key).unwrap();
+
+    let result = runtime
+        .run(app.get_fhe_program(rotate).unwrap(), vec![a_c], &public_key)
+        .unwrap();
+
+    let left: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+    let right: Batched<4> = runtime.decrypt(&result[1], &private_key).unwrap();
+
+    // Rotating right by 3 of 4 lanes is the same as rotating left by 1.
+    assert_eq!(left, [[2, 3, 4, 1], [6, 7, 8, 5]].into());
+    assert_eq!(right, left);
+}
+
+#[test]
+fn can_compare_ciphers_for_equality() {
+    #[fhe_program(scheme = "bfv")]
+    fn equal(a: Cipher<Batched<4>>, b: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a.equals(b)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(equal)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Batched::<4>::try_from([vec![5, 5, 0, -3], vec![5, 5, 1, 7]]).unwrap();
+    let b = Batched::<4>::try_from([vec![5, 6, 0, -3], vec![6, 5, -1, 7]]).unwrap();
+
+    let a_c = runtime.encrypt(a, &public_key).unwrap();
+    let b_c = runtime.encrypt(b, &public_key).unwrap();
+
+    let result = runtime
+        .run(
+            app.get_fhe_program(equal).unwrap(),
+            vec![a_c, b_c],
+            &public_key,
+        )
+        .unwrap();
+
+    let c: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, [[1, 0, 1, 1], [0, 1, 0, 1]].into());
+}
+
+#[test]
+fn can_mask_cipher_slots() {
+    #[fhe_program(scheme = "bfv")]
+    fn mask(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a.mask(&[true, false, true, false])
+    }
+
+    let app = Compiler::new()
+        .fhe_program(mask)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+    let a_c = runtime.encrypt(a, &public_key).unwrap();
+
+    let result = runtime
+        .run(app.get_fhe_program(mask).unwrap(), vec![a_c], &public_key)
+        .unwrap();
+
+    let c: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, [[1, 0, 3, 0], [5, 0, 7, 0]].into());
+}
+
+#[test]
+fn mask_rejects_empty_pattern() {
+    #[fhe_program(scheme = "bfv")]
+    fn mask(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a.mask(&[false; 4])
+    }
+
+    let result = Compiler::new()
+        .fhe_program(mask)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile();
+
+    assert!(matches!(result, Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn mul_slots_rejects_wrong_length() {
+    #[fhe_program(scheme = "bfv")]
+    fn scale(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a.mul_slots(&[1, 2, 3])
+    }
+
+    let result = Compiler::new()
+        .fhe_program(scale)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile();
+
+    assert!(matches!(result, Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn mul_slots_rejects_all_zero_scalars() {
+    #[fhe_program(scheme = "bfv")]
+    fn scale(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a.mul_slots(&[0; 4])
+    }
+
+    let result = Compiler::new()
+        .fhe_program(scale)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile();
+
+    assert!(matches!(result, Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn can_multiply_slots_by_scalars() {
+    #[fhe_program(scheme = "bfv")]
+    fn scale(a: Cipher<Batched<4>>) -> (Cipher<Batched<4>>, Cipher<Batched<4>>) {
+        (
+            a.mul_slots(&[1, 2, 3, 4]),
+            a.mul_slots(&[1, 2, 3, 4, -1, -2, -3, -4]),
+        )
+    }
+
+    let app = Compiler::new()
+        .fhe_program(scale)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+    let a_c = runtime.encrypt(a, &public_key).unwrap();
+
+    let result = runtime
+        .run(app.get_fhe_program(scale).unwrap(), vec![a_c], &public_key)
+        .unwrap();
+
+    let both_rows: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+    let per_row: Batched<4> = runtime.decrypt(&result[1], &private_key).unwrap();
+
+    assert_eq!(both_rows, [[1, 4, 9, 16], [5, 12, 21, 32]].into());
+    assert_eq!(per_row, [[1, 4, 9, 16], [-5, -12, -21, -32]].into());
+}
+
+#[test]
+fn equality_requires_prime_plain_modulus() {
+    #[fhe_program(scheme = "bfv")]
+    fn equal(a: Cipher<Batched<4>>, b: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a.equals(b)
+    }
+
+    let result = Compiler::new()
+        .fhe_program(equal)
+        .with_params(&Params {
+            lattice_dimension: 4096,
+            plain_modulus: 65536,
+            coeff_modulus: CoefficientModulus::bfv_default(4096, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|x| x.value())
+                .collect(),
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        })
+        .compile();
+
+    assert!(matches!(result, Err(Error::Unsupported(_))));
+}
+
 #[test]
 fn can_add_cipher_cipher() {
     fn add_impl<T>(a: T, b: T) -> T
@@ -301,3 +554,46 @@ fn can_neg_cipher_cipher() {
 
     assert_eq!(c, neg_impl(a));
 }
+
+#[test]
+fn generates_only_the_galois_keys_a_program_needs() {
+    #[fhe_program(scheme = "bfv")]
+    fn rotate(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        (a << 1).swap_rows() + (a >> 2)
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn double(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a + a
+    }
+
+    let app = Compiler::new()
+        .fhe_program(rotate)
+        .fhe_program(double)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let rotate_program = app.get_fhe_program(rotate).unwrap();
+    let double_program = app.get_fhe_program(double).unwrap();
+
+    let (public_key, _) = runtime.generate_keys_with_galois(double_program).unwrap();
+
+    assert!(public_key.galois_key.is_none());
+
+    let (public_key, private_key) = runtime.generate_keys_with_galois(rotate_program).unwrap();
+
+    assert!(public_key.galois_key.is_some());
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+    let a_c = runtime.encrypt(a, &public_key).unwrap();
+
+    let result = runtime.run(rotate_program, vec![a_c], &public_key).unwrap();
+
+    let c: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, ((a << 1).swap_rows() + (a >> 2)));
+}
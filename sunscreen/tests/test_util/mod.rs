@@ -0,0 +1,145 @@
+//! Shared helpers for integration tests.
+
+use petgraph::{algo::toposort, visit::EdgeRef, Direction};
+use sunscreen::{
+    types::{bfv::Signed, Cipher, TypeName},
+    CompiledFheProgram, FheProgramInput, FheRuntime,
+};
+use sunscreen_compiler_common::{EdgeInfo, GraphQuery};
+use sunscreen_fhe_program::{FheProgram, Operation};
+
+/**
+ * A tiny deterministic PRNG (SplitMix64) so fuzzing runs are
+ * reproducible from a seed.
+ */
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /**
+     * Returns a value in `[-2^15, 2^15)`. Keeping inputs small ensures
+     * a few levels of multiplication stay within the plaintext modulus.
+     */
+    fn next_input(&mut self) -> i64 {
+        (self.next_u64() as i64) >> 48
+    }
+}
+
+/**
+ * Evaluates the given FHE program in the clear, treating every
+ * ciphertext as a [`Signed`] value.
+ */
+fn interpret(program: &FheProgram, inputs: &[i64]) -> Vec<i64> {
+    let graph = &program.graph.0;
+    let query = GraphQuery::new(graph);
+    let order = toposort(graph, None).expect("FHE program has a cycle");
+
+    let mut values = vec![None; graph.node_bound()];
+    let mut outputs = vec![];
+
+    let operand = |values: &[Option<i64>], id, edge| {
+        let parent = graph
+            .edges_directed(id, Direction::Incoming)
+            .find(|e| *e.weight() == edge)
+            .map(|e| e.source())
+            .expect("Missing operand");
+
+        values[parent.index()].expect("Operand not yet evaluated")
+    };
+
+    for id in order {
+        let node = query.get_node(id).unwrap();
+
+        let value = match &node.operation {
+            Operation::InputCiphertext(i) => inputs[*i],
+            Operation::Add | Operation::Sub | Operation::Multiply => {
+                let left = operand(&values, id, EdgeInfo::Left);
+                let right = operand(&values, id, EdgeInfo::Right);
+
+                match node.operation {
+                    Operation::Add => left.wrapping_add(right),
+                    Operation::Sub => left.wrapping_sub(right),
+                    _ => left.wrapping_mul(right),
+                }
+            }
+            Operation::Negate => operand(&values, id, EdgeInfo::Unary).wrapping_neg(),
+            Operation::Relinearize | Operation::ModSwitch => operand(&values, id, EdgeInfo::Unary),
+            Operation::OutputCiphertext => {
+                let x = operand(&values, id, EdgeInfo::Unary);
+                outputs.push((id, x));
+                x
+            }
+            op => panic!("Cleartext interpreter does not support {op:?}"),
+        };
+
+        values[id.index()] = Some(value);
+    }
+
+    // The runtime returns outputs in node index order.
+    outputs.sort_by_key(|(id, _)| *id);
+
+    outputs.into_iter().map(|(_, x)| x).collect()
+}
+
+/**
+ * Runs `program` on `iterations` sets of random inputs both
+ * homomorphically and through a cleartext interpreter, asserting the
+ * results match.
+ *
+ * # Remarks
+ * Every argument and return value of `program` must be a
+ * `Cipher<Signed>`. The inputs are derived from `seed`, so a failing
+ * iteration can be reproduced by rerunning with the same seed.
+ */
+pub fn fuzz_program(program: &CompiledFheProgram, iterations: usize, seed: u64) {
+    let signature = &program.metadata.signature;
+
+    assert!(
+        signature
+            .arguments
+            .iter()
+            .chain(signature.returns.iter())
+            .all(|t| *t == Cipher::<Signed>::type_name()),
+        "fuzz_program only supports programs over Cipher<Signed>"
+    );
+
+    let runtime = FheRuntime::new(&program.metadata.params).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let mut rng = SplitMix64(seed);
+
+    for i in 0..iterations {
+        let inputs = signature
+            .arguments
+            .iter()
+            .map(|_| rng.next_input())
+            .collect::<Vec<_>>();
+
+        let expected = interpret(&program.fhe_program_fn, &inputs);
+
+        let args = inputs
+            .iter()
+            .map(|x| runtime.encrypt(Signed::from(*x), &public_key).unwrap())
+            .map(FheProgramInput::from)
+            .collect::<Vec<_>>();
+
+        let actual = runtime
+            .run(program, args, &public_key)
+            .unwrap()
+            .iter()
+            .map(|c| i64::from(runtime.decrypt::<Signed>(c, &private_key).unwrap()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            actual, expected,
+            "Mismatch on iteration {i} (seed {seed}) with inputs {inputs:?}"
+        );
+    }
+}
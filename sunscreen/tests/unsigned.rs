@@ -5,7 +5,7 @@ use proptest::prelude::{prop::num::u64::ANY, prop_assert_eq, proptest, ProptestC
 use sunscreen::{
     fhe_program,
     types::{
-        bfv::{Unsigned, Unsigned256},
+        bfv::{Unsigned, Unsigned256, Unsigned64, UnsignedN},
         Cipher,
     },
     Compiler, FheApplication, FheProgramInput, FheRuntime, PrivateKey, PublicKey,
@@ -163,3 +163,60 @@ fn carry_at_limb_boundary() {
     let b = U256::from_words([0, 1, 0, 0]);
     run_with(a, b, U256::wrapping_add, add, add_plain);
 }
+
+#[test]
+fn plain_minus_cipher_respects_operand_order() {
+    #[fhe_program(scheme = "bfv")]
+    fn sub_from_plain(a: Unsigned64, b: Cipher<Unsigned64>) -> Cipher<Unsigned64> {
+        a - b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(sub_from_plain)
+        .compile()
+        .unwrap();
+    let rt = FheRuntime::new(app.params()).unwrap();
+    let (pk, sk) = rt.generate_keys().unwrap();
+
+    for b in [U64::from_u64(3), U64::from_u64(5), U64::from_u64(7)] {
+        let a = U64::from_u64(5);
+        let b_c = rt.encrypt(Unsigned64::from(b), &pk).unwrap();
+        let args: Vec<FheProgramInput> = vec![Unsigned64::from(a).into(), b_c.into()];
+
+        let result = rt
+            .run(app.get_fhe_program(sub_from_plain).unwrap(), args, &pk)
+            .unwrap();
+
+        let c: Unsigned64 = rt.decrypt(&result[0], &sk).unwrap();
+
+        assert_eq!(a.wrapping_sub(&b), c.into());
+    }
+}
+
+#[test]
+fn can_run_8_bit_unsigned() {
+    #[fhe_program(scheme = "bfv")]
+    fn mul_add(
+        a: Cipher<UnsignedN<8>>,
+        b: Cipher<UnsignedN<8>>,
+        c: UnsignedN<8>,
+    ) -> Cipher<UnsignedN<8>> {
+        a * b + c
+    }
+
+    let app = Compiler::new().fhe_program(mul_add).compile().unwrap();
+    let rt = FheRuntime::new(app.params()).unwrap();
+    let (pk, sk) = rt.generate_keys().unwrap();
+
+    let a = rt.encrypt(UnsignedN::<8>::from(12u8), &pk).unwrap();
+    let b = rt.encrypt(UnsignedN::<8>::from(13u8), &pk).unwrap();
+    let args: Vec<FheProgramInput> = vec![a.into(), b.into(), UnsignedN::<8>::from(44u8).into()];
+
+    let result = rt
+        .run(app.get_fhe_program(mul_add).unwrap(), args, &pk)
+        .unwrap();
+
+    let c: UnsignedN<8> = rt.decrypt(&result[0], &sk).unwrap();
+
+    assert_eq!(u8::from(c), 200);
+}
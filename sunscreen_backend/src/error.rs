@@ -1,4 +1,5 @@
 use seal_fhe::Error as SealError;
+use sunscreen_compiler_common::transforms::CompilerPassError;
 use sunscreen_runtime::FheProgramRunFailure as RuntimeError;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -41,6 +42,23 @@ pub enum Error {
      * [`TargetNoiseLevel::NotApplicable`](crate::noise_model::TargetNoiseLevel::NotApplicable).
      */
     NotApplicable,
+
+    /**
+     * The named compiler pass (first argument) produced an FHE program
+     * that fails validation (second argument).
+     */
+    GraphInvariantViolation(String, sunscreen_fhe_program::Error),
+
+    /**
+     * A user-defined pass was placed relative to a pass that doesn't
+     * exist.
+     */
+    UnknownPass(String),
+
+    /**
+     * The named user-defined compiler pass (first argument) failed.
+     */
+    PassFailed(String, CompilerPassError),
 }
 
 impl From<sunscreen_fhe_program::Error> for Error {
@@ -11,13 +11,21 @@ mod error;
  * A module for performing noise estimation on FHE programs.
  */
 pub mod noise_model;
+/**
+ * A module for statically detecting plaintext overflow in FHE programs.
+ */
+pub mod overflow;
 mod transforms;
 
 pub use error::*;
 
 use sunscreen_fhe_program::FheProgram;
+use sunscreen_runtime::Params;
 
-use transforms::transform_intermediate_representation;
+use transforms::{
+    transform_intermediate_representation, transform_intermediate_representation_with_passes,
+};
+pub use transforms::{CompileOptions, FheCompilerPass, PassPosition, PassTiming, UserPass};
 
 /**
  * Clones the given [`FheProgram`] and compiles it.
@@ -25,7 +33,8 @@ use transforms::transform_intermediate_representation;
 pub fn compile(ir: &FheProgram) -> FheProgram {
     let mut clone = ir.clone();
 
-    transform_intermediate_representation(&mut clone);
+    // Passes only fail when checking invariants, so this can't fail.
+    transform_intermediate_representation(&mut clone, &CompileOptions::default()).unwrap();
 
     clone
 }
@@ -34,7 +43,69 @@ pub fn compile(ir: &FheProgram) -> FheProgram {
  * Consumes the given [`FheProgram`] and compiles it.
  */
 pub fn compile_inplace(mut ir: FheProgram) -> FheProgram {
-    transform_intermediate_representation(&mut ir);
+    // Passes only fail when checking invariants, so this can't fail.
+    transform_intermediate_representation(&mut ir, &CompileOptions::default()).unwrap();
 
     ir
 }
+
+/**
+ * Consumes the given [`FheProgram`] and compiles it, validating the
+ * graph after every transformation.
+ *
+ * # Remarks
+ * This is a development aid for catching passes that break graph
+ * invariants. If a pass produces an invalid graph, returns
+ * [`Error::GraphInvariantViolation`] with the name of the offending pass
+ * and the validation errors.
+ */
+pub fn compile_inplace_with_invariant_check(mut ir: FheProgram) -> Result<FheProgram> {
+    transform_intermediate_representation(
+        &mut ir,
+        &CompileOptions {
+            check_invariants: true,
+            ..CompileOptions::default()
+        },
+    )?;
+
+    Ok(ir)
+}
+
+/**
+ * Consumes the given [`FheProgram`] and compiles it under the given
+ * options, returning how long each transformation took.
+ *
+ * # Remarks
+ * When [`CompileOptions::check_invariants`] is set, this validates the
+ * graph after every transformation as in
+ * [`compile_inplace_with_invariant_check`].
+ */
+pub fn compile_inplace_timed(
+    mut ir: FheProgram,
+    options: &CompileOptions,
+) -> Result<(FheProgram, Vec<PassTiming>)> {
+    let timings = transform_intermediate_representation(&mut ir, options)?;
+
+    Ok((ir, timings))
+}
+
+/**
+ * Like [`compile_inplace_timed`], but also runs the given user-defined
+ * passes, placing each at its [`PassPosition`].
+ *
+ * # Remarks
+ * User passes run under `params`. Returns [`Error::UnknownPass`] if a
+ * pass is placed relative to a pass that doesn't exist and
+ * [`Error::PassFailed`] if a user pass fails.
+ */
+pub fn compile_inplace_with_passes(
+    mut ir: FheProgram,
+    options: &CompileOptions,
+    user_passes: &[UserPass],
+    params: &Params,
+) -> Result<(FheProgram, Vec<PassTiming>)> {
+    let timings =
+        transform_intermediate_representation_with_passes(&mut ir, options, user_passes, params)?;
+
+    Ok((ir, timings))
+}
@@ -1,8 +1,6 @@
 use crate::{noise_model::NoiseModel, Error, Result};
-use num::{BigUint, ToPrimitive};
-use sunscreen_runtime::Params;
-
-use super::noise_budget_to_noise;
+use num::BigUint;
+use sunscreen_runtime::{CanonicalEmbeddingNorm, Params};
 
 #[derive(Clone)]
 /**
@@ -10,7 +8,9 @@ use super::noise_budget_to_noise;
  * embedding norm of ciphertexts' randomness as an upper bound.
  *
  * # Remarks
- * The implemented heuristics come from the following sources:
+ * The bounds themselves live in the runtime's
+ * [`CanonicalEmbeddingNorm`], which its static noise estimate shares.
+ * They come from the following sources:
  * * `encrypt`: "Optimizations of Fully Homomorphic Encryption" by Ilia Iliashenko, page 45.
  * * `add_ct_ct`: SEAL 2.3.1 release notes page 12.
  * * `add_ct_pt`: SEAL 2.3.1 release notes page 13.
@@ -23,6 +23,8 @@ pub struct CanonicalEmbeddingNormModel {
      * The BFV scheme parameters to use for this model.
      */
     pub params: Params,
+
+    bounds: CanonicalEmbeddingNorm,
 }
 
 impl CanonicalEmbeddingNormModel {
@@ -31,20 +33,16 @@ impl CanonicalEmbeddingNormModel {
      *
      * # Remarks
      * Returns [`Error::InvalidParams`] if the given parameters:
-     * * contain fewer than 2 coefficient modulus chain values
+     * * don't use the BFV scheme
+     * * contain no coefficient modulus chain values
      * * have a plain modulus < 2
      */
     pub fn new(params: &Params) -> Result<Self> {
-        if params.coeff_modulus.is_empty() {
-            return Err(Error::InvalidParams);
-        }
-
-        if params.plain_modulus < 2 {
-            return Err(Error::InvalidParams);
-        }
+        let bounds = CanonicalEmbeddingNorm::new(params).map_err(|_| Error::InvalidParams)?;
 
         Ok(Self {
             params: params.clone(),
+            bounds,
         })
     }
 
@@ -77,17 +75,7 @@ impl CanonicalEmbeddingNormModel {
 
 impl NoiseModel for CanonicalEmbeddingNormModel {
     fn encrypt(&self) -> f64 {
-        let t = self.params.plain_modulus as f64;
-        let q = self
-            .total_q()
-            .to_f64()
-            .expect("Failed to convert BigUInt to f64");
-        let n = self.params.lattice_dimension as f64;
-
-        let noise = t * (n * (t - 1f64) / 2f64)
-            + 2f64 * super::NOISE_STD_DEV * f64::sqrt(12f64 * n * n + 9f64 * n);
-
-        noise / q
+        self.bounds.encrypt()
     }
 
     fn add_ct_ct(&self, a_invariant_noise: f64, b_invariant_noise: f64) -> f64 {
@@ -95,44 +83,15 @@ impl NoiseModel for CanonicalEmbeddingNormModel {
     }
 
     fn add_ct_pt(&self, ct_invariant_noise: f64) -> f64 {
-        let r_t = self
-            .r_t()
-            .to_f64()
-            .expect("Failed to convert BigUInt to f64");
-
-        let q = self
-            .total_q()
-            .to_f64()
-            .expect("Failed to convert BigUInt to f64");
-
-        let pt_noise =
-            r_t * self.params.lattice_dimension as f64 * self.params.plain_modulus as f64;
-
-        ct_invariant_noise + pt_noise / q
+        self.bounds.add_ct_pt(ct_invariant_noise)
     }
 
     fn mul_ct_ct(&self, a_invariant_noise: f64, b_invariant_noise: f64) -> f64 {
-        let q = self
-            .total_q()
-            .to_f64()
-            .expect("Failed to convert BigUInt to f64");
-        let t = self.params.plain_modulus as f64;
-        let n = self.params.lattice_dimension as f64;
-
-        let term_0 = t * f64::sqrt(3. * n + 2. * n * n) * (a_invariant_noise + b_invariant_noise);
-
-        let term_1 = 3. * a_invariant_noise + b_invariant_noise;
-
-        let term_2 = (t / q) * f64::sqrt(3f64 * n + 2f64 * n * n + 4. / 3. * n * n * n);
-
-        term_0 + term_1 + term_2
+        self.bounds.mul_ct_ct(a_invariant_noise, b_invariant_noise)
     }
 
     fn mul_ct_pt(&self, a_invariant_noise: f64) -> f64 {
-        let n = self.params.lattice_dimension as f64;
-        let t = self.params.plain_modulus as f64;
-
-        a_invariant_noise * n * (t - 1.)
+        self.bounds.mul_ct_pt(a_invariant_noise)
     }
 
     fn relinearize(&self, a_invariant_noise: f64) -> f64 {
@@ -156,18 +115,15 @@ impl NoiseModel for CanonicalEmbeddingNormModel {
     }
 
     fn shift_left(&self, a_invariant_noise: f64, _places: i32) -> f64 {
-        // TODO: Make a real heuristic
-        a_invariant_noise + noise_budget_to_noise(8.)
+        self.bounds.rotate(a_invariant_noise)
     }
 
     fn shift_right(&self, a_invariant_noise: f64, _places: i32) -> f64 {
-        // TODO: Make a real heuristic
-        a_invariant_noise + noise_budget_to_noise(8.)
+        self.bounds.rotate(a_invariant_noise)
     }
 
     fn swap_rows(&self, a_invariant_noise: f64) -> f64 {
-        // TODO: Make a real heuristic
-        a_invariant_noise + noise_budget_to_noise(8.)
+        self.bounds.rotate(a_invariant_noise)
     }
 }
 
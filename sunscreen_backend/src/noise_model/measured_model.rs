@@ -238,7 +238,6 @@ pub struct MeasuredModel {
 }
 
 fn create_seal_params(params: &Params) -> Result<EncryptionParameters> {
-    #[allow(unreachable_patterns)]
     match params.scheme_type {
         FheProgramSchemeType::Bfv => {
             let plaintext_modulus = PlainModulus::raw(params.plain_modulus)?;
@@ -351,6 +350,11 @@ impl MeasuredModel {
 
         let evaluator = match ir.data {
             FheProgramSchemeType::Bfv => BFVEvaluator::new(&context).unwrap(),
+            // CKKS noise isn't measured the same way, and BGV's modulus
+            // switching isn't modeled.
+            FheProgramSchemeType::Ckks | FheProgramSchemeType::Bgv => {
+                return Err(Error::InvalidParams)
+            }
         };
 
         let (relin_keys, galois_keys) = make_relin_galois_keys(ir, &keygen)?;
@@ -98,6 +98,13 @@ pub fn predict_noise(model: &(dyn NoiseModel + Sync), fhe_program: &FheProgram)
 
                     model.neg(noise_levels[x.index()].load())
                 }
+                ModSwitch => {
+                    // Models track BFV's invariant noise, and BFV
+                    // programs never switch moduli.
+                    let x = query.get_unary_operand(node_id).unwrap();
+
+                    noise_levels[x.index()].load()
+                }
                 Sub => {
                     let (left, right) = query.get_binary_operands(node_id).unwrap();
 
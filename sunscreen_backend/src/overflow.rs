@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use petgraph::{algo::toposort, stable_graph::NodeIndex, Direction};
+use sunscreen_fhe_program::{FheProgram, Literal, Operation};
+use sunscreen_runtime::InnerPlaintext;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * A conservative bound on the plaintext polynomial a node's value
+ * encodes.
+ */
+pub struct CoefficientBound {
+    /**
+     * The largest magnitude of any coefficient.
+     */
+    pub max_abs: u128,
+
+    /**
+     * The number of (possibly) nonzero coefficients.
+     */
+    pub len: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * A node whose coefficients may exceed what the plaintext modulus can
+ * faithfully represent.
+ */
+pub struct OverflowWarning {
+    /**
+     * The first node along its path whose bound overflows.
+     */
+    pub node: NodeIndex,
+
+    /**
+     * The node's coefficient bound.
+     */
+    pub bound: CoefficientBound,
+}
+
+/**
+ * Statically finds the additions, subtractions, and multiplications
+ * in `ir` whose results may not decode correctly under
+ * `plain_modulus` and `lattice_dimension`.
+ *
+ * # Remarks
+ * Each input is assumed to encode a polynomial within `input_bound`.
+ * Literal plaintexts use their actual coefficients. A coefficient `c`
+ * decodes faithfully when `2|c| < plain_modulus`, so this reports each
+ * node whose bound exceeds that while its operands' bounds don't.
+ * Nodes downstream of an overflow aren't reported again.
+ *
+ * The analysis is conservative: a warning means overflow is possible
+ * for some inputs within the bound, not that it occurs.
+ */
+pub fn find_overflows(
+    ir: &FheProgram,
+    plain_modulus: u64,
+    lattice_dimension: u64,
+    input_bound: CoefficientBound,
+) -> Vec<OverflowWarning> {
+    let overflows = |b: &CoefficientBound| b.max_abs.saturating_mul(2) >= plain_modulus as u128;
+
+    let mut bounds: HashMap<NodeIndex, CoefficientBound> = HashMap::new();
+    let mut warnings = vec![];
+
+    // FHE programs are DAGs, so this can't fail.
+    for id in toposort(&ir.graph.0, None).unwrap() {
+        let operands = ir
+            .graph
+            .neighbors_directed(id, Direction::Incoming)
+            .filter_map(|p| bounds.get(&p).copied())
+            .collect::<Vec<_>>();
+
+        let bound = match &ir.graph[id].operation {
+            Operation::InputCiphertext(_) | Operation::InputPlaintext(_) => input_bound,
+            Operation::Literal(Literal::Plaintext(p)) => {
+                literal_bound(p, plain_modulus).unwrap_or(input_bound)
+            }
+            // Rotation amounts aren't plaintext values.
+            Operation::Literal(Literal::U64(_)) => continue,
+            Operation::Add | Operation::AddPlaintext | Operation::Sub | Operation::SubPlaintext => {
+                CoefficientBound {
+                    max_abs: operands
+                        .iter()
+                        .map(|b| b.max_abs)
+                        .fold(0, u128::saturating_add),
+                    len: operands.iter().map(|b| b.len).max().unwrap_or(0),
+                }
+            }
+            Operation::Multiply | Operation::MultiplyPlaintext => {
+                // Each product coefficient sums at most min(len) terms,
+                // even after wrapping around x^n + 1.
+                let terms = operands.iter().map(|b| b.len).min().unwrap_or(0);
+
+                CoefficientBound {
+                    max_abs: operands
+                        .iter()
+                        .map(|b| b.max_abs)
+                        .fold(terms as u128, u128::saturating_mul),
+                    len: u64::min(
+                        operands
+                            .iter()
+                            .map(|b| b.len)
+                            .sum::<u64>()
+                            .saturating_sub(1),
+                        lattice_dimension,
+                    ),
+                }
+            }
+            // Negation, relinearization, clones, outputs, and rotations
+            // don't change coefficient magnitudes.
+            _ => match operands.first() {
+                Some(b) => *b,
+                None => continue,
+            },
+        };
+
+        let arithmetic = matches!(
+            ir.graph[id].operation,
+            Operation::Add
+                | Operation::AddPlaintext
+                | Operation::Sub
+                | Operation::SubPlaintext
+                | Operation::Multiply
+                | Operation::MultiplyPlaintext
+        );
+
+        if arithmetic && overflows(&bound) && !operands.iter().any(overflows) {
+            warnings.push(OverflowWarning { node: id, bound });
+        }
+
+        bounds.insert(id, bound);
+    }
+
+    warnings
+}
+
+/**
+ * The bound of a serialized literal plaintext, treating coefficients
+ * above `plain_modulus / 2` as negative.
+ */
+fn literal_bound(bytes: &[u8], plain_modulus: u64) -> Option<CoefficientBound> {
+    let InnerPlaintext::Seal(p) = InnerPlaintext::from_bytes(bytes).ok()?;
+    let p = p.first()?;
+
+    let max_abs = (0..p.len())
+        .map(|i| {
+            let c = p.get_coefficient(i);
+            u64::min(c, plain_modulus.saturating_sub(c))
+        })
+        .max()
+        .unwrap_or(0);
+
+    Some(CoefficientBound {
+        max_abs: max_abs as u128,
+        len: p.len() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sunscreen_fhe_program::{FheProgramTrait, SchemeType};
+
+    const BITS: CoefficientBound = CoefficientBound {
+        max_abs: 1,
+        len: 64,
+    };
+
+    #[test]
+    fn reports_first_overflowing_addition() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let mut sum = a;
+        let mut sums = vec![];
+
+        for _ in 0..10 {
+            sum = ir.add_add(sum, a);
+            sums.push(sum);
+        }
+
+        ir.add_output_ciphertext(sum);
+
+        // After the 4th addition, coefficients may reach 5 and
+        // 2 * 5 >= 10.
+        let warnings = find_overflows(&ir, 10, 4096, BITS);
+
+        assert_eq!(
+            warnings,
+            vec![OverflowWarning {
+                node: sums[3],
+                bound: CoefficientBound {
+                    max_abs: 5,
+                    len: 64
+                }
+            }]
+        );
+
+        assert!(find_overflows(&ir, 1024, 4096, BITS).is_empty());
+    }
+
+    #[test]
+    fn multiplication_grows_by_shorter_length() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let mul = ir.add_multiply(a, b);
+        ir.add_output_ciphertext(mul);
+
+        // Products of 64-bit values have coefficients up to 64.
+        assert!(find_overflows(&ir, 129, 4096, BITS).is_empty());
+        assert_eq!(
+            find_overflows(&ir, 128, 4096, BITS),
+            vec![OverflowWarning {
+                node: mul,
+                bound: CoefficientBound {
+                    max_abs: 64,
+                    len: 127
+                }
+            }]
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use petgraph::{algo::toposort, stable_graph::NodeIndex, visit::EdgeRef, Direction};
+use seal_fhe::Plaintext as SealPlaintext;
+use sunscreen_compiler_common::{EdgeInfo, GraphQuery, NodeInfo};
+use sunscreen_fhe_program::{FheProgram, Literal, Operation};
+use sunscreen_runtime::{InnerPlaintext, WithContext};
+
+/**
+ * Folding a product costs one step per pair of coefficients, so skip
+ * products of large (e.g. batched) literals.
+ */
+const MAX_PRODUCT_TERMS: usize = 1 << 16;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Chain {
+    Sum { negate: bool },
+    Product,
+}
+
+impl Chain {
+    fn of(op: &Operation) -> Option<Self> {
+        match op {
+            Operation::AddPlaintext => Some(Self::Sum { negate: false }),
+            Operation::SubPlaintext => Some(Self::Sum { negate: true }),
+            Operation::MultiplyPlaintext => Some(Self::Product),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * Collapses chains of plaintext literal additions, subtractions, and
+ * multiplications into a single plaintext operation. For example:
+ * ```ignore
+ * b = a + 5
+ * c = b + 3
+ * ```
+ * becomes `c = a + (5 + 3)`, where `5 + 3` is computed at compile time.
+ *
+ * # Remarks
+ * Literals are combined coefficient-wise (for sums) or as polynomials
+ * modulo `x^n + 1` (for products), all modulo the plain modulus they
+ * were encoded under. These operations commute with every plaintext
+ * encoding, so folding preserves the program's result regardless of
+ * the literals' types, including negative `Signed` values encoded in
+ * two's complement modulo `t`.
+ *
+ * Folded nodes keep their indices; the intermediate nodes they no
+ * longer reference are left for dead code elimination.
+ */
+pub fn apply_constant_folding(ir: &mut FheProgram) {
+    // FHE programs are DAGs, so this can't fail.
+    for id in toposort(&ir.graph.0, None).unwrap() {
+        let outer = match Chain::of(&ir.graph[id].operation) {
+            Some(c) => c,
+            None => continue,
+        };
+
+        let query = GraphQuery::new(&ir.graph.0);
+
+        let (inner_id, outer_lit) = match query.get_binary_operands(id) {
+            Ok(operands) => operands,
+            Err(_) => continue,
+        };
+
+        let inner = match Chain::of(&ir.graph[inner_id].operation) {
+            Some(c) => c,
+            None => continue,
+        };
+
+        let (x, inner_lit) = match query.get_binary_operands(inner_id) {
+            Ok(operands) => operands,
+            Err(_) => continue,
+        };
+
+        let (a, b) = match (literal(ir, inner_lit), literal(ir, outer_lit)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => continue,
+        };
+
+        let folded = match (inner, outer) {
+            (Chain::Sum { negate: neg_a }, Chain::Sum { negate: neg_b }) => {
+                sum(&a, neg_a, &b, neg_b).map(|p| (Operation::AddPlaintext, p))
+            }
+            (Chain::Product, Chain::Product) => {
+                product(&a, &b).map(|p| (Operation::MultiplyPlaintext, p))
+            }
+            _ => None,
+        };
+
+        let (op, p) = match folded {
+            Some(f) => f,
+            None => continue,
+        };
+
+        let bytes = match InnerPlaintext::Seal(vec![p]).to_bytes() {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+
+        let incoming = ir
+            .graph
+            .edges_directed(id, Direction::Incoming)
+            .map(|e| e.id())
+            .collect::<Vec<_>>();
+
+        for e in incoming {
+            ir.graph.remove_edge(e);
+        }
+
+        let lit = ir
+            .graph
+            .add_node(NodeInfo::new(Operation::Literal(Literal::Plaintext(bytes))));
+
+        ir.graph.add_edge(x, id, EdgeInfo::Left);
+        ir.graph.add_edge(lit, id, EdgeInfo::Right);
+        ir.graph[id].operation = op;
+    }
+}
+
+/**
+ * Returns the single plaintext a literal node holds.
+ */
+fn literal(ir: &FheProgram, id: NodeIndex) -> Option<WithContext<SealPlaintext>> {
+    let bytes = match &ir.graph[id].operation {
+        Operation::Literal(Literal::Plaintext(bytes)) => bytes,
+        _ => return None,
+    };
+
+    let InnerPlaintext::Seal(mut p) = InnerPlaintext::from_bytes(bytes).ok()?;
+
+    if p.len() != 1 {
+        return None;
+    }
+
+    p.pop()
+}
+
+fn negate(c: u64, t: u64) -> u64 {
+    (t - c % t) % t
+}
+
+/**
+ * Computes `±a ± b` coefficient-wise.
+ */
+fn sum(
+    a: &WithContext<SealPlaintext>,
+    neg_a: bool,
+    b: &WithContext<SealPlaintext>,
+    neg_b: bool,
+) -> Option<WithContext<SealPlaintext>> {
+    if a.params != b.params {
+        return None;
+    }
+
+    let t = a.params.plain_modulus;
+    let len = usize::max(a.data.len(), b.data.len());
+
+    let coefficient = |p: &SealPlaintext, negated: bool, i: usize| {
+        let c = if i < p.len() { p.get_coefficient(i) } else { 0 };
+
+        if negated {
+            negate(c, t)
+        } else {
+            c % t
+        }
+    };
+
+    let mut result = SealPlaintext::new().ok()?;
+    result.resize(len);
+
+    for i in 0..len {
+        let c = (coefficient(&a.data, neg_a, i) as u128 + coefficient(&b.data, neg_b, i) as u128)
+            % t as u128;
+
+        result.set_coefficient(i, c as u64);
+    }
+
+    Some(WithContext {
+        params: a.params.clone(),
+        data: result,
+    })
+}
+
+/**
+ * Computes `a * b` modulo `x^n + 1`. Returns `None` rather than a zero
+ * product, since multiplying by a zero plaintext fails in SEAL.
+ */
+fn product(
+    a: &WithContext<SealPlaintext>,
+    b: &WithContext<SealPlaintext>,
+) -> Option<WithContext<SealPlaintext>> {
+    if a.params != b.params || a.data.len() * b.data.len() > MAX_PRODUCT_TERMS {
+        return None;
+    }
+
+    let t = a.params.plain_modulus as u128;
+    let n = a.params.lattice_dimension as usize;
+
+    let a_coeffs = (0..a.data.len())
+        .map(|i| a.data.get_coefficient(i) as u128 % t)
+        .collect::<Vec<_>>();
+    let b_coeffs = (0..b.data.len())
+        .map(|i| b.data.get_coefficient(i) as u128 % t)
+        .collect::<Vec<_>>();
+
+    let len = usize::min((a_coeffs.len() + b_coeffs.len()).saturating_sub(1), n);
+    let mut coeffs = vec![0u128; len];
+
+    for (i, x) in a_coeffs.iter().enumerate() {
+        for (j, y) in b_coeffs.iter().enumerate() {
+            let term = x * y % t;
+            let k = i + j;
+
+            // x^n = -1
+            if k < n {
+                coeffs[k] = (coeffs[k] + term) % t;
+            } else {
+                coeffs[k - n] = (coeffs[k - n] + t - term) % t;
+            }
+        }
+    }
+
+    if coeffs.iter().all(|c| *c == 0) {
+        return None;
+    }
+
+    let mut result = SealPlaintext::new().ok()?;
+    result.resize(len);
+
+    for (i, c) in coeffs.iter().enumerate() {
+        result.set_coefficient(i, *c as u64);
+    }
+
+    Some(WithContext {
+        params: a.params.clone(),
+        data: result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use seal_fhe::{CoefficientModulus, SecurityLevel};
+    use sunscreen_fhe_program::{FheProgramTrait, SchemeType};
+    use sunscreen_runtime::Params;
+
+    const N: usize = 4096;
+
+    fn params() -> Params {
+        Params {
+            lattice_dimension: N as u64,
+            coeff_modulus: CoefficientModulus::bfv_default(N as u64, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|x| x.value())
+                .collect(),
+            plain_modulus: 17,
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        }
+    }
+
+    fn literal_node(ir: &mut FheProgram, coeffs: &[u64]) -> NodeIndex {
+        let mut p = SealPlaintext::new().unwrap();
+        p.resize(coeffs.len());
+
+        for (i, c) in coeffs.iter().enumerate() {
+            p.set_coefficient(i, *c);
+        }
+
+        let bytes = InnerPlaintext::Seal(vec![WithContext {
+            params: params(),
+            data: p,
+        }])
+        .to_bytes()
+        .unwrap();
+
+        ir.add_input_literal(Literal::Plaintext(bytes))
+    }
+
+    fn coefficients(ir: &FheProgram, id: NodeIndex) -> Vec<u64> {
+        let p = literal(ir, id).unwrap().data;
+
+        (0..p.len()).map(|i| p.get_coefficient(i)).collect()
+    }
+
+    fn plaintext_operand(ir: &FheProgram, id: NodeIndex) -> (NodeIndex, NodeIndex) {
+        GraphQuery::new(&ir.graph.0)
+            .get_binary_operands(id)
+            .unwrap()
+    }
+
+    #[test]
+    fn folds_additions_and_subtractions() {
+        // (a + [1, 2]) - [3] + [16, 0, 5]
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let l_1 = literal_node(&mut ir, &[1, 2]);
+        let l_2 = literal_node(&mut ir, &[3]);
+        let l_3 = literal_node(&mut ir, &[16, 0, 5]);
+
+        let add_1 = ir.add_binary_operation(Operation::AddPlaintext, a, l_1);
+        let sub = ir.add_binary_operation(Operation::SubPlaintext, add_1, l_2);
+        let add_2 = ir.add_binary_operation(Operation::AddPlaintext, sub, l_3);
+        ir.add_output_ciphertext(add_2);
+
+        apply_constant_folding(&mut ir);
+
+        let (x, lit) = plaintext_operand(&ir, add_2);
+
+        assert_eq!(x, a);
+        assert_eq!(ir.graph[add_2].operation, Operation::AddPlaintext);
+        assert_eq!(coefficients(&ir, lit), vec![14, 2, 5]);
+    }
+
+    #[test]
+    fn folds_multiplications_modulo_cyclotomic() {
+        // (a * (1 + x^(N - 1))) * 2x
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let mut wrapping = vec![0; N];
+        wrapping[0] = 1;
+        wrapping[N - 1] = 1;
+
+        let a = ir.add_input_ciphertext(0);
+        let l_1 = literal_node(&mut ir, &wrapping);
+        let l_2 = literal_node(&mut ir, &[0, 2]);
+
+        let mul_1 = ir.add_multiply_plaintext(a, l_1);
+        let mul_2 = ir.add_multiply_plaintext(mul_1, l_2);
+        ir.add_output_ciphertext(mul_2);
+
+        apply_constant_folding(&mut ir);
+
+        let (x, lit) = plaintext_operand(&ir, mul_2);
+
+        // 2x + 2x^N = 2x - 2
+        let mut expected = vec![0; N];
+        expected[0] = 15;
+        expected[1] = 2;
+
+        assert_eq!(x, a);
+        assert_eq!(coefficients(&ir, lit), expected);
+    }
+
+    #[test]
+    fn leaves_mixed_chains_alone() {
+        // (a + [1]) * [2]
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let l_1 = literal_node(&mut ir, &[1]);
+        let l_2 = literal_node(&mut ir, &[2]);
+
+        let add = ir.add_binary_operation(Operation::AddPlaintext, a, l_1);
+        let mul = ir.add_multiply_plaintext(add, l_2);
+        ir.add_output_ciphertext(mul);
+
+        let node_count = ir.graph.node_count();
+
+        apply_constant_folding(&mut ir);
+
+        assert_eq!(plaintext_operand(&ir, mul), (add, l_2));
+        assert_eq!(ir.graph.node_count(), node_count);
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use petgraph::{
+    algo::toposort,
+    stable_graph::{EdgeIndex, NodeIndex},
+    visit::EdgeRef,
+    Direction,
+};
+use sunscreen_compiler_common::{EdgeInfo, NodeInfo};
+use sunscreen_fhe_program::{
+    FheProgram,
+    Operation::{self, *},
+    SchemeType,
+};
+
+/**
+ * Inserts the [`ModSwitch`] nodes a BGV program needs and leaves other
+ * schemes' programs untouched.
+ *
+ * # Remarks
+ * Each ciphertext multiplication is followed by a modulus switch to
+ * keep its noise in check, so a ciphertext's level is the number of
+ * switches on its path from the inputs. SEAL only combines ciphertexts
+ * at the same level, so the operands of an addition, subtraction, or
+ * multiplication computed along paths with fewer multiplications are
+ * switched down to the other operand's level first. For example,
+ * `a * b + c` switches `c` once before the addition.
+ *
+ * Plaintexts have no level and pass through unchanged.
+ */
+pub fn apply_insert_mod_switches(ir: &mut FheProgram) {
+    if ir.data != SchemeType::Bgv {
+        return;
+    }
+
+    // Ids are live and the program is a DAG, so this can't fail.
+    let order = toposort(&ir.graph.0, None).unwrap();
+
+    // The level of each ciphertext, counting the switch that will
+    // follow each multiplication.
+    let mut levels: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut products = vec![];
+    let mut alignments = vec![];
+
+    for id in order {
+        let op = &ir.graph[id].operation;
+
+        let operands = ir
+            .graph
+            .edges_directed(id, Direction::Incoming)
+            .filter_map(|e| levels.get(&e.source()).map(|l| (e.id(), *l)))
+            .collect::<Vec<(EdgeIndex, usize)>>();
+
+        let level = match op {
+            InputCiphertext(_) => 0,
+            _ => match operands.iter().map(|(_, l)| *l).max() {
+                Some(l) => l,
+                // Plaintexts have no level.
+                None => continue,
+            },
+        };
+
+        if matches!(op, Add | Sub | Multiply) {
+            for (e, l) in &operands {
+                if *l < level {
+                    alignments.push((*e, level - l));
+                }
+            }
+        }
+
+        if matches!(op, Multiply) {
+            products.push(id);
+            levels.insert(id, level + 1);
+        } else {
+            levels.insert(id, level);
+        }
+    }
+
+    // Align operands before switching products so a product's switch
+    // also feeds any chain aligning it.
+    for (e, count) in alignments {
+        let (source, target) = ir.graph.edge_endpoints(e).unwrap();
+        let operand_type = ir.graph.remove_edge(e).unwrap();
+
+        let mut prev = source;
+
+        for _ in 0..count {
+            let switch = ir.graph.add_node(NodeInfo::new(ModSwitch));
+            ir.graph.add_edge(prev, switch, EdgeInfo::Unary);
+
+            prev = switch;
+        }
+
+        ir.graph.add_edge(prev, target, operand_type);
+    }
+
+    for id in products {
+        insert_mod_switch_after(ir, id);
+    }
+}
+
+/**
+ * Inserts a [`ModSwitch`] after `id` and moves the node's consumers
+ * onto it.
+ */
+fn insert_mod_switch_after(ir: &mut FheProgram, id: NodeIndex) {
+    let consumers = ir
+        .graph
+        .edges_directed(id, Direction::Outgoing)
+        .map(|e| (e.id(), e.target(), *e.weight()))
+        .collect::<Vec<_>>();
+
+    let switch = ir.graph.add_node(NodeInfo::new(ModSwitch));
+    ir.graph.add_edge(id, switch, EdgeInfo::Unary);
+
+    for (e, target, operand_type) in consumers {
+        ir.graph.remove_edge(e);
+        ir.graph.add_edge(switch, target, operand_type);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sunscreen_fhe_program::FheProgramTrait;
+
+    fn count_switches(ir: &FheProgram) -> usize {
+        ir.graph
+            .node_weights()
+            .filter(|n| n.operation == Operation::ModSwitch)
+            .count()
+    }
+
+    /**
+     * The number of [`ModSwitch`] nodes between `id` and its nearest
+     * non-switch ancestor along its `operand_type` edge.
+     */
+    fn switches_before(ir: &FheProgram, id: NodeIndex, operand_type: EdgeInfo) -> usize {
+        let mut cur = ir
+            .graph
+            .edges_directed(id, Direction::Incoming)
+            .find(|e| *e.weight() == operand_type)
+            .unwrap()
+            .source();
+
+        let mut count = 0;
+
+        while ir.graph[cur].operation == ModSwitch {
+            count += 1;
+            cur = ir
+                .graph
+                .neighbors_directed(cur, Direction::Incoming)
+                .next()
+                .unwrap();
+        }
+
+        count
+    }
+
+    fn mul_add(scheme: SchemeType) -> (FheProgram, NodeIndex) {
+        // a * b + c
+        let mut ir = FheProgram::new(scheme);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let c = ir.add_input_ciphertext(2);
+        let mul = ir.add_multiply(a, b);
+        let add = ir.add_add(mul, c);
+        ir.add_output_ciphertext(add);
+
+        (ir, add)
+    }
+
+    #[test]
+    fn ignores_other_schemes() {
+        for scheme in [SchemeType::Bfv, SchemeType::Ckks] {
+            let (mut ir, _) = mul_add(scheme);
+
+            apply_insert_mod_switches(&mut ir);
+
+            assert_eq!(count_switches(&ir), 0);
+        }
+    }
+
+    #[test]
+    fn switches_after_multiply_and_aligns_operands() {
+        let (mut ir, add) = mul_add(SchemeType::Bgv);
+
+        apply_insert_mod_switches(&mut ir);
+
+        assert_eq!(count_switches(&ir), 2);
+        assert_eq!(switches_before(&ir, add, EdgeInfo::Left), 1);
+        assert_eq!(switches_before(&ir, add, EdgeInfo::Right), 1);
+        assert!(ir.validate().is_ok());
+    }
+
+    #[test]
+    fn aligns_operands_across_several_levels() {
+        // ((a * b) * (a * b) + c) * p
+        let mut ir = FheProgram::new(SchemeType::Bgv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let c = ir.add_input_ciphertext(2);
+        let mul_1 = ir.add_multiply(a, b);
+        let mul_2 = ir.add_multiply(mul_1, mul_1);
+        let add = ir.add_add(mul_2, c);
+        let p = ir.add_input_plaintext(0);
+        let mul_plain = ir.add_multiply_plaintext(add, p);
+        ir.add_output_ciphertext(mul_plain);
+
+        apply_insert_mod_switches(&mut ir);
+
+        assert_eq!(count_switches(&ir), 4);
+        assert_eq!(switches_before(&ir, mul_2, EdgeInfo::Left), 1);
+        assert_eq!(switches_before(&ir, add, EdgeInfo::Left), 1);
+        assert_eq!(switches_before(&ir, add, EdgeInfo::Right), 2);
+        assert_eq!(switches_before(&ir, mul_plain, EdgeInfo::Right), 0);
+        assert!(ir.validate().is_ok());
+    }
+}
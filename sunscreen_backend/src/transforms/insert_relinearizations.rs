@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::convert::Infallible;
 
 use sunscreen_compiler_common::{
@@ -10,11 +11,98 @@ use sunscreen_fhe_program::{
     Operation::{self, *},
 };
 
-use petgraph::{stable_graph::NodeIndex, visit::EdgeRef, Direction};
+use petgraph::{algo::toposort, stable_graph::NodeIndex, visit::EdgeRef, Direction};
 
 type FheGraphQuery<'a> = GraphQuery<'a, NodeInfo<Operation>, EdgeInfo>;
 
+/**
+ * Inserts a [`Relinearize`] after every ciphertext multiplication.
+ */
 pub fn apply_insert_relinearizations(ir: &mut FheProgram) {
+    insert_relinearizations_after(ir, |id, query| {
+        // We only need to insert relinearizations for ciphertext
+        // multiplications. Plaintext multiplications don't increase
+        // the number of polynomials (see
+        // multiply_plaintext_does_not_increase_polynomials) test in
+        // assumptions.rs
+        matches!(query.get_node(id).unwrap().operation, Multiply)
+    });
+}
+
+/**
+ * Whether the evaluator accepts a ciphertext operand with more than 2
+ * polynomials. The result then has as many polynomials as its largest
+ * operand.
+ */
+fn accepts_unrelinearized(op: &Operation) -> bool {
+    matches!(
+        op,
+        Add | Sub | Negate | AddPlaintext | SubPlaintext | MultiplyPlaintext
+    )
+}
+
+/**
+ * Inserts [`Relinearize`] nodes only where a ciphertext's size matters,
+ * rather than after every multiplication.
+ *
+ * # Remarks
+ * A product can flow unrelinearized through additions, subtractions,
+ * negations, and plaintext operations, so `(a * b) + (c * d)` needs one
+ * relinearization after the addition instead of one per multiplication.
+ * A value is relinearized before anything else consumes it: another
+ * multiplication, a rotation, or an output.
+ *
+ * A value is only left unrelinearized when it has a single consumer.
+ * Each relinearization therefore covers at least one multiplication and
+ * each multiplication is covered by exactly one relinearization, so
+ * this never emits more relinearizations than
+ * [`apply_insert_relinearizations`].
+ */
+pub fn apply_insert_deferred_relinearizations(ir: &mut FheProgram) {
+    // Ids are live and the program is a DAG, so these can't fail.
+    let order = toposort(&ir.graph.0, None).unwrap();
+
+    let mut unrelinearized = HashSet::new();
+    let mut relinearize = HashSet::new();
+
+    for id in order {
+        let op = &ir.graph[id].operation;
+
+        let grows = matches!(op, Multiply)
+            || (accepts_unrelinearized(op)
+                && ir
+                    .graph
+                    .neighbors_directed(id, Direction::Incoming)
+                    .any(|p| unrelinearized.contains(&p)));
+
+        if !grows {
+            continue;
+        }
+
+        let mut consumers = ir.graph.edges_directed(id, Direction::Outgoing);
+
+        match (consumers.next(), consumers.next()) {
+            (None, _) => {}
+            (Some(e), None) if accepts_unrelinearized(&ir.graph[e.target()].operation) => {
+                unrelinearized.insert(id);
+            }
+            _ => {
+                relinearize.insert(id);
+            }
+        }
+    }
+
+    insert_relinearizations_after(ir, |id, _| relinearize.contains(&id));
+}
+
+/**
+ * Inserts a [`Relinearize`] after each node matching `predicate` and
+ * moves the node's consumers onto it.
+ */
+fn insert_relinearizations_after<F>(ir: &mut FheProgram, predicate: F)
+where
+    F: Fn(NodeIndex, &FheGraphQuery) -> bool,
+{
     let insert_relin = |id: NodeIndex, query: FheGraphQuery| {
         let mut transforms = GraphTransforms::new();
 
@@ -43,16 +131,10 @@ pub fn apply_insert_relinearizations(ir: &mut FheProgram) {
     };
 
     forward_traverse_mut(&mut ir.graph.0, |query, id| {
-        // Id is given to us, so the node should exist. Just
-        // unwrap.
-        let transforms = match query.get_node(id).unwrap().operation {
-            // We only need to insert relinearizations for ciphertext
-            // multiplications. Plaintext multiplications don't increase
-            // the number of polynomials (see
-            // multiply_plaintext_does_not_increase_polynomials) test in
-            // assumptions.rs
-            Multiply => insert_relin(id, query),
-            _ => GraphTransforms::default(),
+        let transforms = if predicate(id, &query) {
+            insert_relin(id, query)
+        } else {
+            GraphTransforms::default()
         };
 
         Ok::<_, Infallible>(transforms)
@@ -143,4 +225,66 @@ mod tests {
             .neighbors_directed(relin_nodes[0], Direction::Outgoing)
             .all(|i| { matches!(query.get_node(i).unwrap().operation, Operation::Add) }),);
     }
+
+    fn relin_operands(ir: &FheProgram) -> Vec<Operation> {
+        let query = GraphQuery::new(&ir.graph.0);
+
+        let mut ops = ir
+            .graph
+            .node_indices()
+            .filter(|i| matches!(ir.graph[*i].operation, Operation::Relinearize))
+            .map(|i| {
+                ir.graph[query.get_unary_operand(i).unwrap()]
+                    .operation
+                    .clone()
+            })
+            .collect::<Vec<_>>();
+
+        ops.sort_by_key(|op| op.to_string());
+
+        ops
+    }
+
+    #[test]
+    fn deferred_relinearizes_before_multiply_and_output() {
+        // ((a * b) + c) * d
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let c = ir.add_input_ciphertext(2);
+        let d = ir.add_input_ciphertext(3);
+        let mul_1 = ir.add_multiply(a, b);
+        let add = ir.add_add(mul_1, c);
+        let mul_2 = ir.add_multiply(add, d);
+        ir.add_output_ciphertext(mul_2);
+
+        apply_insert_deferred_relinearizations(&mut ir);
+
+        assert_eq!(
+            relin_operands(&ir),
+            vec![Operation::Add, Operation::Multiply]
+        );
+
+        ir.validate().unwrap();
+    }
+
+    #[test]
+    fn deferred_relinearizes_shared_products_once() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let mul = ir.add_multiply(a, b);
+        let add_1 = ir.add_add(mul, a);
+        let add_2 = ir.add_add(mul, b);
+        ir.add_output_ciphertext(add_1);
+        ir.add_output_ciphertext(add_2);
+
+        apply_insert_deferred_relinearizations(&mut ir);
+
+        assert_eq!(relin_operands(&ir), vec![Operation::Multiply]);
+
+        ir.validate().unwrap();
+    }
 }
@@ -1,13 +1,503 @@
+mod constant_folding;
+mod insert_mod_switches;
 mod insert_relinearizations;
 
+use log::{debug, trace};
 use petgraph::stable_graph::NodeIndex;
-use sunscreen_fhe_program::{FheProgram, FheProgramTrait};
+use sunscreen_compiler_common::transforms::{
+    common_subexpression_elimination, dead_code_elimination, CompilerPass,
+};
+use sunscreen_fhe_program::{FheProgram, FheProgramTrait, Operation, SchemeType};
+use sunscreen_runtime::Params;
 
-use insert_relinearizations::apply_insert_relinearizations;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-pub fn transform_intermediate_representation(ir: &mut FheProgram) {
-    apply_insert_relinearizations(ir);
+use crate::{Error, Result};
+use constant_folding::apply_constant_folding;
+use insert_mod_switches::apply_insert_mod_switches;
+use insert_relinearizations::{
+    apply_insert_deferred_relinearizations, apply_insert_relinearizations,
+};
 
-    // Dead code elimination.
+/**
+ * A named transformation applied to an [`FheProgram`].
+ */
+type Pass = (&'static str, fn(&mut FheProgram));
+
+/**
+ * A user-defined pass over an [`FheProgram`]'s graph.
+ */
+pub type FheCompilerPass = dyn CompilerPass<Operation, Params> + Send + Sync;
+
+/**
+ * The last built-in pass that optimizes the program. Passes after it
+ * lower the program for the runtime.
+ */
+const LAST_OPTIMIZATION_PASS: &str = "common_subexpression_elimination";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/**
+ * Where a [`UserPass`] runs in the pipeline.
+ */
+pub enum PassPosition {
+    /**
+     * After the built-in optimization passes (and any user passes
+     * previously placed here), but before the pass that inserts
+     * relinearizations.
+     */
+    AfterOptimizations,
+
+    /**
+     * Immediately before the named pass.
+     */
+    Before(String),
+
+    /**
+     * Immediately after the named pass.
+     */
+    After(String),
+}
+
+#[derive(Clone)]
+/**
+ * A user-defined pass and where to run it.
+ */
+pub struct UserPass {
+    /**
+     * The pass to run.
+     */
+    pub pass: Arc<FheCompilerPass>,
+
+    /**
+     * Where to run it.
+     */
+    pub position: PassPosition,
+}
+
+/**
+ * A step in the pipeline: either a built-in [`Pass`] or a user pass
+ * and the parameters to run it under.
+ */
+#[derive(Clone, Copy)]
+enum Step<'a> {
+    BuiltIn(Pass),
+    User(&'a FheCompilerPass, &'a Params),
+}
+
+impl<'a> Step<'a> {
+    fn name(&self) -> &str {
+        match self {
+            Self::BuiltIn((name, _)) => name,
+            Self::User(pass, _) => pass.name(),
+        }
+    }
+
+    fn run(&self, ir: &mut FheProgram) -> Result<()> {
+        match self {
+            Self::BuiltIn((_, pass)) => {
+                pass(ir);
+                Ok(())
+            }
+            Self::User(pass, params) => pass
+                .run(&mut ir.graph.0, params)
+                .map_err(|e| Error::PassFailed(pass.name().to_owned(), e)),
+        }
+    }
+}
+
+impl<'a> From<Pass> for Step<'a> {
+    fn from(pass: Pass) -> Self {
+        Self::BuiltIn(pass)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Options controlling backend compilation.
+ */
+pub struct CompileOptions {
+    /**
+     * Validate the graph after every pass. See
+     * [`compile_inplace_with_invariant_check`](crate::compile_inplace_with_invariant_check).
+     */
+    pub check_invariants: bool,
+
+    /**
+     * Defer relinearizing a product until its size matters (e.g. before
+     * another multiplication or an output) rather than relinearizing
+     * after every multiplication.
+     */
+    pub relin_optimization: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            check_invariants: false,
+            relin_optimization: true,
+        }
+    }
+}
+
+/**
+ * The optimization passes run during backend compilation of a program
+ * in the given scheme under the given options, in order.
+ */
+fn passes(options: &CompileOptions, scheme: SchemeType) -> Vec<Pass> {
+    let insert_relinearizations: fn(&mut FheProgram) = if options.relin_optimization {
+        apply_insert_deferred_relinearizations
+    } else {
+        apply_insert_relinearizations
+    };
+
+    let mut passes: Vec<Pass> = vec![
+        ("constant_folding", apply_constant_folding),
+        ("dead_code_elimination", apply_dead_code_elimination),
+        (
+            "common_subexpression_elimination",
+            apply_common_subexpression_elimination,
+        ),
+        ("insert_relinearizations", insert_relinearizations),
+    ];
+
+    if scheme == SchemeType::Bgv {
+        passes.push(("insert_mod_switches", apply_insert_mod_switches));
+    }
+
+    passes.push(("compact", compact));
+
+    passes
+}
+
+/**
+ * Inputs are roots too: removing an unused one would renumber the rest
+ * and change the program's signature.
+ */
+fn apply_dead_code_elimination(ir: &mut FheProgram) {
+    let roots = ir
+        .graph
+        .node_indices()
+        .filter(|n| {
+            matches!(
+                ir.graph[*n].operation,
+                Operation::OutputCiphertext
+                    | Operation::InputCiphertext(_)
+                    | Operation::InputPlaintext(_)
+            )
+        })
+        .collect::<Vec<NodeIndex>>();
+
+    dead_code_elimination(&mut ir.graph.0, &roots);
+}
+
+fn apply_common_subexpression_elimination(ir: &mut FheProgram) {
+    common_subexpression_elimination(&mut ir.graph.0);
+}
+
+/**
+ * The runtime indexes node data by node index, so the final program
+ * must have contiguous indices.
+ */
+fn compact(ir: &mut FheProgram) {
     *ir = ir.prune(&ir.get_outputs().collect::<Vec<NodeIndex>>());
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/**
+ * The wall-clock time a backend pass took to run.
+ */
+pub struct PassTiming {
+    /**
+     * The name of the pass.
+     */
+    pub name: String,
+
+    /**
+     * How long the pass took, excluding any invariant checks.
+     */
+    pub duration: Duration,
+}
+
+/**
+ * Runs each of the given steps over `ir` in order, logging each step's
+ * node counts before and after, and returns how long each took. When
+ * `check_invariants` is set, validates the graph after every step and
+ * returns [`Error::GraphInvariantViolation`] naming the first pass that
+ * produced an invalid graph.
+ */
+fn run_passes(
+    ir: &mut FheProgram,
+    steps: &[Step<'_>],
+    check_invariants: bool,
+) -> Result<Vec<PassTiming>> {
+    let mut timings = Vec::with_capacity(steps.len());
+
+    for step in steps {
+        let nodes_before = ir.graph.node_count();
+        trace!("Running pass {} on {} nodes", step.name(), nodes_before);
+
+        let start = Instant::now();
+        step.run(ir)?;
+        let duration = start.elapsed();
+
+        debug!(
+            "Pass {}: {} -> {} nodes in {:?}",
+            step.name(),
+            nodes_before,
+            ir.graph.node_count(),
+            duration
+        );
+
+        timings.push(PassTiming {
+            name: step.name().to_owned(),
+            duration,
+        });
+
+        if check_invariants {
+            ir.validate()
+                .map_err(|e| Error::GraphInvariantViolation(step.name().to_owned(), e))?;
+        }
+    }
+
+    Ok(timings)
+}
+
+/**
+ * Places each user pass into the built-in pipeline in the order given.
+ * Returns [`Error::UnknownPass`] if a pass is anchored to a name
+ * neither a built-in nor an earlier user pass has.
+ */
+fn schedule<'a>(
+    options: &CompileOptions,
+    user_passes: &'a [UserPass],
+    params: &'a Params,
+) -> Result<Vec<Step<'a>>> {
+    let mut steps = passes(options, params.scheme_type)
+        .into_iter()
+        .map(Step::from)
+        .collect::<Vec<_>>();
+
+    fn position_of(steps: &[Step<'_>], name: &str) -> Result<usize> {
+        steps
+            .iter()
+            .position(|s| s.name() == name)
+            .ok_or_else(|| Error::UnknownPass(name.to_owned()))
+    }
+
+    let mut after_optimizations = position_of(&steps, LAST_OPTIMIZATION_PASS)? + 1;
+
+    for p in user_passes {
+        let index = match &p.position {
+            PassPosition::AfterOptimizations => after_optimizations,
+            PassPosition::Before(name) => position_of(&steps, name)?,
+            PassPosition::After(name) => position_of(&steps, name)? + 1,
+        };
+
+        if index <= after_optimizations {
+            after_optimizations += 1;
+        }
+
+        steps.insert(index, Step::User(p.pass.as_ref(), params));
+    }
+
+    Ok(steps)
+}
+
+pub fn transform_intermediate_representation(
+    ir: &mut FheProgram,
+    options: &CompileOptions,
+) -> Result<Vec<PassTiming>> {
+    let steps = passes(options, ir.data)
+        .into_iter()
+        .map(Step::from)
+        .collect::<Vec<_>>();
+
+    run_passes(ir, &steps, options.check_invariants)
+}
+
+pub fn transform_intermediate_representation_with_passes(
+    ir: &mut FheProgram,
+    options: &CompileOptions,
+    user_passes: &[UserPass],
+    params: &Params,
+) -> Result<Vec<PassTiming>> {
+    run_passes(
+        ir,
+        &schedule(options, user_passes, params)?,
+        options.check_invariants,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::visit::EdgeRef;
+    use sunscreen_fhe_program::{Error as FheProgramError, IRError, Operation, SchemeType};
+
+    fn checked() -> CompileOptions {
+        CompileOptions {
+            check_invariants: true,
+            ..CompileOptions::default()
+        }
+    }
+
+    fn create_test_program() -> FheProgram {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let mul = ir.add_multiply(a, b);
+        ir.add_output_ciphertext(mul);
+
+        ir
+    }
+
+    fn break_multiply(ir: &mut FheProgram) {
+        let edge = ir
+            .graph
+            .edge_references()
+            .find(|e| matches!(ir.graph[e.target()].operation, Operation::Multiply))
+            .map(|e| e.id())
+            .unwrap();
+
+        ir.graph.remove_edge(edge);
+    }
+
+    #[test]
+    fn invariant_check_passes_on_valid_program() {
+        let mut ir = create_test_program();
+
+        let timings = transform_intermediate_representation(&mut ir, &checked()).unwrap();
+
+        assert_eq!(
+            timings.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            passes(&checked(), SchemeType::Bfv)
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn invariant_check_reports_offending_pass() {
+        let mut ir = create_test_program();
+
+        let passes: &[Step] = &[
+            Step::BuiltIn(("insert_relinearizations", apply_insert_relinearizations)),
+            Step::BuiltIn(("break_multiply", break_multiply)),
+            Step::BuiltIn(("compact", compact)),
+        ];
+
+        match run_passes(&mut ir, passes, true) {
+            Err(Error::GraphInvariantViolation(pass, FheProgramError::IRError(e))) => {
+                assert_eq!(pass, "break_multiply");
+                assert!(e.iter().any(|e| matches!(e, IRError::NodeError(_))));
+            }
+            x => panic!("Expected GraphInvariantViolation, got {x:?}"),
+        }
+    }
+
+    fn count_ops(ir: &FheProgram, op: Operation) -> usize {
+        ir.graph
+            .node_weights()
+            .filter(|n| n.operation == op)
+            .count()
+    }
+
+    #[test]
+    fn duplicate_multiplications_are_merged() {
+        // (a * b) + (b * a)
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let mul_1 = ir.add_multiply(a, b);
+        let mul_2 = ir.add_multiply(b, a);
+        let add = ir.add_add(mul_1, mul_2);
+        ir.add_output_ciphertext(add);
+
+        transform_intermediate_representation(&mut ir, &checked()).unwrap();
+
+        assert_eq!(count_ops(&ir, Operation::Multiply), 1);
+        assert_eq!(count_ops(&ir, Operation::Relinearize), 1);
+    }
+
+    #[test]
+    fn duplicate_outputs_are_preserved() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let neg_1 = ir.add_negate(a);
+        let neg_2 = ir.add_negate(a);
+        ir.add_output_ciphertext(neg_1);
+        ir.add_output_ciphertext(neg_2);
+        ir.add_output_ciphertext(neg_1);
+
+        transform_intermediate_representation(&mut ir, &checked()).unwrap();
+
+        assert_eq!(count_ops(&ir, Operation::Negate), 1);
+        assert_eq!(ir.get_outputs().count(), 3);
+    }
+
+    #[test]
+    fn mod_switches_are_only_inserted_for_bgv() {
+        for (scheme, switches) in [(SchemeType::Bfv, 0), (SchemeType::Bgv, 1)] {
+            let mut ir = create_test_program();
+            ir.data = scheme;
+
+            let timings = transform_intermediate_representation(&mut ir, &checked()).unwrap();
+
+            assert_eq!(count_ops(&ir, Operation::ModSwitch), switches);
+            assert_eq!(
+                timings.iter().any(|t| t.name == "insert_mod_switches"),
+                switches > 0
+            );
+        }
+    }
+
+    #[test]
+    fn relin_optimization_defers_relinearization_past_add() {
+        // (a * b) + (c * d)
+        let create = || {
+            let mut ir = FheProgram::new(SchemeType::Bfv);
+
+            let a = ir.add_input_ciphertext(0);
+            let b = ir.add_input_ciphertext(1);
+            let c = ir.add_input_ciphertext(2);
+            let d = ir.add_input_ciphertext(3);
+            let mul_1 = ir.add_multiply(a, b);
+            let mul_2 = ir.add_multiply(c, d);
+            let add = ir.add_add(mul_1, mul_2);
+            ir.add_output_ciphertext(add);
+
+            ir
+        };
+
+        let mut eager = create();
+        let mut deferred = create();
+
+        transform_intermediate_representation(
+            &mut eager,
+            &CompileOptions {
+                relin_optimization: false,
+                ..checked()
+            },
+        )
+        .unwrap();
+        transform_intermediate_representation(&mut deferred, &checked()).unwrap();
+
+        assert_eq!(count_ops(&eager, Operation::Relinearize), 2);
+        assert_eq!(count_ops(&deferred, Operation::Relinearize), 1);
+        assert_eq!(deferred.graph.node_count() + 1, eager.graph.node_count());
+    }
+
+    #[test]
+    fn invariant_check_disabled_ignores_broken_pass() {
+        let mut ir = create_test_program();
+
+        let passes: &[Step] = &[Step::BuiltIn(("break_multiply", break_multiply))];
+
+        assert!(run_passes(&mut ir, passes, false).is_ok());
+    }
+}
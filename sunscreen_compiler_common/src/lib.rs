@@ -73,6 +73,18 @@ pub trait Operation: Clone + Debug + Hash + PartialEq + Eq {
      * ordered operands.
      */
     fn is_ordered(&self) -> bool;
+
+    /**
+     * Whether two instances of this operation with the same operands
+     * may be merged into one during common subexpression elimination.
+     *
+     * # Remarks
+     * Defaults to true. Operations that are observable beyond the value
+     * they compute (e.g. program outputs) should return false.
+     */
+    fn is_mergeable(&self) -> bool {
+        true
+    }
 }
 
 /**
@@ -66,11 +66,20 @@ fn get_binary_operands<O: Operation>(
  * e = c * d + 42
  * ```
  * The `c * d` subexpression can be computed once and shared between
- * the two expressions.
+ * the two expressions. Because the graph is traversed in topological
+ * order, merging two nodes can expose their children as duplicates,
+ * so entire duplicated chains collapse.
+ *
+ * Operations for which [`Operation::is_mergeable`] returns false are
+ * never merged.
+ *
+ * Returns the number of nodes eliminated.
  */
 pub fn common_subexpression_elimination<O: Operation>(
     graph: &mut StableGraph<NodeInfo<O>, EdgeInfo>,
-) {
+) -> usize {
+    let node_count = graph.node_count();
+
     forward_traverse_mut(graph, |query, index| {
         let mut transforms: GraphTransforms<NodeInfo<O>, EdgeInfo> = GraphTransforms::new();
 
@@ -104,6 +113,10 @@ pub fn common_subexpression_elimination<O: Operation>(
 
             let child_op = &child_node.operation;
 
+            if !child_op.is_mergeable() {
+                continue;
+            }
+
             if child_op.is_binary() {
                 let (left, right) = get_binary_operands(&query, e);
 
@@ -135,6 +148,8 @@ pub fn common_subexpression_elimination<O: Operation>(
         Ok::<_, Infallible>(transforms)
     })
     .expect("Traverse closure should be infallible.");
+
+    node_count - graph.node_count()
 }
 
 #[cfg(test)]
@@ -357,4 +372,63 @@ mod tests {
 
         assert!(equals);
     }
+
+    #[test]
+    fn collapses_duplicated_chains() {
+        let mut fe = CompilationResult::<Operation>::new();
+
+        let in_1 = fe.add_node(NodeInfo::new(Operation::PublicInput(NodeIndex::from(0))));
+        let in_2 = fe.add_node(NodeInfo::new(Operation::PublicInput(NodeIndex::from(1))));
+        let mul_1 = fe.add_node(NodeInfo::new(Operation::Mul));
+        let mul_2 = fe.add_node(NodeInfo::new(Operation::Mul));
+        let neg_1 = fe.add_node(NodeInfo::new(Operation::Neg));
+        let neg_2 = fe.add_node(NodeInfo::new(Operation::Neg));
+        let add = fe.add_node(NodeInfo::new(Operation::Add));
+
+        fe.add_edge(in_1, mul_1, EdgeInfo::Left);
+        fe.add_edge(in_2, mul_1, EdgeInfo::Right);
+        fe.add_edge(in_1, mul_2, EdgeInfo::Left);
+        fe.add_edge(in_2, mul_2, EdgeInfo::Right);
+        fe.add_edge(mul_1, neg_1, EdgeInfo::Unary);
+        fe.add_edge(mul_2, neg_2, EdgeInfo::Unary);
+        fe.add_edge(neg_1, add, EdgeInfo::Left);
+        fe.add_edge(neg_2, add, EdgeInfo::Right);
+
+        assert_eq!(common_subexpression_elimination(&mut fe.0), 2);
+        assert_eq!(fe.node_count(), 5);
+
+        let muls = fe
+            .node_weights()
+            .filter(|n| n.operation == Operation::Mul)
+            .count();
+        assert_eq!(muls, 1);
+    }
+
+    #[test]
+    fn no_duplicates_is_unchanged() {
+        let mut fe = CompilationResult::<Operation>::new();
+
+        let in_1 = fe.add_node(NodeInfo::new(Operation::PublicInput(NodeIndex::from(0))));
+        let in_2 = fe.add_node(NodeInfo::new(Operation::PublicInput(NodeIndex::from(1))));
+        let add = fe.add_node(NodeInfo::new(Operation::Add));
+        let sub = fe.add_node(NodeInfo::new(Operation::Sub));
+
+        fe.add_edge(in_1, add, EdgeInfo::Left);
+        fe.add_edge(in_2, add, EdgeInfo::Right);
+        fe.add_edge(in_1, sub, EdgeInfo::Left);
+        fe.add_edge(in_2, sub, EdgeInfo::Right);
+
+        let expected = fe.clone();
+
+        assert_eq!(common_subexpression_elimination(&mut fe.0), 0);
+
+        let equals = is_isomorphic_matching(
+            &Graph::from(fe.0),
+            &Graph::from(expected.0),
+            |x, y| x == y,
+            |x, y| x == y,
+        );
+
+        assert!(equals);
+    }
 }
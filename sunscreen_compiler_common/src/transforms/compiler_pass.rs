@@ -0,0 +1,49 @@
+use petgraph::stable_graph::StableGraph;
+use thiserror::Error;
+
+use crate::{EdgeInfo, NodeInfo, Operation};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{0}")]
+/**
+ * The reason a [`CompilerPass`] failed.
+ */
+pub struct CompilerPassError(pub String);
+
+impl CompilerPassError {
+    /**
+     * Creates a [`CompilerPassError`] with the given message.
+     */
+    pub fn new(msg: &str) -> Self {
+        Self(msg.to_owned())
+    }
+}
+
+/**
+ * A user-defined transformation a compiler runs over a program's graph
+ * alongside its built-in passes.
+ *
+ * # Remarks
+ * `O` is the compiler's operation type and `P` the parameters the
+ * program is being compiled under. The pass must leave the graph
+ * well-formed for the compiler's subsequent passes.
+ */
+pub trait CompilerPass<O, P>
+where
+    O: Operation,
+{
+    /**
+     * The name of this pass, used in logs and timings and as an anchor
+     * for placing other passes.
+     */
+    fn name(&self) -> &str;
+
+    /**
+     * Transforms the given graph.
+     */
+    fn run(
+        &self,
+        graph: &mut StableGraph<NodeInfo<O>, EdgeInfo>,
+        params: &P,
+    ) -> Result<(), CompilerPassError>;
+}
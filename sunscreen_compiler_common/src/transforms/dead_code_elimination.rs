@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+
+use petgraph::{
+    stable_graph::{NodeIndex, StableGraph},
+    Direction,
+};
+
+use crate::{EdgeInfo, NodeInfo, Operation};
+
+/**
+ * For the given compilation graph, perform dead code elimination
+ * (DCE).
+ *
+ * # Remarks
+ * Removes every node (and its incident edges) from which none of the
+ * given `roots` (typically the program's outputs) are reachable. For
+ * example:
+ * ```ignore
+ * a = b + c
+ * d = b * c // never used
+ * output(a)
+ * ```
+ * `d` contributes nothing to the output and is removed.
+ *
+ * Nodes in `roots` are always kept, so callers should include any node
+ * that must survive regardless of use, such as a program's inputs.
+ *
+ * This pass removes nodes in place and doesn't compact the graph, so
+ * surviving nodes keep their indices. Running it more than once has no
+ * further effect.
+ */
+pub fn dead_code_elimination<O: Operation>(
+    graph: &mut StableGraph<NodeInfo<O>, EdgeInfo>,
+    roots: &[NodeIndex],
+) {
+    let mut live = HashSet::new();
+    let mut stack = roots.to_owned();
+
+    while let Some(id) = stack.pop() {
+        if !graph.contains_node(id) || !live.insert(id) {
+            continue;
+        }
+
+        stack.extend(graph.neighbors_directed(id, Direction::Incoming));
+    }
+
+    let dead = graph
+        .node_indices()
+        .filter(|id| !live.contains(id))
+        .collect::<Vec<NodeIndex>>();
+
+    for id in dead {
+        graph.remove_node(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Operation as OperationTrait;
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+    enum Operation {
+        Input,
+        Add,
+        Mul,
+        Neg,
+    }
+
+    impl OperationTrait for Operation {
+        fn is_binary(&self) -> bool {
+            matches!(self, Operation::Add | Operation::Mul)
+        }
+
+        fn is_commutative(&self) -> bool {
+            matches!(self, Operation::Mul | Operation::Add)
+        }
+
+        fn is_unary(&self) -> bool {
+            matches!(self, Operation::Neg)
+        }
+
+        fn is_unordered(&self) -> bool {
+            false
+        }
+
+        fn is_ordered(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn removes_unreachable_nodes() {
+        let mut graph = StableGraph::<NodeInfo<Operation>, EdgeInfo>::new();
+
+        let a = graph.add_node(NodeInfo::new(Operation::Input));
+        let b = graph.add_node(NodeInfo::new(Operation::Input));
+        let add = graph.add_node(NodeInfo::new(Operation::Add));
+        let mul = graph.add_node(NodeInfo::new(Operation::Mul));
+        let neg = graph.add_node(NodeInfo::new(Operation::Neg));
+
+        graph.add_edge(a, add, EdgeInfo::Left);
+        graph.add_edge(b, add, EdgeInfo::Right);
+        graph.add_edge(a, mul, EdgeInfo::Left);
+        graph.add_edge(b, mul, EdgeInfo::Right);
+        graph.add_edge(mul, neg, EdgeInfo::Unary);
+
+        dead_code_elimination(&mut graph, &[add]);
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.contains_node(a));
+        assert!(graph.contains_node(b));
+        assert!(graph.contains_node(add));
+        assert!(!graph.contains_node(mul));
+        assert!(!graph.contains_node(neg));
+
+        // Idempotent
+        dead_code_elimination(&mut graph, &[add]);
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+    }
+}
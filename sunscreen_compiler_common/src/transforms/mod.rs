@@ -1,5 +1,9 @@
 mod common_subexpression_elimination;
+mod compiler_pass;
+mod dead_code_elimination;
 mod graph_transforms;
 
 pub use common_subexpression_elimination::*;
+pub use compiler_pass::*;
+pub use dead_code_elimination::*;
 pub use graph_transforms::*;
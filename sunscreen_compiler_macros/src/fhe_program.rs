@@ -35,9 +35,31 @@ struct FheProgram<'a> {
 impl<'a> FheProgram<'a> {
     // Handles validation
     fn new(item_fn: &'a ItemFn, attr_params: FheProgramAttrs) -> Result<Self> {
-        let generics = &item_fn.sig.generics;
-        let inputs = &item_fn.sig.inputs;
-        let ret = &item_fn.sig.output;
+        let sig = &item_fn.sig;
+        let generics = &sig.generics;
+        let inputs = &sig.inputs;
+        let ret = &sig.output;
+
+        // The body gets wrapped in a plain fn, so these would otherwise
+        // be silently dropped.
+        if let Some(t) = &sig.asyncness {
+            return Err(Error::new(t.span(), "FHE programs cannot be `async`."));
+        }
+
+        if let Some(t) = &sig.constness {
+            return Err(Error::new(t.span(), "FHE programs cannot be `const`."));
+        }
+
+        if let Some(t) = &sig.unsafety {
+            return Err(Error::new(t.span(), "FHE programs cannot be `unsafe`."));
+        }
+
+        if let Some(abi) = &sig.abi {
+            return Err(Error::new(
+                abi.span(),
+                "FHE programs cannot specify an ABI.",
+            ));
+        }
 
         if !generics.params.is_empty() {
             return Err(Error::new(
@@ -105,6 +127,14 @@ impl<'a> FheProgram<'a> {
         emit_signature(&argument_types, &self.return_types)
     }
 
+    // The names of the arguments
+    fn argument_names(&self) -> Vec<String> {
+        self.unwrapped_inputs
+            .iter()
+            .map(|(_, _, name)| name.to_string())
+            .collect()
+    }
+
     // The arguments to the internal closure (input args wrapped in FheProgramNode)
     fn fhe_program_args(&self) -> Vec<TokenStream> {
         self.unwrapped_inputs
@@ -172,6 +202,16 @@ impl<'a> FheProgram<'a> {
                     sunscreen::SchemeType::Bfv
                 }
             }
+            Scheme::Ckks => {
+                quote! {
+                    sunscreen::SchemeType::Ckks
+                }
+            }
+            Scheme::Bgv => {
+                quote! {
+                    sunscreen::SchemeType::Bgv
+                }
+            }
         };
 
         let fhe_program_args = self.fhe_program_args();
@@ -185,6 +225,7 @@ impl<'a> FheProgram<'a> {
         let inner_return_into_values = pack_into_tuple(&suffix_coerce(&inner_return_idents));
 
         let signature = self.signature();
+        let argument_names = self.argument_names();
 
         let fhe_arg_var_decl = self.fhe_arg_var_decl();
         let fhe_arg_vars = self.fhe_arg_vars();
@@ -210,12 +251,15 @@ impl<'a> FheProgram<'a> {
                     use std::mem::transmute;
                     use sunscreen::{fhe::{CURRENT_FHE_CTX, FheContext}, Error, INDEX_ARENA, Result, Params, SchemeType, Value, types::{intern::{FheProgramNode, Input, Output, Coerce}, NumCiphertexts, Type, TypeName, SwapRows, LaneCount, TypeNameInstance}};
 
-                    if SchemeType::Bfv != params.scheme_type {
+                    if #scheme_type != params.scheme_type {
                         return Err(Error::IncorrectScheme)
                     }
 
                     let mut context = FheContext::new(params.clone());
 
+                    // Discard any error left behind by a build that panicked.
+                    sunscreen::fhe::take_build_error();
+
                     CURRENT_FHE_CTX.with(|ctx| {
                         #[allow(clippy::let_unit_value)]
                         #[allow(clippy::unused_unit)]
@@ -258,6 +302,10 @@ impl<'a> FheProgram<'a> {
                         ctx.swap(&RefCell::new(None));
                     });
 
+                    if let Some(err) = sunscreen::fhe::take_build_error() {
+                        return Err(err);
+                    }
+
                     Ok(context.graph)
                 }
 
@@ -268,6 +316,10 @@ impl<'a> FheProgram<'a> {
                     #signature
                 }
 
+                fn argument_names(&self) -> Vec<String> {
+                    vec![#(#argument_names.to_owned()),*]
+                }
+
                 fn scheme_type(&self) -> sunscreen::SchemeType {
                     #scheme_type
                 }
@@ -347,4 +399,62 @@ mod test {
 
         assert!(FheProgram::new(&attempt_fn, attrs).is_err())
     }
+
+    fn error_line(src: &str) -> usize {
+        let attrs = FheProgramAttrs {
+            scheme: Scheme::Bfv,
+            chain_count: 1,
+        };
+        let item_fn = syn::parse_str::<ItemFn>(src).unwrap();
+
+        match FheProgram::new(&item_fn, attrs) {
+            Ok(_) => panic!("Expected an error"),
+            Err(e) => e.span().start().line,
+        }
+    }
+
+    #[test]
+    fn errors_point_at_offending_line() {
+        let qualifier = "
+            #[allow(unused)]
+            async
+            fn f(a: Cipher<Signed>) -> Cipher<Signed> {
+                a
+            }";
+
+        assert_eq!(error_line(qualifier), 3);
+
+        let argument = "
+            fn f(
+                a: Cipher<Signed>,
+                mut b: Cipher<Signed>,
+            ) -> Cipher<Signed> {
+                a + b
+            }";
+
+        assert_eq!(error_line(argument), 4);
+
+        let generics = "
+            fn f
+            <T>
+            (a: Cipher<T>) -> Cipher<Signed> {
+                a
+            }";
+
+        assert_eq!(error_line(generics), 3);
+    }
+
+    #[test]
+    fn disallows_fn_qualifiers() {
+        for qualifier in ["async", "const", "unsafe", "extern \"C\""] {
+            let attrs = FheProgramAttrs {
+                scheme: Scheme::Bfv,
+                chain_count: 1,
+            };
+            let src = format!("{qualifier} fn f(a: Cipher<Signed>) -> Cipher<Signed> {{ a }}");
+            let attempt_fn = syn::parse_str::<ItemFn>(&src).unwrap();
+
+            assert!(FheProgram::new(&attempt_fn, attrs).is_err());
+        }
+    }
 }
@@ -0,0 +1,131 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields};
+
+use crate::error::{Error, Result};
+
+pub fn derive_fhe_type(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match derive_fhe_type_inner(input) {
+        Ok(s) => s.into(),
+        Err(Error::CompileError(s, msg)) => proc_macro::TokenStream::from(quote_spanned! {
+            s => compile_error! { #msg }
+        }),
+    }
+}
+
+fn derive_fhe_type_inner(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => f.named.iter().collect::<Vec<_>>(),
+            _ => {
+                return Err(Error::compile_error(
+                    s.fields.span(),
+                    "FheType can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::compile_error(
+                name.span(),
+                "FheType can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let field_names = fields
+        .iter()
+        .map(|f| f.ident.as_ref().unwrap())
+        .collect::<Vec<_>>();
+    let field_types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+
+    // Emit these at each field's type so a field that isn't an FheType
+    // reports its error there rather than at the derive.
+    let assertions = field_types.iter().map(|ty| {
+        quote_spanned! {ty.span() =>
+            assert_fhe_type::<#ty>();
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics sunscreen::types::NumCiphertexts for #name #ty_generics #where_clause {
+            const NUM_CIPHERTEXTS: usize = 0 #(+ <#field_types as sunscreen::types::NumCiphertexts>::NUM_CIPHERTEXTS)*;
+        }
+
+        impl #impl_generics sunscreen::types::TryIntoPlaintext for #name #ty_generics #where_clause {
+            fn try_into_plaintext(
+                &self,
+                params: &sunscreen::Params,
+            ) -> std::result::Result<sunscreen::Plaintext, sunscreen::RuntimeError> {
+                let mut plaintexts = vec![];
+
+                #(
+                    match sunscreen::types::TryIntoPlaintext::try_into_plaintext(&self.#field_names, params)?.inner {
+                        sunscreen::InnerPlaintext::Seal(p) => plaintexts.extend(p),
+                    };
+                )*
+
+                Ok(sunscreen::Plaintext {
+                    data_type: sunscreen::types::TypeNameInstance::type_name_instance(self),
+                    inner: sunscreen::InnerPlaintext::Seal(plaintexts),
+                })
+            }
+        }
+
+        impl #impl_generics sunscreen::types::TryFromPlaintext for #name #ty_generics #where_clause {
+            fn try_from_plaintext(
+                plaintext: &sunscreen::Plaintext,
+                params: &sunscreen::Params,
+            ) -> std::result::Result<Self, sunscreen::RuntimeError> {
+                let plaintexts = match &plaintext.inner {
+                    sunscreen::InnerPlaintext::Seal(p) => p,
+                };
+
+                if plaintexts.len() != <Self as sunscreen::types::NumCiphertexts>::NUM_CIPHERTEXTS {
+                    return Err(sunscreen::RuntimeError::MalformedPlaintext);
+                }
+
+                let mut offset = 0;
+
+                #(
+                    let count = <#field_types as sunscreen::types::NumCiphertexts>::NUM_CIPHERTEXTS;
+
+                    let #field_names = <#field_types as sunscreen::types::TryFromPlaintext>::try_from_plaintext(
+                        &sunscreen::Plaintext {
+                            data_type: <#field_types as sunscreen::types::TypeName>::type_name(),
+                            inner: sunscreen::InnerPlaintext::Seal(
+                                plaintexts[offset..offset + count].to_vec(),
+                            ),
+                        },
+                        params,
+                    )?;
+
+                    offset += count;
+                )*
+
+                let _ = offset;
+
+                Ok(Self { #(#field_names),* })
+            }
+        }
+
+        impl #impl_generics sunscreen::FheProgramInputTrait for #name #ty_generics #where_clause {}
+
+        impl #impl_generics sunscreen::types::FheType for #name #ty_generics #where_clause {}
+
+        impl #impl_generics sunscreen::types::BfvType for #name #ty_generics #where_clause {}
+
+        const _: () = {
+            fn assert_fhe_type<T: sunscreen::types::FheType>() {}
+
+            #[allow(dead_code)]
+            fn assert_fields #impl_generics () #where_clause {
+                #(#assertions)*
+            }
+        };
+    })
+}
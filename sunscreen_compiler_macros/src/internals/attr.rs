@@ -165,6 +165,8 @@ fn try_parse_dict(input: ParseStream) -> SynResult<HashMap<String, AttrValue>> {
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum Scheme {
     Bfv,
+    Ckks,
+    Bgv,
 }
 
 impl TryFrom<&AttrValue> for Scheme {
@@ -175,6 +177,8 @@ impl TryFrom<&AttrValue> for Scheme {
 
         let scheme = match as_str {
             "bfv" => Self::Bfv,
+            "ckks" => Self::Ckks,
+            "bgv" => Self::Bgv,
             _ => {
                 return Err(SynError::new(
                     value.span(),
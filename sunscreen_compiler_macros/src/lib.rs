@@ -9,6 +9,7 @@ extern crate proc_macro;
 mod error;
 mod fhe_program;
 mod fhe_program_transforms;
+mod fhe_type;
 mod internals;
 mod type_name;
 mod zkp_program;
@@ -21,6 +22,33 @@ pub fn derive_typename(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
     type_name::derive_typename(input)
 }
 
+#[proc_macro_derive(FheType)]
+/**
+ * Allows you to `#[derive(FheType)]` on a struct whose fields are all
+ * `FheType`s, so it can be encrypted, decrypted, and passed to FHE
+ * programs as a single value.
+ *
+ * # Remarks
+ * The struct's plaintext is its fields' plaintexts concatenated in
+ * declaration order, so it decomposes into the sum of its fields'
+ * ciphertext counts.
+ *
+ * Only structs with named fields are supported. You must also
+ * `#[derive(TypeName)]`.
+ *
+ * # Examples
+ * ```rust,ignore
+ * #[derive(Clone, Debug, PartialEq, TypeName, FheType)]
+ * struct Point {
+ *     x: Signed,
+ *     y: Signed,
+ * }
+ * ```
+ */
+pub fn derive_fhe_type(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    fhe_type::derive_fhe_type(input)
+}
+
 #[proc_macro_attribute]
 /**
  * Specifies a function to be an [`fhe_program`](macro@fhe_program). An [`fhe_program`](macro@fhe_program) has any number of inputs that impl the
@@ -30,8 +58,12 @@ pub fn derive_typename(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
  * This function gets run by the compiler to build up the [`fhe_program`](macro@fhe_program) you specify and does not
  * directly or eagerly perform homomorphic operations.
  *
+ * FHE programs can't be generic, `async`, `const`, `unsafe`, or
+ * `extern`, and their arguments can't be `mut` or have attributes. The
+ * compile error for each of these points at the offending token.
+ *
  * # Parameters
- * * `scheme` (required): Designates the scheme this [`fhe_program`](macro@fhe_program) uses. Today, this must be `"bfv"`.
+ * * `scheme` (required): Designates the scheme this [`fhe_program`](macro@fhe_program) uses. This must be `"bfv"` or the experimental `"ckks"` or `"bgv"`.
  *
  * # Examples
  * ```rust,ignore
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use petgraph::{dot::Dot, stable_graph::NodeIndex, visit::EdgeRef};
+use sunscreen_compiler_common::Render;
+
+use crate::{multiplicative_depths, FheProgram, FheProgramTrait, Literal, Operation};
+
+/**
+ * A human-readable label for a node. Inputs show their argument index
+ * and outputs their position among the program's outputs.
+ */
+fn label(op: &Operation, output_index: Option<usize>) -> String {
+    match (op, output_index) {
+        (Operation::InputCiphertext(id), _) => format!("Input[{id}]"),
+        (Operation::InputPlaintext(id), _) => format!("InputPlaintext[{id}]"),
+        (Operation::Literal(Literal::U64(x)), _) => format!("Literal[{x}]"),
+        (Operation::Literal(Literal::Plaintext(_)), _) => "Literal[Plaintext]".to_owned(),
+        (Operation::OutputCiphertext, Some(i)) => format!("Output[{i}]"),
+        (op, _) => format!("{op:?}"),
+    }
+}
+
+/**
+ * The fill color for a node by the kind of operation.
+ */
+fn operation_color(op: &Operation) -> &'static str {
+    match op {
+        Operation::InputCiphertext(_) | Operation::InputPlaintext(_) => "lightblue",
+        Operation::Literal(_) => "white",
+        Operation::OutputCiphertext => "lightgrey",
+        Operation::Multiply
+        | Operation::MultiplyPlaintext
+        | Operation::Relinearize
+        | Operation::ModSwitch => "salmon",
+        Operation::Add
+        | Operation::AddPlaintext
+        | Operation::Sub
+        | Operation::SubPlaintext
+        | Operation::Negate => "palegreen",
+        Operation::ShiftLeft | Operation::ShiftRight | Operation::SwapRows => "khaki",
+    }
+}
+
+/**
+ * Interpolates from green at depth 0 to red at `max_depth`.
+ */
+fn depth_color(depth: usize, max_depth: usize) -> String {
+    let red = (255 * depth).checked_div(max_depth).unwrap_or(0);
+
+    format!("#{:02x}{:02x}00", red, 255 - red)
+}
+
+/**
+ * Renders `ir` in DOT format, filling each node with the color `color`
+ * returns for it.
+ */
+fn render<F>(ir: &FheProgram, color: F) -> String
+where
+    F: Fn(NodeIndex) -> String,
+{
+    let outputs = ir
+        .get_outputs()
+        .enumerate()
+        .map(|(i, id)| (id, i))
+        .collect::<HashMap<_, _>>();
+
+    let data = Dot::with_attr_getters(
+        &ir.graph.0,
+        &[
+            petgraph::dot::Config::NodeNoLabel,
+            petgraph::dot::Config::EdgeNoLabel,
+        ],
+        &|_, e| format!("label=\"{}\"", e.weight().render()),
+        &|_, (id, n)| {
+            format!(
+                "label=\"{}\" style=filled fillcolor=\"{}\"",
+                label(&n.operation, outputs.get(&id).copied()),
+                color(id)
+            )
+        },
+    );
+
+    format!("{data:?}")
+}
+
+pub(crate) fn to_dot(ir: &FheProgram) -> String {
+    render(ir, |id| operation_color(&ir.graph[id].operation).to_owned())
+}
+
+pub(crate) fn to_dot_with_depth_colors(ir: &FheProgram) -> String {
+    let depths = multiplicative_depths(ir);
+    let max_depth = depths.values().copied().max().unwrap_or(0);
+
+    render(ir, |id| depth_color(depths[&id], max_depth))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SchemeType;
+
+    /**
+     * A minimal check that `dot` is a well-formed DOT digraph of
+     * single-line node and edge statements, returning the statements.
+     */
+    fn parse_dot(dot: &str) -> Vec<&str> {
+        let body = dot
+            .trim()
+            .strip_prefix("digraph {")
+            .and_then(|d| d.strip_suffix('}'))
+            .expect("Not a digraph");
+
+        let statements = body
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>();
+
+        for s in &statements {
+            let (target, attrs) = s.split_once(" [ ").expect("Missing attribute list");
+            let attrs = attrs
+                .strip_suffix(']')
+                .expect("Unterminated attribute list");
+
+            let ids = target.split(" -> ").collect::<Vec<_>>();
+            assert!(ids.len() <= 2);
+            assert!(ids.iter().all(|id| id.parse::<usize>().is_ok()));
+
+            assert_eq!(attrs.matches('"').count() % 2, 0, "Unbalanced quotes");
+        }
+
+        statements
+    }
+
+    fn create_program() -> FheProgram {
+        // (a * b) + c
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let c = ir.add_input_ciphertext(2);
+        let mul = ir.add_multiply(a, b);
+        let add = ir.add_add(mul, c);
+        ir.add_output_ciphertext(add);
+
+        ir
+    }
+
+    #[test]
+    fn dot_has_readable_labels() {
+        let ir = create_program();
+        let dot = ir.to_dot();
+
+        let statements = parse_dot(&dot);
+
+        // 6 nodes and 5 edges.
+        assert_eq!(statements.len(), 11);
+
+        for label in ["Input[0]", "Input[2]", "Multiply", "Add", "Output[0]"] {
+            assert!(dot.contains(&format!("label=\"{label}\"")), "{label}");
+        }
+
+        assert!(dot.contains("fillcolor=\"salmon\""));
+        assert!(dot.contains("label=\"Left\""));
+    }
+
+    #[test]
+    fn depth_colors_range_from_green_to_red() {
+        let ir = create_program();
+        let dot = ir.to_dot_with_depth_colors();
+
+        parse_dot(&dot);
+
+        assert!(dot.contains("label=\"Input[0]\" style=filled fillcolor=\"#00ff00\""));
+        assert!(dot.contains("label=\"Multiply\" style=filled fillcolor=\"#ff0000\""));
+    }
+
+    #[test]
+    fn depth_colors_of_linear_program_are_green() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let neg = ir.add_negate(a);
+        ir.add_output_ciphertext(neg);
+
+        let dot = ir.to_dot_with_depth_colors();
+
+        assert_eq!(dot.matches("#00ff00").count(), 3);
+    }
+}
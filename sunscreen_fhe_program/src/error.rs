@@ -25,6 +25,26 @@ pub enum Error {
      */
     #[error("Attempted to deserialize and unknown scheme type.")]
     InvalidSchemeType,
+
+    /**
+     * The requested output index (first argument) exceeds the number of
+     * outputs in the [`FheProgram`](crate::FheProgram) (second argument).
+     */
+    #[error("Output index {} is out of range for a program with {} outputs", .0.0, .0.1)]
+    OutputIndexOutOfRange(Box<(usize, usize)>),
+    /**
+     * Failed to serialize or deserialize an [`FheProgram`](crate::FheProgram).
+     */
+    #[error("Serialization error: {0}")]
+    SerializationError(Box<String>),
+
+    /**
+     * The serialized [`FheProgram`](crate::FheProgram) was written in
+     * the given format version, which this version of Sunscreen can't
+     * read. See [`FORMAT_VERSION`](crate::FORMAT_VERSION).
+     */
+    #[error("Unsupported FHE program format version {0}")]
+    UnsupportedFormatVersion(u32),
 }
 
 const_assert!(std::mem::size_of::<Error>() <= 16);
@@ -36,6 +56,20 @@ impl Error {
     pub fn ir_error(inner: &[IRError]) -> Self {
         Self::IRError(Box::new(inner.to_owned()))
     }
+
+    /**
+     * Creates an [`Error::OutputIndexOutOfRange`].
+     */
+    pub fn output_index_out_of_range(index: usize, num_outputs: usize) -> Self {
+        Self::OutputIndexOutOfRange(Box::new((index, num_outputs)))
+    }
+
+    /**
+     * Creates an [`Error::SerializationError`].
+     */
+    pub fn serialization_error(msg: &str) -> Self {
+        Self::SerializationError(Box::new(msg.to_owned()))
+    }
 }
 
 /**
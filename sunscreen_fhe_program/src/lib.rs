@@ -4,18 +4,23 @@
 //! This crate contains the types for manipulating the intermediate representation
 //! for Sunscreen's compiler backend.
 
+mod dot;
 mod error;
 mod literal;
 mod operation;
+pub mod passes;
+mod serialization;
+mod stats;
 
 mod validation;
 
 use petgraph::{
-    algo::toposort,
     algo::tred::*,
+    algo::{is_isomorphic_matching, toposort},
     graph::{Graph, NodeIndex},
     stable_graph::StableGraph,
-    visit::IntoNeighbors,
+    visit::{EdgeRef, IntoNeighbors},
+    Direction,
 };
 use serde::{Deserialize, Serialize};
 
@@ -23,14 +28,18 @@ pub use error::*;
 pub use literal::*;
 pub use operation::*;
 pub use seal_fhe::SecurityLevel;
+pub use serialization::FORMAT_VERSION;
+pub use stats::*;
 
-use sunscreen_compiler_common::{CompilationResult, Context, EdgeInfo, NodeInfo};
+use sunscreen_compiler_common::{CompilationResult, Context, EdgeInfo, NodeInfo, Render};
 
-use std::collections::HashSet;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, Serialize, Hash, Deserialize, PartialEq, Eq)]
 /**
- * Sunscreen supports the BFV scheme.
+ * Sunscreen supports the BFV scheme and has experimental support for
+ * CKKS and BGV.
  */
 pub enum SchemeType {
     /**
@@ -89,6 +98,35 @@ pub enum SchemeType {
      * will be approximate and/or particular to the scheme parameters.
      */
     Bfv,
+
+    /**
+     *
+     * # Remarks
+     * [CKKS](https://eprint.iacr.org/2016/421.pdf) is a leveled scheme for approximate arithmetic
+     * on vectors of complex numbers. Plaintexts encode N/2 complex values (where N is the
+     * polynomial degree), each multiplied by a scale and rounded, so results are only accurate to
+     * roughly `log2(scale)` bits less the noise operations introduce.
+     *
+     * Each ciphertext multiplication is followed by a rescale, which consumes a prime from the
+     * coefficient modulus. Support is experimental: only addition and multiplication of `Complex`
+     * values are supported, and parameters must be given explicitly. Programs can't yet combine
+     * ciphertexts that went through different numbers of multiplications, so the compiler rejects
+     * `a * b + c` and `(a * b) * c` but accepts `(a * b) * (c * d)`.
+     */
+    Ckks,
+
+    /**
+     *
+     * # Remarks
+     * [BGV](https://eprint.iacr.org/2011/277.pdf) is a leveled scheme on the same plaintexts as
+     * [`Bfv`](Self::Bfv), so BFV's encodings work unchanged. Unlike BFV, BGV keeps noise in check
+     * by switching to a smaller coefficient modulus, so each ciphertext multiplication is followed
+     * by a modulus switch that consumes a prime from the coefficient modulus. The compiler inserts
+     * these as [`ModSwitch`](Operation::ModSwitch) operations, switching the higher of two
+     * operands down to the other's level before combining them. Support is experimental, and
+     * parameters must be given explicitly.
+     */
+    Bgv,
 }
 
 impl From<SchemeType> for u8 {
@@ -98,6 +136,8 @@ impl From<SchemeType> for u8 {
     fn from(val: SchemeType) -> Self {
         match val {
             SchemeType::Bfv => 0,
+            SchemeType::Ckks => 1,
+            SchemeType::Bgv => 2,
         }
     }
 }
@@ -111,6 +151,8 @@ impl TryFrom<u8> for SchemeType {
     fn try_from(val: u8) -> Result<Self> {
         Ok(match val {
             0 => Self::Bfv,
+            1 => Self::Ckks,
+            2 => Self::Bgv,
             _ => Err(Error::InvalidSchemeType)?,
         })
     }
@@ -214,6 +256,12 @@ pub trait FheProgramTrait {
      */
     fn add_relinearize(&mut self, x: NodeIndex) -> NodeIndex;
 
+    /**
+     * Appends an operation that switches `x` to the next modulus in the
+     * coefficient modulus chain.
+     */
+    fn add_mod_switch(&mut self, x: NodeIndex) -> NodeIndex;
+
     /**
      * Appends an operation that rotates ciphertext `x` left by the literal node at `y` places.
      *
@@ -252,6 +300,23 @@ pub trait FheProgramTrait {
      */
     fn prune(&self, nodes: &[NodeIndex]) -> Self;
 
+    /**
+     * Returns a derived FheProgram that computes only the output at
+     * `index`.
+     *
+     * # Remarks
+     * Outputs are numbered in the order the runtime returns them. The
+     * derived program keeps every input so it accepts the same
+     * arguments as the original, but nodes that only contribute to
+     * other outputs are removed.
+     *
+     * Returns [`Error::OutputIndexOutOfRange`] if `index` is not less
+     * than the number of outputs.
+     */
+    fn reduce_to_output(&self, index: usize) -> Result<Self>
+    where
+        Self: Sized;
+
     /**
      * Validates this [`FheProgram`] for correctness.
      */
@@ -267,6 +332,151 @@ pub trait FheProgramTrait {
      * operations.
      */
     fn requires_galois_keys(&self) -> bool;
+
+    /**
+     * Returns the sorted rotation steps this FHE program's Galois keys
+     * must support. See [`passes::collect_rotations`].
+     */
+    fn required_galois_steps(&self) -> Vec<i32>;
+
+    /**
+     * Returns the multiplicative depth of this FHE program: the largest
+     * number of [`Operation::Multiply`] and
+     * [`Operation::MultiplyPlaintext`] nodes along any path from an
+     * input to an output.
+     *
+     * # Remarks
+     * The multiplicative depth largely determines the required
+     * coefficient modulus size, and hence performance.
+     */
+    fn multiplicative_depth(&self) -> usize;
+
+    /**
+     * Returns the largest number of [`Operation::ModSwitch`] nodes along
+     * any path from an input to an output.
+     *
+     * # Remarks
+     * Each modulus switch consumes a prime from the coefficient modulus,
+     * so this is how many levels a compiled BGV program needs.
+     */
+    fn mod_switch_depth(&self) -> usize;
+
+    /**
+     * Returns a longest path from an input to an output, with length
+     * measured in [`Operation::Multiply`] and
+     * [`Operation::MultiplyPlaintext`] nodes, starting with the input.
+     *
+     * # Remarks
+     * Even with unlimited parallelism, the operations along this path
+     * must run one after another. Plaintext operands don't add to a
+     * path's length, so where a ciphertext and a plaintext path tie,
+     * this follows the ciphertext. Remaining ties break toward the
+     * lowest node index. Returns an empty path if the program has no
+     * outputs.
+     */
+    fn critical_path(&self) -> Vec<NodeIndex>;
+
+    /**
+     * Returns the number of multiplications along the
+     * [`critical_path`](Self::critical_path), which equals the
+     * [`multiplicative_depth`](Self::multiplicative_depth).
+     */
+    fn critical_path_depth(&self) -> usize;
+
+    /**
+     * Counts the homomorphic operations of each kind in this FHE
+     * program.
+     */
+    fn operation_count(&self) -> OperationStats;
+
+    /**
+     * Returns the largest number of multiplications along any path from
+     * an input to the given node, including the node itself.
+     *
+     * # Panics
+     * Panics if `node` isn't in this program.
+     */
+    fn depth_of_node(&self, node: NodeIndex) -> usize;
+
+    /**
+     * Renders this program in DOT format, labeling each node with its
+     * operation (e.g. `Input[0]`, `Multiply`, `Output[0]`) and coloring
+     * it by the kind of operation.
+     */
+    fn to_dot(&self) -> String;
+
+    /**
+     * Like [`to_dot`](Self::to_dot), but colors each node by its
+     * multiplicative depth (see
+     * [`depth_of_node`](Self::depth_of_node)) on a gradient from green
+     * at depth 0 to red at the program's deepest node.
+     */
+    fn to_dot_with_depth_colors(&self) -> String;
+
+    /**
+     * Whether this program and `other` compute the same graph of
+     * operations, ignoring node indices.
+     *
+     * # Remarks
+     * This checks graph isomorphism, matching operations (including
+     * literal values and input indices) and operand positions. Unlike
+     * comparing [`deterministic_hash`](Self::deterministic_hash)es, it
+     * can't report a false positive.
+     */
+    fn structurally_eq(&self, other: &Self) -> bool;
+
+    /**
+     * Returns an equivalent FheProgram whose node indices are assigned
+     * in a canonical topological order.
+     *
+     * # Remarks
+     * Nodes are numbered breadth-first from the inputs. Among the nodes
+     * ready to be numbered, ties are broken by each node's operation and
+     * the canonical indices of its operands, so two isomorphic programs
+     * receive identical node and edge numberings regardless of the
+     * order in which they were constructed.
+     */
+    fn alpha_rename(&self) -> Self;
+
+    /**
+     * Returns a hash of this program's structure that's stable across
+     * runs and equal for isomorphic programs.
+     */
+    fn deterministic_hash(&self) -> u64;
+
+    /**
+     * Serializes this program to a compact binary format, tagged with
+     * [`FORMAT_VERSION`].
+     */
+    fn to_bincode(&self) -> Result<Vec<u8>>;
+
+    /**
+     * Deserializes a program written by [`to_bincode`](Self::to_bincode).
+     *
+     * # Remarks
+     * Returns [`Error::UnsupportedFormatVersion`] if `data` was written
+     * in a different format version.
+     */
+    fn from_bincode(data: &[u8]) -> Result<Self>
+    where
+        Self: Sized;
+
+    /**
+     * Serializes this program to human-readable JSON, tagged with
+     * [`FORMAT_VERSION`].
+     */
+    fn to_json(&self) -> Result<String>;
+
+    /**
+     * Deserializes a program written by [`to_json`](Self::to_json).
+     *
+     * # Remarks
+     * Returns [`Error::UnsupportedFormatVersion`] if `data` was written
+     * in a different format version.
+     */
+    fn from_json(data: &str) -> Result<Self>
+    where
+        Self: Sized;
 }
 
 impl FheProgramTrait for FheProgram {
@@ -310,6 +520,10 @@ impl FheProgramTrait for FheProgram {
         self.add_unary_operation(Operation::Relinearize, x)
     }
 
+    fn add_mod_switch(&mut self, x: NodeIndex) -> NodeIndex {
+        self.add_unary_operation(Operation::ModSwitch, x)
+    }
+
     fn add_rotate_left(&mut self, x: NodeIndex, y: NodeIndex) -> NodeIndex {
         self.add_binary_operation(Operation::ShiftLeft, x, y)
     }
@@ -341,12 +555,21 @@ impl FheProgramTrait for FheProgram {
         let (res, revmap) = dag_to_toposorted_adjacency_list(&compact_graph, &topo);
         let (_, closure) = dag_transitive_reduction_closure(&res);
 
+        // Graph::from compacts away the StableGraph's holes, keeping
+        // nodes in order, so translate nodes to their compacted indices.
+        let compact_ids = self
+            .graph
+            .node_indices()
+            .enumerate()
+            .map(|(i, n)| (n, NodeIndex::new(i)))
+            .collect::<HashMap<_, _>>();
+
         let mut closure_set = HashSet::new();
 
         let mut visit: Vec<NodeIndex> = vec![];
 
         for n in nodes {
-            let mapped_id = revmap[n.index()];
+            let mapped_id = revmap[compact_ids[n].index()];
             visit.push(mapped_id);
             closure_set.insert(mapped_id);
         }
@@ -385,6 +608,16 @@ impl FheProgramTrait for FheProgram {
         }
     }
 
+    fn reduce_to_output(&self, index: usize) -> Result<FheProgram> {
+        let outputs = self.get_outputs().collect::<Vec<NodeIndex>>();
+
+        let output = outputs
+            .get(index)
+            .ok_or_else(|| Error::output_index_out_of_range(index, outputs.len()))?;
+
+        Ok(self.prune(&[*output]))
+    }
+
     fn validate(&self) -> Result<()> {
         let errors = validation::validate_ir(self);
 
@@ -409,6 +642,275 @@ impl FheProgramTrait for FheProgram {
             )
         })
     }
+
+    fn required_galois_steps(&self) -> Vec<i32> {
+        let mut steps = passes::collect_rotations(&self.graph.0)
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        steps.sort_unstable();
+
+        steps
+    }
+
+    fn multiplicative_depth(&self) -> usize {
+        let depths = multiplicative_depths(self);
+
+        self.get_outputs().map(|o| depths[&o]).max().unwrap_or(0)
+    }
+
+    fn mod_switch_depth(&self) -> usize {
+        let depths = depths_counting(self, |op| matches!(op, Operation::ModSwitch));
+
+        self.get_outputs().map(|o| depths[&o]).max().unwrap_or(0)
+    }
+
+    fn critical_path(&self) -> Vec<NodeIndex> {
+        let depths = multiplicative_depths(self);
+
+        let is_plaintext = |n: NodeIndex| {
+            matches!(
+                self.graph[n].operation,
+                Operation::InputPlaintext(_) | Operation::Literal(_)
+            )
+        };
+
+        let mut cur = self
+            .get_outputs()
+            .min_by_key(|o| (Reverse(depths[o]), o.index()));
+
+        let mut path = vec![];
+
+        // Walk back along parents that account for each node's depth.
+        while let Some(id) = cur {
+            path.push(id);
+
+            let parent_depth =
+                depths[&id] - usize::from(is_multiplication(&self.graph[id].operation));
+
+            cur = self
+                .graph
+                .neighbors_directed(id, Direction::Incoming)
+                .filter(|p| depths[p] == parent_depth)
+                .min_by_key(|p| (is_plaintext(*p), p.index()));
+        }
+
+        path.reverse();
+
+        path
+    }
+
+    fn critical_path_depth(&self) -> usize {
+        self.critical_path()
+            .iter()
+            .filter(|n| is_multiplication(&self.graph[**n].operation))
+            .count()
+    }
+
+    fn operation_count(&self) -> OperationStats {
+        let mut stats = OperationStats::default();
+
+        for n in self.graph.node_weights() {
+            stats.record(&n.operation);
+        }
+
+        stats
+    }
+
+    fn depth_of_node(&self, node: NodeIndex) -> usize {
+        assert!(self.graph.contains_node(node), "Node not in FHE program");
+
+        multiplicative_depths(self)[&node]
+    }
+
+    fn to_dot(&self) -> String {
+        dot::to_dot(self)
+    }
+
+    fn to_dot_with_depth_colors(&self) -> String {
+        dot::to_dot_with_depth_colors(self)
+    }
+
+    fn structurally_eq(&self, other: &Self) -> bool {
+        self.data == other.data
+            && is_isomorphic_matching(
+                &Graph::from(self.graph.0.clone()),
+                &Graph::from(other.graph.0.clone()),
+                |n1, n2| n1 == n2,
+                |e1, e2| e1 == e2,
+            )
+    }
+
+    fn alpha_rename(&self) -> FheProgram {
+        // A node's key is its operation and its operands' new indices.
+        // Parents are always renamed before their children, so a key is
+        // fully determined by the time the node is ready.
+        type Key = (String, Vec<(String, usize)>);
+
+        let key = |id: NodeIndex, renamed: &HashMap<NodeIndex, NodeIndex>| -> Key {
+            let mut operands = self
+                .graph
+                .edges_directed(id, Direction::Incoming)
+                .map(|e| (e.weight().render(), renamed[&e.source()].index()))
+                .collect::<Vec<_>>();
+            operands.sort();
+
+            (self.graph[id].render(), operands)
+        };
+
+        let mut renamed = HashMap::new();
+        let mut remaining_parents = self
+            .graph
+            .node_indices()
+            .map(|id| {
+                let count = self.graph.edges_directed(id, Direction::Incoming).count();
+
+                (id, count)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut ready = BinaryHeap::new();
+
+        for (id, count) in &remaining_parents {
+            if *count == 0 {
+                ready.push(Reverse((key(*id, &renamed), *id)));
+            }
+        }
+
+        let mut graph = StableGraph::new();
+
+        while let Some(Reverse((_, id))) = ready.pop() {
+            renamed.insert(id, graph.add_node(self.graph[id].clone()));
+
+            for child in self.graph.neighbors_directed(id, Direction::Outgoing) {
+                let count = remaining_parents.get_mut(&child).unwrap();
+                *count -= 1;
+
+                if *count == 0 {
+                    ready.push(Reverse((key(child, &renamed), child)));
+                }
+            }
+        }
+
+        let mut edges = self
+            .graph
+            .edge_references()
+            .map(|e| (renamed[&e.source()], renamed[&e.target()], *e.weight()))
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|(s, t, e)| (*t, *s, e.render()));
+
+        for (s, t, e) in edges {
+            graph.add_edge(s, t, e);
+        }
+
+        Self {
+            data: self.data,
+            graph: CompilationResult(graph),
+        }
+    }
+
+    fn deterministic_hash(&self) -> u64 {
+        let canonical = self.alpha_rename();
+        let mut hasher = Fnv1a::new();
+
+        hasher.write(&[u8::from(canonical.data)]);
+
+        for n in canonical.graph.node_weights() {
+            hasher.write_str(&n.render());
+        }
+
+        for e in canonical.graph.edge_references() {
+            hasher.write_usize(e.source().index());
+            hasher.write_usize(e.target().index());
+            hasher.write_str(&e.weight().render());
+        }
+
+        hasher.0
+    }
+
+    fn to_bincode(&self) -> Result<Vec<u8>> {
+        serialization::to_bincode(self)
+    }
+
+    fn from_bincode(data: &[u8]) -> Result<Self> {
+        serialization::from_bincode(data)
+    }
+
+    fn to_json(&self) -> Result<String> {
+        serialization::to_json(self)
+    }
+
+    fn from_json(data: &str) -> Result<Self> {
+        serialization::from_json(data)
+    }
+}
+
+/**
+ * A 64-bit FNV-1a hasher. Unlike [`std::collections::hash_map::DefaultHasher`],
+ * its output is guaranteed not to change between Rust releases.
+ */
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_usize(&mut self, val: usize) {
+        self.write(&(val as u64).to_le_bytes());
+    }
+
+    fn write_str(&mut self, val: &str) {
+        self.write_usize(val.len());
+        self.write(val.as_bytes());
+    }
+}
+
+/**
+ * Computes the multiplicative depth of every node in the given program.
+ */
+fn multiplicative_depths(ir: &FheProgram) -> HashMap<NodeIndex, usize> {
+    depths_counting(ir, is_multiplication)
+}
+
+/**
+ * Computes, for every node in the given program, the largest number of
+ * nodes whose operation satisfies `counts` along any path ending there.
+ */
+fn depths_counting<F>(ir: &FheProgram, counts: F) -> HashMap<NodeIndex, usize>
+where
+    F: Fn(&Operation) -> bool,
+{
+    let mut depths = HashMap::new();
+
+    for id in toposort(&ir.graph.0, None).unwrap() {
+        let parent_depth = ir
+            .graph
+            .neighbors_directed(id, Direction::Incoming)
+            .map(|p| depths[&p])
+            .max()
+            .unwrap_or(0);
+
+        let counted = counts(&ir.graph[id].operation);
+
+        depths.insert(id, parent_depth + usize::from(counted));
+    }
+
+    depths
+}
+
+/**
+ * Whether `op` counts toward multiplicative depth.
+ */
+fn is_multiplication(op: &Operation) -> bool {
+    matches!(op, Operation::Multiply | Operation::MultiplyPlaintext)
 }
 
 #[cfg(test)]
@@ -474,6 +976,28 @@ mod tests {
         assert!(eq(&pruned, &expected_ir));
     }
 
+    #[test]
+    fn can_prune_graph_with_hole_before_roots() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let ct1 = ir.add_input_ciphertext(0);
+        let ct2 = ir.add_input_ciphertext(1);
+        let dead = ir.add_multiply(ct1, ct2);
+        let neg = ir.add_negate(ct2);
+        let out = ir.add_output_ciphertext(neg);
+        ir.graph.0.remove_node(dead);
+
+        let pruned = ir.prune(&[out]);
+
+        let mut expected_ir = FheProgram::new(SchemeType::Bfv);
+        let _ct1 = expected_ir.add_input_ciphertext(0);
+        let ct2 = expected_ir.add_input_ciphertext(1);
+        let neg = expected_ir.add_negate(ct2);
+        expected_ir.add_output_ciphertext(neg);
+
+        assert!(eq(&pruned, &expected_ir));
+    }
+
     #[test]
     fn can_prune_with_multiple_nodes() {
         let mut ir = FheProgram::new(SchemeType::Bfv);
@@ -525,9 +1049,300 @@ mod tests {
         assert!(eq(&pruned, &expected_ir));
     }
 
+    #[test]
+    fn can_reduce_to_single_output() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let ct1 = ir.add_input_ciphertext(0);
+        let ct2 = ir.add_input_ciphertext(1);
+        let add = ir.add_add(ct1, ct2);
+        let mul = ir.add_multiply(ct1, ct2);
+        let neg = ir.add_negate(mul);
+        ir.add_output_ciphertext(add);
+        ir.add_output_ciphertext(neg);
+
+        let reduced = ir.reduce_to_output(0).unwrap();
+
+        let mut expected_ir = FheProgram::new(SchemeType::Bfv);
+        let ct1 = expected_ir.add_input_ciphertext(0);
+        let ct2 = expected_ir.add_input_ciphertext(1);
+        let add = expected_ir.add_add(ct1, ct2);
+        expected_ir.add_output_ciphertext(add);
+
+        assert!(eq(&reduced, &expected_ir));
+        assert_eq!(reduced.get_outputs().count(), 1);
+        assert!(reduced.graph.node_count() < ir.graph.node_count());
+        reduced.validate().unwrap();
+    }
+
+    #[test]
+    fn reduce_to_output_rejects_out_of_range_index() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let ct = ir.add_input_ciphertext(0);
+        ir.add_output_ciphertext(ct);
+
+        assert_eq!(
+            ir.reduce_to_output(1).unwrap_err(),
+            Error::output_index_out_of_range(1, 1)
+        );
+    }
+
+    #[test]
+    fn multiplicative_depth_of_linear_chain() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let mul_1 = ir.add_multiply(a, a);
+        let add = ir.add_add(mul_1, a);
+        let mul_2 = ir.add_multiply(add, a);
+        let mul_3 = ir.add_multiply(mul_2, a);
+        ir.add_output_ciphertext(mul_3);
+
+        assert_eq!(ir.multiplicative_depth(), 3);
+        assert_eq!(ir.depth_of_node(a), 0);
+        assert_eq!(ir.depth_of_node(mul_1), 1);
+        assert_eq!(ir.depth_of_node(add), 1);
+        assert_eq!(ir.depth_of_node(mul_2), 2);
+    }
+
+    #[test]
+    fn mod_switch_depth_counts_longest_chain() {
+        let mut ir = FheProgram::new(SchemeType::Bgv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let mul = ir.add_multiply(a, b);
+        let switch_1 = ir.add_mod_switch(mul);
+        let switch_2 = ir.add_mod_switch(b);
+        let add = ir.add_add(switch_1, switch_2);
+        let switch_3 = ir.add_mod_switch(add);
+        ir.add_output_ciphertext(switch_3);
+
+        assert_eq!(ir.mod_switch_depth(), 2);
+        assert_eq!(ir.multiplicative_depth(), 1);
+    }
+
+    #[test]
+    fn can_count_operations() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let p = ir.add_input_plaintext(2);
+        let mul = ir.add_multiply(a, b);
+        let relin = ir.add_relinearize(mul);
+        let sub = ir.add_sub(relin, a);
+        let mul_plain = ir.add_multiply_plaintext(sub, p);
+        let neg = ir.add_negate(mul_plain);
+        ir.add_output_ciphertext(neg);
+
+        let stats = ir.operation_count();
+
+        assert_eq!(
+            stats,
+            OperationStats {
+                additions: 1,
+                plaintext_additions: 0,
+                multiplications: 1,
+                plaintext_multiplications: 1,
+                negations: 1,
+                relinearizations: 1,
+                rotations: 0,
+            }
+        );
+        assert!(stats.total_cost() > OperationStats::default().total_cost());
+    }
+
+    #[test]
+    fn multiplicative_depth_of_diamond() {
+        // One side of the diamond is deeper than the other.
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let mul_1 = ir.add_multiply(a, b);
+        let mul_2 = ir.add_multiply(mul_1, b);
+        let neg = ir.add_negate(a);
+        let join = ir.add_multiply(mul_2, neg);
+        ir.add_output_ciphertext(join);
+
+        assert_eq!(ir.depth_of_node(neg), 0);
+        assert_eq!(ir.depth_of_node(join), 3);
+        assert_eq!(ir.multiplicative_depth(), 3);
+    }
+
+    #[test]
+    fn plaintext_paths_dont_add_depth() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let p = ir.add_input_plaintext(1);
+        let mul_plain = ir.add_multiply_plaintext(a, p);
+        let add = ir.add_add(a, a);
+        ir.add_output_ciphertext(mul_plain);
+        ir.add_output_ciphertext(add);
+
+        assert_eq!(ir.depth_of_node(p), 0);
+        assert_eq!(ir.depth_of_node(add), 0);
+        assert_eq!(ir.multiplicative_depth(), 1);
+
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let neg = ir.add_negate(a);
+        ir.add_output_ciphertext(neg);
+
+        assert_eq!(ir.multiplicative_depth(), 0);
+    }
+
+    #[test]
+    fn critical_path_of_linear_chain() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let mul_1 = ir.add_multiply(a, a);
+        let add = ir.add_add(mul_1, a);
+        let mul_2 = ir.add_multiply(add, a);
+        let out = ir.add_output_ciphertext(mul_2);
+
+        assert_eq!(ir.critical_path(), vec![a, mul_1, add, mul_2, out]);
+        assert_eq!(ir.critical_path_depth(), 2);
+    }
+
+    #[test]
+    fn critical_path_of_diamond() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let neg = ir.add_negate(a);
+        let mul_1 = ir.add_multiply(a, b);
+        let mul_2 = ir.add_multiply(mul_1, b);
+        let join = ir.add_add(neg, mul_2);
+        let out = ir.add_output_ciphertext(join);
+
+        assert_eq!(ir.critical_path(), vec![a, mul_1, mul_2, join, out]);
+        assert_eq!(ir.critical_path_depth(), 2);
+        assert_eq!(ir.critical_path_depth(), ir.multiplicative_depth());
+    }
+
+    #[test]
+    fn critical_path_skips_plaintext_operands() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        // The plaintext has the lower index, but the path should follow
+        // the ciphertext.
+        let p = ir.add_input_plaintext(0);
+        let a = ir.add_input_ciphertext(1);
+        let mul_plain = ir.add_multiply_plaintext(a, p);
+        let neg = ir.add_negate(mul_plain);
+        let shallow = ir.add_output_ciphertext(a);
+        let deep = ir.add_output_ciphertext(neg);
+
+        assert_eq!(ir.critical_path(), vec![a, mul_plain, neg, deep]);
+        assert!(!ir.critical_path().contains(&shallow));
+        assert_eq!(ir.critical_path_depth(), 1);
+
+        assert!(FheProgram::new(SchemeType::Bfv).critical_path().is_empty());
+    }
+
+    fn build_program(reversed: bool) -> FheProgram {
+        // (a * b) + -c, with nodes added in different orders.
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let (a, b, c) = if reversed {
+            let c = ir.add_input_ciphertext(2);
+            let b = ir.add_input_ciphertext(1);
+            let a = ir.add_input_ciphertext(0);
+            (a, b, c)
+        } else {
+            let a = ir.add_input_ciphertext(0);
+            let b = ir.add_input_ciphertext(1);
+            let c = ir.add_input_ciphertext(2);
+            (a, b, c)
+        };
+
+        let (mul, neg) = if reversed {
+            let neg = ir.add_negate(c);
+            let mul = ir.add_multiply(a, b);
+            (mul, neg)
+        } else {
+            let mul = ir.add_multiply(a, b);
+            let neg = ir.add_negate(c);
+            (mul, neg)
+        };
+
+        let add = ir.add_add(mul, neg);
+        ir.add_output_ciphertext(add);
+
+        ir
+    }
+
+    fn identical(a: &FheProgram, b: &FheProgram) -> bool {
+        let edges = |p: &FheProgram| {
+            p.graph
+                .edge_references()
+                .map(|e| (e.source(), e.target(), *e.weight()))
+                .collect::<Vec<_>>()
+        };
+
+        a.graph.node_weights().eq(b.graph.node_weights()) && edges(a) == edges(b)
+    }
+
+    #[test]
+    fn alpha_renamed_isomorphic_programs_are_identical() {
+        let a = build_program(false);
+        let b = build_program(true);
+
+        assert!(!identical(&a, &b));
+        assert!(eq(&a, &b));
+
+        let a = a.alpha_rename();
+        let b = b.alpha_rename();
+
+        assert!(identical(&a, &b));
+        assert!(identical(&a, &a.alpha_rename()));
+        a.validate().unwrap();
+    }
+
+    #[test]
+    fn deterministic_hash_distinguishes_programs() {
+        let a = build_program(false);
+        let b = build_program(true);
+
+        assert_eq!(a.deterministic_hash(), b.deterministic_hash());
+
+        let mut c = build_program(false);
+        let input = c.add_input_ciphertext(3);
+        c.add_output_ciphertext(input);
+
+        assert_ne!(a.deterministic_hash(), c.deterministic_hash());
+    }
+
+    #[test]
+    fn structurally_eq_ignores_node_indices() {
+        let a = build_program(false);
+        let b = build_program(true);
+
+        assert!(a.structurally_eq(&b));
+        assert!(b.structurally_eq(&a));
+
+        let mut c = build_program(false);
+        let input = c.add_input_ciphertext(3);
+        c.add_output_ciphertext(input);
+
+        assert!(!a.structurally_eq(&c));
+
+        let mut d = build_program(false);
+        d.data = SchemeType::Bgv;
+
+        assert!(!a.structurally_eq(&d));
+    }
+
     #[test]
     fn can_roundtrip_scheme_type() {
-        let schemes = [SchemeType::Bfv];
+        let schemes = [SchemeType::Bfv, SchemeType::Ckks, SchemeType::Bgv];
         for s in schemes {
             let s_2: u8 = s.into();
             let s_2 = SchemeType::try_from(s_2).unwrap();
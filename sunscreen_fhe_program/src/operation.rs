@@ -35,6 +35,15 @@ pub enum Operation {
      */
     Relinearize,
 
+    /**
+     * In leveled schemes (i.e. BGV), switches a ciphertext to the next modulus in the
+     * coefficient modulus chain, dropping a prime and scaling its noise down with it.
+     *
+     * Sunscreen inserts these after each multiplication and wherever two operands at
+     * different levels are combined.
+     */
+    ModSwitch,
+
     /**
      * Multiply two ciphertext values.
      */
@@ -122,7 +131,11 @@ impl OperationTrait for Operation {
     fn is_unary(&self) -> bool {
         matches!(
             self,
-            Self::Negate | Self::Relinearize | Self::SwapRows | Self::OutputCiphertext
+            Self::Negate
+                | Self::Relinearize
+                | Self::ModSwitch
+                | Self::SwapRows
+                | Self::OutputCiphertext
         )
     }
 
@@ -133,4 +146,9 @@ impl OperationTrait for Operation {
     fn is_ordered(&self) -> bool {
         false
     }
+
+    fn is_mergeable(&self) -> bool {
+        // Each output is a distinct return value.
+        !matches!(self, Self::OutputCiphertext)
+    }
 }
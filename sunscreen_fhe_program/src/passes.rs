@@ -0,0 +1,77 @@
+//! Analyses over an [`FheProgram`](crate::FheProgram)'s graph.
+
+use std::collections::HashSet;
+
+use petgraph::{stable_graph::StableGraph, visit::EdgeRef, Direction};
+use sunscreen_compiler_common::{EdgeInfo, NodeInfo};
+
+use crate::{Literal, Operation};
+
+/**
+ * Collects the rotation steps of every slot rotation in `graph`, in
+ * the form SEAL expects when generating Galois keys: positive steps
+ * rotate rows left, negative steps rotate them right, and 0 swaps
+ * rows.
+ *
+ * # Remarks
+ * Rotations with a malformed amount operand are skipped; validating
+ * the program reports these.
+ */
+pub fn collect_rotations(graph: &StableGraph<NodeInfo<Operation>, EdgeInfo>) -> HashSet<i32> {
+    graph
+        .node_indices()
+        .filter_map(|id| {
+            let amount = || {
+                graph
+                    .edges_directed(id, Direction::Incoming)
+                    .find(|e| matches!(e.weight(), EdgeInfo::Right))
+                    .and_then(|e| match graph[e.source()].operation {
+                        Operation::Literal(Literal::U64(v)) => Some(v as i32),
+                        _ => None,
+                    })
+            };
+
+            match graph[id].operation {
+                Operation::ShiftLeft => amount(),
+                Operation::ShiftRight => amount().map(|v| -v),
+                Operation::SwapRows => Some(0),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FheProgram, FheProgramTrait, SchemeType};
+
+    #[test]
+    fn collects_each_rotation_once() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let one = ir.add_input_literal(Literal::U64(1));
+        let three = ir.add_input_literal(Literal::U64(3));
+
+        let l_1 = ir.add_rotate_left(a, one);
+        let l_2 = ir.add_rotate_left(l_1, one);
+        let r = ir.append_rotate_right(l_2, three);
+        let s = ir.add_unary_operation(Operation::SwapRows, r);
+        ir.add_output_ciphertext(s);
+
+        assert_eq!(collect_rotations(&ir.graph.0), HashSet::from([1, -3, 0]));
+        assert_eq!(ir.required_galois_steps(), vec![-3, 0, 1]);
+    }
+
+    #[test]
+    fn programs_without_rotations_need_no_steps() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_multiply(a, a);
+        ir.add_output_ciphertext(b);
+
+        assert!(ir.required_galois_steps().is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, FheProgram, Result};
+
+/**
+ * The version of the format [`FheProgramTrait::to_bincode`](crate::FheProgramTrait::to_bincode)
+ * and [`FheProgramTrait::to_json`](crate::FheProgramTrait::to_json)
+ * write. Sunscreen's saved applications carry it too. Bump this
+ * whenever the serialized form of [`FheProgram`] or of the metadata
+ * applications store alongside it changes.
+ */
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    version: u32,
+    program: &'a FheProgram,
+}
+
+#[derive(Deserialize)]
+struct OwnedEnvelope {
+    version: u32,
+    program: FheProgram,
+}
+
+/**
+ * Just the leading version field of an envelope, so a payload from
+ * another format version is rejected before attempting to parse it.
+ */
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+fn check_version(version: u32) -> Result<()> {
+    if version != FORMAT_VERSION {
+        return Err(Error::UnsupportedFormatVersion(version));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn to_bincode(program: &FheProgram) -> Result<Vec<u8>> {
+    bincode::serialize(&Envelope {
+        version: FORMAT_VERSION,
+        program,
+    })
+    .map_err(|e| Error::serialization_error(&e.to_string()))
+}
+
+pub(crate) fn from_bincode(data: &[u8]) -> Result<FheProgram> {
+    // bincode encodes fields in order, so the version comes first.
+    let version: Version =
+        bincode::deserialize(data).map_err(|e| Error::serialization_error(&e.to_string()))?;
+
+    check_version(version.version)?;
+
+    let envelope: OwnedEnvelope =
+        bincode::deserialize(data).map_err(|e| Error::serialization_error(&e.to_string()))?;
+
+    Ok(envelope.program)
+}
+
+pub(crate) fn to_json(program: &FheProgram) -> Result<String> {
+    serde_json::to_string(&Envelope {
+        version: FORMAT_VERSION,
+        program,
+    })
+    .map_err(|e| Error::serialization_error(&e.to_string()))
+}
+
+pub(crate) fn from_json(data: &str) -> Result<FheProgram> {
+    let version: Version =
+        serde_json::from_str(data).map_err(|e| Error::serialization_error(&e.to_string()))?;
+
+    check_version(version.version)?;
+
+    let envelope: OwnedEnvelope =
+        serde_json::from_str(data).map_err(|e| Error::serialization_error(&e.to_string()))?;
+
+    Ok(envelope.program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FheProgramTrait, SchemeType};
+
+    fn program() -> FheProgram {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let c = ir.add_add(a, b);
+        ir.add_output_ciphertext(c);
+
+        ir
+    }
+
+    #[test]
+    fn can_roundtrip_bincode() {
+        let ir = program();
+
+        let data = ir.to_bincode().unwrap();
+
+        assert!(ir.structurally_eq(&FheProgram::from_bincode(&data).unwrap()));
+    }
+
+    #[test]
+    fn can_roundtrip_json() {
+        let ir = program();
+
+        let data = ir.to_json().unwrap();
+
+        assert!(ir.structurally_eq(&FheProgram::from_json(&data).unwrap()));
+    }
+
+    #[test]
+    fn rejects_other_format_versions() {
+        let mut data = program().to_bincode().unwrap();
+        data[0..4].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+
+        assert_eq!(
+            FheProgram::from_bincode(&data).unwrap_err(),
+            Error::UnsupportedFormatVersion(FORMAT_VERSION + 1)
+        );
+
+        let json = program().to_json().unwrap().replacen(
+            &format!("\"version\":{}", FORMAT_VERSION),
+            &format!("\"version\":{}", FORMAT_VERSION + 1),
+            1,
+        );
+
+        assert_eq!(
+            FheProgram::from_json(&json).unwrap_err(),
+            Error::UnsupportedFormatVersion(FORMAT_VERSION + 1)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_data() {
+        assert!(matches!(
+            FheProgram::from_bincode(&[1, 0, 0, 0, 42]),
+            Err(Error::SerializationError(_))
+        ));
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Operation;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+/**
+ * The number of each kind of homomorphic operation in an
+ * [`FheProgram`](crate::FheProgram). See
+ * [`operation_count`](crate::FheProgramTrait::operation_count).
+ */
+pub struct OperationStats {
+    /**
+     * Ciphertext-ciphertext additions and subtractions.
+     */
+    pub additions: usize,
+
+    /**
+     * Ciphertext-plaintext additions and subtractions.
+     */
+    pub plaintext_additions: usize,
+
+    /**
+     * Ciphertext-ciphertext multiplications.
+     */
+    pub multiplications: usize,
+
+    /**
+     * Ciphertext-plaintext multiplications.
+     */
+    pub plaintext_multiplications: usize,
+
+    /**
+     * Negations.
+     */
+    pub negations: usize,
+
+    /**
+     * Relinearizations.
+     */
+    pub relinearizations: usize,
+
+    /**
+     * Rotations and row swaps.
+     */
+    pub rotations: usize,
+}
+
+impl OperationStats {
+    // The approximate cost of each operation relative to a
+    // ciphertext-ciphertext addition under SEAL's BFV implementation.
+    const ADDITION_COST: f64 = 1.0;
+    const PLAINTEXT_ADDITION_COST: f64 = 1.0;
+    const MULTIPLICATION_COST: f64 = 60.0;
+    const PLAINTEXT_MULTIPLICATION_COST: f64 = 10.0;
+    const NEGATION_COST: f64 = 0.5;
+    const RELINEARIZATION_COST: f64 = 40.0;
+    const ROTATION_COST: f64 = 40.0;
+
+    pub(crate) fn record(&mut self, op: &Operation) {
+        match op {
+            Operation::Add | Operation::Sub => self.additions += 1,
+            Operation::AddPlaintext | Operation::SubPlaintext => self.plaintext_additions += 1,
+            Operation::Multiply => self.multiplications += 1,
+            Operation::MultiplyPlaintext => self.plaintext_multiplications += 1,
+            Operation::Negate => self.negations += 1,
+            Operation::Relinearize => self.relinearizations += 1,
+            Operation::ShiftLeft | Operation::ShiftRight | Operation::SwapRows => {
+                self.rotations += 1
+            }
+            _ => {}
+        }
+    }
+
+    /**
+     * Estimates the program's execution cost in units of one
+     * ciphertext-ciphertext addition.
+     *
+     * # Remarks
+     * The weights are rough and ignore the parameters, so use this to
+     * compare variants of a program rather than to predict run time.
+     */
+    pub fn total_cost(&self) -> f64 {
+        self.additions as f64 * Self::ADDITION_COST
+            + self.plaintext_additions as f64 * Self::PLAINTEXT_ADDITION_COST
+            + self.multiplications as f64 * Self::MULTIPLICATION_COST
+            + self.plaintext_multiplications as f64 * Self::PLAINTEXT_MULTIPLICATION_COST
+            + self.negations as f64 * Self::NEGATION_COST
+            + self.relinearizations as f64 * Self::RELINEARIZATION_COST
+            + self.rotations as f64 * Self::ROTATION_COST
+    }
+}
@@ -66,12 +66,54 @@ pub enum Error {
     #[error("Data returned from FHE program doesn't match return signature")]
     ReturnTypeMetadataError,
 
+    /**
+     * The runtime requires authenticated inputs, but a ciphertext
+     * argument lacked a valid authentication tag. See
+     * [`require_authenticated_inputs`](crate::GenericRuntime::require_authenticated_inputs).
+     */
+    #[error("Ciphertext was not produced by this runtime")]
+    UnauthenticatedCiphertext,
+
     /**
      * Decryption failed because the cipher text had too much noise.
      */
     #[error("Too much noise")]
     TooMuchNoise,
 
+    /**
+     * Decoding a value detected noise corrupted it, e.g. its checksum
+     * didn't match.
+     */
+    #[error("The decoded value is corrupted")]
+    CorruptedResult,
+
+    /**
+     * Decoding a value detected it overflowed the plaintext modulus. See
+     * [`decrypt_checked`](crate::GenericRuntime::decrypt_checked).
+     */
+    #[error("The decoded value overflowed the plaintext modulus (decoded as {recovered_value})")]
+    OverflowDetected {
+        /**
+         * The value decoding would otherwise have returned.
+         */
+        recovered_value: i64,
+    },
+
+    /**
+     * [`run_checked`](crate::GenericRuntime::run_checked) estimated the
+     * FHE program would exhaust its noise budget, so its outputs
+     * wouldn't decrypt correctly.
+     */
+    #[error("The FHE program would exhaust its noise budget")]
+    InsufficientNoiseBudget,
+
+    /**
+     * The analytic noise model only covers the BFV scheme, so it can't
+     * estimate noise under the given scheme.
+     */
+    #[error("Noise estimation doesn't support the {0:?} scheme")]
+    UnsupportedNoiseEstimate(sunscreen_fhe_program::SchemeType),
+
     /**
      * Executing an Fhe Program failed.
      */
@@ -103,6 +145,21 @@ pub enum Error {
     #[error("Plaintext is malformed")]
     MalformedPlaintext,
 
+    /**
+     * The given [`Plaintext`](crate::Plaintext) has more coefficients
+     * (first argument) than the maximum (second argument), the lattice
+     * dimension of the parameters decoding it.
+     */
+    #[error("Plaintext has {} coefficients, exceeding the maximum of {}", self.unwrap_plaintext_too_large_data().0, self.unwrap_plaintext_too_large_data().1)]
+    PlaintextTooLarge(Box<(usize, usize)>),
+
+    /**
+     * The decoded value doesn't fit in the type being decoded, so
+     * decoding would otherwise have silently wrapped it.
+     */
+    #[error("Decoded value is out of range: {0}")]
+    ValueOutOfRange(Box<String>),
+
     /**
      * An error occurred when serializing/deserializing with bincode.
      */
@@ -116,6 +173,14 @@ pub enum Error {
     #[error("Not a SEAL plaintext")]
     NotASealPlaintext,
 
+    /**
+     * A task spawned by an async method, such as
+     * [`run_async`](crate::GenericRuntime::run_async), panicked or was
+     * cancelled.
+     */
+    #[error("Async task failed: {0}")]
+    AsyncTaskFailed(Box<String>),
+
     /**
      * An error occurred when creating or verifying a proof.
      */
@@ -153,6 +218,27 @@ impl Error {
         Self::FheTypeError(Box::new(msg.to_owned()))
     }
 
+    /**
+     * Create an [`Error::PlaintextTooLarge`].
+     */
+    pub fn plaintext_too_large(len: usize, max: usize) -> Self {
+        Self::PlaintextTooLarge(Box::new((len, max)))
+    }
+
+    /**
+     * Create an [`Error::ValueOutOfRange`].
+     */
+    pub fn value_out_of_range(msg: &str) -> Self {
+        Self::ValueOutOfRange(Box::new(msg.to_owned()))
+    }
+
+    /**
+     * Create an [`Error::AsyncTaskFailed`].
+     */
+    pub fn async_task_failed(msg: &str) -> Self {
+        Self::AsyncTaskFailed(Box::new(msg.to_owned()))
+    }
+
     /**
      * Create an [`Error::ZkpBuilderError`].
      */
@@ -167,6 +253,13 @@ impl Error {
         }
     }
 
+    fn unwrap_plaintext_too_large_data(&self) -> &(usize, usize) {
+        match self {
+            Self::PlaintextTooLarge(d) => d,
+            _ => panic!("Not a plaintext too large error"),
+        }
+    }
+
     fn unwrap_type_mismatch_data(&self) -> &(Type, Type) {
         match self {
             Self::TypeMismatch(d) => d,
@@ -1,4 +1,7 @@
-use crate::serialization::WithContext;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::{serialization::WithContext, Params, Result};
 
 use seal_fhe::{
     GaloisKeys, PublicKey as SealPublicKey, RelinearizationKeys, SecretKey as SealSecretKey,
@@ -52,6 +55,152 @@ pub struct PublicKey {
  */
 pub struct PrivateKey(pub(crate) WithContext<SealSecretKey>);
 
+impl PublicKey {
+    /**
+     * Serializes the encryption key in SEAL's native format, suitable
+     * for sending to another party.
+     *
+     * # Remarks
+     * Only [`public_key`](Self::public_key) is serialized. Use
+     * [`WithContext::to_bytes`] on [`galois_key`](Self::galois_key) and
+     * [`relin_key`](Self::relin_key) to send those separately.
+     */
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.public_key.to_bytes()
+    }
+
+    /**
+     * Deserializes an encryption key produced by
+     * [`to_bytes`](Self::to_bytes) under the given parameters.
+     *
+     * # Remarks
+     * The returned key has no Galois or relinearization keys.
+     */
+    pub fn from_bytes(bytes: &[u8], params: &Params) -> Result<Self> {
+        Ok(Self {
+            public_key: WithContext::from_bytes(bytes, params)?,
+            galois_key: None,
+            relin_key: None,
+        })
+    }
+}
+
+impl PrivateKey {
+    /**
+     * Serializes this key in SEAL's native format.
+     */
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.0.to_bytes()
+    }
+
+    /**
+     * Deserializes a key produced by [`to_bytes`](Self::to_bytes) under
+     * the given parameters.
+     */
+    pub fn from_bytes(bytes: &[u8], params: &Params) -> Result<Self> {
+        Ok(Self(WithContext::from_bytes(bytes, params)?))
+    }
+}
+
+#[derive(Clone, Default)]
+/**
+ * A cache of generated keys, keyed by the parameters they were
+ * generated under.
+ *
+ * # Remarks
+ * Key generation is expensive. Services that serve many requests under
+ * the same parameters can pass one `KeyCache` to
+ * [`generate_or_cache_keys`](crate::GenericRuntime::generate_or_cache_keys)
+ * to generate each parameter set's keys only once.
+ *
+ * `KeyCache` is `Send` and `Sync`, and clones share the same underlying
+ * cache, so one cache may serve several threads. Every user of a cache
+ * receives the same private key, so only share a cache between
+ * computations that belong to the same key owner.
+ */
+pub struct KeyCache {
+    inner: Arc<Mutex<HashMap<Params, KeySlot>>>,
+}
+
+/**
+ * The keys for one parameter set, filled in by whichever caller
+ * generates them first.
+ */
+type KeySlot = Arc<Mutex<Option<(PublicKey, PrivateKey)>>>;
+
+impl KeyCache {
+    /**
+     * Creates an empty cache.
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * The number of parameter sets with cached keys.
+     */
+    pub fn len(&self) -> usize {
+        // A slot we can't lock is still generating its keys.
+        self.inner
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|slot| matches!(slot.try_lock().as_deref(), Ok(Some(_))))
+            .count()
+    }
+
+    /**
+     * Whether the cache contains no keys.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+     * Removes every entry.
+     */
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().clear();
+    }
+
+    /**
+     * Returns the keys for `params`, calling `generate` to create them
+     * on a miss.
+     *
+     * # Remarks
+     * Only the entry for `params` stays locked during generation, so
+     * concurrent callers never generate keys for the same params twice
+     * but don't wait on each other for different params.
+     */
+    pub(crate) fn get_or_try_insert_with<F>(
+        &self,
+        params: &Params,
+        generate: F,
+    ) -> Result<(PublicKey, PrivateKey)>
+    where
+        F: FnOnce() -> Result<(PublicKey, PrivateKey)>,
+    {
+        let slot = self
+            .inner
+            .lock()
+            .unwrap()
+            .entry(params.clone())
+            .or_default()
+            .clone();
+
+        let mut slot = slot.lock().unwrap();
+
+        if let Some(keys) = &*slot {
+            return Ok(keys.clone());
+        }
+
+        let keys = generate()?;
+        *slot = Some(keys.clone());
+
+        Ok(keys)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,4 +337,133 @@ mod tests {
         );
         assert_eq!(private_key.0.as_bytes(), private_2.0.as_bytes());
     }
+
+    #[test]
+    fn json_key_payload_is_base64() {
+        let runtime = Runtime::new_fhe(&get_params()).unwrap();
+
+        let (_, private_key) = runtime.generate_keys().unwrap();
+
+        let json = serde_json::to_value(&private_key).unwrap();
+
+        assert!(json["data"].is_string());
+
+        // The older array-of-bytes encoding still deserializes.
+        let mut legacy = json.clone();
+        legacy["data"] = serde_json::to_value(private_key.to_bytes().unwrap()).unwrap();
+
+        let private_2: PrivateKey = serde_json::from_value(json).unwrap();
+        let private_3: PrivateKey = serde_json::from_value(legacy).unwrap();
+
+        assert_eq!(private_key.0.as_bytes(), private_2.0.as_bytes());
+        assert_eq!(private_key.0.as_bytes(), private_3.0.as_bytes());
+    }
+
+    fn get_params() -> Params {
+        Params {
+            lattice_dimension: 8192,
+            security_level: SecurityLevel::TC128,
+            plain_modulus: PlainModulus::batching(8192, 20).unwrap().value(),
+            scheme_type: SchemeType::Bfv,
+            coeff_modulus: CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|x| x.value())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn can_roundtrip_key_bytes() {
+        let params = get_params();
+        let runtime = Runtime::new_fhe(&params).unwrap();
+
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        let public_2 = PublicKey::from_bytes(&public_key.to_bytes().unwrap(), &params).unwrap();
+        let private_2 = PrivateKey::from_bytes(&private_key.to_bytes().unwrap(), &params).unwrap();
+
+        let relin_key = public_key.relin_key.as_ref().unwrap();
+        let relin_2 =
+            WithContext::<RelinearizationKeys>::from_bytes(&relin_key.to_bytes().unwrap(), &params)
+                .unwrap();
+
+        assert_eq!(
+            public_key.public_key.data.as_bytes(),
+            public_2.public_key.data.as_bytes()
+        );
+        assert!(public_2.galois_key.is_none());
+        assert!(public_2.relin_key.is_none());
+        assert_eq!(private_key.0.as_bytes(), private_2.0.as_bytes());
+        assert_eq!(relin_key.data.as_bytes(), relin_2.data.as_bytes());
+    }
+
+    #[test]
+    fn key_cache_returns_cached_keys() {
+        let params = |plain_modulus| Params {
+            lattice_dimension: 4096,
+            security_level: SecurityLevel::TC128,
+            plain_modulus,
+            scheme_type: SchemeType::Bfv,
+            coeff_modulus: CoefficientModulus::bfv_default(4096, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|x| x.value())
+                .collect(),
+        };
+
+        let cache = KeyCache::new();
+
+        let runtime = Runtime::new_fhe(&params(1024)).unwrap();
+
+        let (public_1, private_1) = runtime.generate_or_cache_keys(&cache).unwrap();
+        let (public_2, private_2) = runtime.generate_or_cache_keys(&cache).unwrap();
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(public_1.to_bytes().unwrap(), public_2.to_bytes().unwrap());
+        assert_eq!(private_1.to_bytes().unwrap(), private_2.to_bytes().unwrap());
+
+        let runtime = Runtime::new_fhe(&params(2048)).unwrap();
+
+        let (public_3, _) = runtime.generate_or_cache_keys(&cache).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert_ne!(public_1.to_bytes().unwrap(), public_3.to_bytes().unwrap());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn key_cache_generates_different_params_independently() {
+        let params = |plain_modulus| Params {
+            lattice_dimension: 4096,
+            security_level: SecurityLevel::TC128,
+            plain_modulus,
+            scheme_type: SchemeType::Bfv,
+            coeff_modulus: CoefficientModulus::bfv_default(4096, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|x| x.value())
+                .collect(),
+        };
+
+        let cache = KeyCache::new();
+
+        let runtime_1 = Runtime::new_fhe(&params(1024)).unwrap();
+        let runtime_2 = Runtime::new_fhe(&params(2048)).unwrap();
+
+        // Would deadlock if generating the first keys locked the whole
+        // cache.
+        cache
+            .get_or_try_insert_with(runtime_1.params(), || {
+                runtime_2.generate_or_cache_keys(&cache)?;
+                assert_eq!(cache.len(), 1);
+
+                runtime_1.generate_keys()
+            })
+            .unwrap();
+
+        assert_eq!(cache.len(), 2);
+    }
 }
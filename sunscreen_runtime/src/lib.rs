@@ -7,6 +7,8 @@ mod array;
 mod error;
 mod keys;
 mod metadata;
+mod noise;
+mod profile;
 mod run;
 mod runtime;
 mod serialization;
@@ -22,6 +24,8 @@ use std::sync::Arc;
 pub use crate::error::*;
 pub use crate::keys::*;
 pub use crate::metadata::*;
+pub use crate::noise::{CanonicalEmbeddingNorm, NoiseReport};
+pub use crate::profile::{OperationProfile, ProfileReport};
 pub use run::*;
 pub use runtime::*;
 pub use serialization::WithContext;
@@ -98,6 +102,31 @@ impl InnerPlaintext {
             Self::Seal(d) => Ok(d),
         }
     }
+
+    /**
+     * Returns [`Error::PlaintextTooLarge`] if any of the underlying
+     * plaintexts has more coefficients than the lattice dimension of
+     * `params`.
+     *
+     * # Remarks
+     * Decoding cost grows with a plaintext's coefficient count, which
+     * comes from untrusted data when plaintexts are deserialized or
+     * built with `from_coefficients`. Types call this before decoding,
+     * so the params decoding a plaintext bound the work, independent of
+     * how many coefficients the type actually reads. No plaintext with
+     * more coefficients than the lattice dimension can be the
+     * decryption of a ciphertext under `params`.
+     */
+    pub fn check_coefficient_count(&self, params: &Params) -> Result<()> {
+        let max = params.lattice_dimension as usize;
+
+        match self {
+            Self::Seal(d) => match d.iter().find(|p| p.len() > max) {
+                Some(p) => Err(Error::plaintext_too_large(p.len(), max)),
+                None => Ok(()),
+            },
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -181,6 +210,138 @@ pub struct Ciphertext {
      * The scheme and backend-specific plaintext.
      */
     pub inner: InnerCiphertext,
+
+    /**
+     * A tag proving a runtime with
+     * [`require_authenticated_inputs`](crate::GenericRuntime::require_authenticated_inputs)
+     * enabled produced this ciphertext. `None` when such a runtime
+     * didn't produce it.
+     */
+    #[serde(default)]
+    pub authentication_tag: Option<[u8; 32]>,
+}
+
+#[derive(Deserialize, Serialize)]
+/**
+ * The wire format of [`Ciphertext::to_bytes`]. Unlike serializing a
+ * [`Ciphertext`] directly, this omits the params from each inner
+ * ciphertext.
+ */
+struct CiphertextBytes {
+    data_type: Type,
+    ciphertexts: Vec<Vec<u8>>,
+    authentication_tag: Option<[u8; 32]>,
+}
+
+impl Ciphertext {
+    /**
+     * Serializes this ciphertext into a compact binary form, suitable
+     * for sending to another party.
+     *
+     * # Remarks
+     * This function internally uses bincode for the framing and SEAL's
+     * native format for each inner ciphertext. The bytes don't include
+     * the parameters; the receiver must already know them and pass them
+     * to [`from_bytes`](Self::from_bytes).
+     */
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let InnerCiphertext::Seal(ciphertexts) = &self.inner;
+
+        let data = CiphertextBytes {
+            data_type: self.data_type.clone(),
+            ciphertexts: ciphertexts
+                .iter()
+                .map(|c| c.to_bytes())
+                .collect::<Result<Vec<_>>>()?,
+            authentication_tag: self.authentication_tag,
+        };
+
+        Ok(bincode::serialize(&data)?)
+    }
+
+    /**
+     * Returns an upper bound on the length of
+     * [`to_bytes`](Self::to_bytes)'s output, without serializing.
+     *
+     * # Remarks
+     * SEAL compresses ciphertexts as it serializes them, so the actual
+     * length is usually smaller.
+     */
+    pub fn serialized_size(&self) -> usize {
+        let InnerCiphertext::Seal(ciphertexts) = &self.inner;
+
+        let framing = CiphertextBytes {
+            data_type: self.data_type.clone(),
+            ciphertexts: vec![vec![]; ciphertexts.len()],
+            authentication_tag: self.authentication_tag,
+        };
+
+        // Serializing to a size counter can't fail.
+        let framing = bincode::serialized_size(&framing).unwrap() as usize;
+
+        framing
+            + ciphertexts
+                .iter()
+                .map(|c| c.data.save_size() as usize)
+                .sum::<usize>()
+    }
+
+    /**
+     * Deserializes a ciphertext produced by [`to_bytes`](Self::to_bytes)
+     * under the given parameters.
+     *
+     * # Remarks
+     * `params` must match the parameters the ciphertext was encrypted
+     * under.
+     */
+    pub fn from_bytes(data: &[u8], params: &Params) -> Result<Self> {
+        let data: CiphertextBytes = bincode::deserialize(data)?;
+
+        let ciphertexts = data
+            .ciphertexts
+            .iter()
+            .map(|c| WithContext::from_bytes(c, params))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            data_type: data.data_type,
+            inner: InnerCiphertext::Seal(ciphertexts),
+            authentication_tag: data.authentication_tag,
+        })
+    }
+
+    /**
+     * Wraps raw SEAL ciphertexts, e.g. ones produced by other SEAL-based
+     * code, as a ciphertext of the given type.
+     *
+     * # Remarks
+     * The caller is responsible for params consistency: each
+     * ciphertext's params must match the SEAL parameters it was actually
+     * encrypted under, and `data` must hold as many ciphertexts as
+     * `data_type` uses. Sunscreen can't check either, and decrypting or
+     * running a program on an inconsistent ciphertext produces garbage.
+     *
+     * The result has no [`authentication_tag`](Self::authentication_tag),
+     * so runtimes requiring authenticated inputs reject it.
+     */
+    pub fn from_seal(data: Vec<WithContext<SealCiphertext>>, data_type: Type) -> Self {
+        Self {
+            data_type,
+            inner: InnerCiphertext::Seal(data),
+            authentication_tag: None,
+        }
+    }
+
+    /**
+     * Unwraps the raw SEAL ciphertexts and the params they were
+     * encrypted under, discarding the data type and authentication tag.
+     * See [`from_seal`](Self::from_seal).
+     */
+    pub fn into_seal(self) -> Vec<WithContext<SealCiphertext>> {
+        match self.inner {
+            InnerCiphertext::Seal(data) => data,
+        }
+    }
 }
 
 /**
@@ -294,6 +455,19 @@ where
      * Attempts to turn a [`Plaintext`] into `Self`. On success, returns
      */
     fn try_from_plaintext(plaintext: &Plaintext, params: &Params) -> Result<Self>;
+
+    /**
+     * Like [`try_from_plaintext`](Self::try_from_plaintext), but fails
+     * if the plaintext shows signs the value overflowed the plaintext
+     * modulus. See
+     * [`decrypt_checked`](crate::GenericRuntime::decrypt_checked).
+     *
+     * # Remarks
+     * The default implementation performs no additional checks.
+     */
+    fn try_from_plaintext_checked(plaintext: &Plaintext, params: &Params) -> Result<Self> {
+        Self::try_from_plaintext(plaintext, params)
+    }
 }
 
 /**
@@ -320,6 +494,11 @@ pub trait FheType:
  */
 pub trait BfvType: FheType {}
 
+/**
+ * Denotes the given type is valid under the CKKS scheme.
+ */
+pub trait CkksType: FheType {}
+
 /**
  * A trait the gives a name an version to a given type
  */
@@ -3,7 +3,7 @@ use seal_fhe::SecurityLevel;
 pub use semver::Version;
 use serde::{Deserialize, Serialize};
 use sunscreen_compiler_common::Type;
-use sunscreen_fhe_program::{FheProgram, SchemeType};
+use sunscreen_fhe_program::{FheProgram, FheProgramTrait, OperationStats, SchemeType};
 
 use crate::{Error, Result};
 
@@ -192,6 +192,75 @@ pub struct FheProgramMetadata {
      * The set of keys required to run the FHE program.
      */
     pub required_keys: Vec<RequiredKeys>,
+
+    /**
+     * The name of each argument in the program, in the same order as
+     * [`CallSignature::arguments`].
+     *
+     * # Remarks
+     * Empty for programs serialized before argument names were
+     * recorded.
+     */
+    #[serde(default)]
+    pub argument_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/**
+ * Describes an argument of a [`CompiledFheProgram`]. See
+ * [`CompiledFheProgram::inputs`].
+ */
+pub struct ProgramInputInfo {
+    /**
+     * The argument's name in the `#[fhe_program]` function, or an
+     * empty string if the program doesn't record it.
+     */
+    pub name: String,
+
+    /**
+     * The argument's fully qualified type name (e.g.
+     * `sunscreen::types::bfv::Signed`).
+     */
+    pub type_name: String,
+
+    /**
+     * Whether the argument is a ciphertext rather than a plaintext.
+     */
+    pub is_cipher: bool,
+
+    /**
+     * The argument's position among the program's arguments.
+     */
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/**
+ * Describes a return value of a [`CompiledFheProgram`]. See
+ * [`CompiledFheProgram::outputs`].
+ */
+pub struct ProgramOutputInfo {
+    /**
+     * Return values are unnamed, so this is the value's position in
+     * the returned tuple (e.g. `"0"`).
+     */
+    pub name: String,
+
+    /**
+     * The return value's fully qualified type name (e.g.
+     * `sunscreen::types::bfv::Signed`).
+     */
+    pub type_name: String,
+
+    /**
+     * Whether the return value is a ciphertext rather than a plaintext.
+     */
+    pub is_cipher: bool,
+
+    /**
+     * The return value's position among the program's return values.
+     */
+    pub index: usize,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -211,6 +280,74 @@ pub struct CompiledFheProgram {
     pub metadata: FheProgramMetadata,
 }
 
+impl CompiledFheProgram {
+    /**
+     * Counts the homomorphic operations of each kind in this FHE
+     * program, after backend optimizations.
+     */
+    pub fn operation_count(&self) -> OperationStats {
+        self.fhe_program_fn.operation_count()
+    }
+
+    /**
+     * Renders this FHE program's graph in GraphViz DOT format, labeling
+     * each node with its operation.
+     *
+     * # Remarks
+     * Pipe the result into e.g. `dot -Tpng` to visualize the program
+     * without running the debugger.
+     */
+    pub fn to_dot(&self) -> String {
+        self.fhe_program_fn.to_dot()
+    }
+
+    /**
+     * Describes each of this program's arguments, in order.
+     *
+     * # Remarks
+     * Useful for dispatching to programs generically, e.g. checking
+     * a client's arguments before calling
+     * [`run`](crate::GenericRuntime::run).
+     */
+    pub fn inputs(&self) -> Vec<ProgramInputInfo> {
+        self.metadata
+            .signature
+            .arguments
+            .iter()
+            .enumerate()
+            .map(|(index, t)| ProgramInputInfo {
+                name: self
+                    .metadata
+                    .argument_names
+                    .get(index)
+                    .cloned()
+                    .unwrap_or_default(),
+                type_name: t.name.clone(),
+                is_cipher: t.is_encrypted,
+                index,
+            })
+            .collect()
+    }
+
+    /**
+     * Describes each of this program's return values, in order.
+     */
+    pub fn outputs(&self) -> Vec<ProgramOutputInfo> {
+        self.metadata
+            .signature
+            .returns
+            .iter()
+            .enumerate()
+            .map(|(index, t)| ProgramOutputInfo {
+                name: index.to_string(),
+                type_name: t.name.clone(),
+                is_cipher: t.is_encrypted,
+                index,
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
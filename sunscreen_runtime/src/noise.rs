@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use petgraph::{algo::toposort, Direction};
+use sunscreen_fhe_program::{FheProgram, FheProgramTrait, Operation, SchemeType};
+
+use crate::{Error, Params, Result};
+
+/**
+ * The standard deviation of the Gaussian noise SEAL introduces when
+ * encrypting.
+ */
+const NOISE_STD_DEV: f64 = 3.2;
+
+/**
+ * Rotations have no analytic bound yet, so assume each consumes this
+ * many bits of noise budget.
+ */
+const ROTATION_BUDGET_BITS: f64 = 8.;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/**
+ * An analytic estimate of an FHE program's noise growth. See
+ * [`GenericRuntime::validate`](crate::GenericRuntime::validate).
+ */
+pub struct NoiseReport {
+    /**
+     * The estimated noise budget (in bits) of a freshly encrypted
+     * ciphertext.
+     */
+    pub initial_budget: f64,
+
+    /**
+     * The estimated noise budget (in bits) remaining in the noisiest
+     * output. Never negative.
+     */
+    pub remaining_budget: f64,
+
+    /**
+     * The largest number of ciphertext multiplications along any path
+     * from an input to an output.
+     */
+    pub multiplicative_depth: usize,
+}
+
+impl NoiseReport {
+    /**
+     * Whether the estimate leaves no noise budget in some output, so
+     * the program may fail to decrypt correctly.
+     */
+    pub fn is_risky(&self) -> bool {
+        self.remaining_budget <= 0.
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/**
+ * Bounds the invariant noise of each BFV operation's result using the
+ * canonical embedding norm.
+ *
+ * # Remarks
+ * Both [`GenericRuntime::validate`](crate::GenericRuntime::validate)
+ * and the compiler's parameter search use these bounds. Operations
+ * these don't cover (additions, negation, relinearization) don't grow
+ * noise beyond their operands'.
+ */
+pub struct CanonicalEmbeddingNorm {
+    n: f64,
+    t: f64,
+    q: f64,
+
+    /**
+     * `q mod t`
+     */
+    r_t: f64,
+}
+
+impl CanonicalEmbeddingNorm {
+    /**
+     * Creates the bounds for `params`.
+     *
+     * # Remarks
+     * Returns [`Error::UnsupportedNoiseEstimate`] unless `params` use
+     * the BFV scheme, and [`Error::ParameterMismatch`] if they have no
+     * coefficient moduli or a plain modulus below 2.
+     */
+    pub fn new(params: &Params) -> Result<Self> {
+        if params.scheme_type != SchemeType::Bfv {
+            return Err(Error::UnsupportedNoiseEstimate(params.scheme_type));
+        }
+
+        if params.coeff_modulus.is_empty() || params.plain_modulus < 2 {
+            return Err(Error::ParameterMismatch);
+        }
+
+        // SEAL reserves the last modulus for key switching.
+        let moduli = || {
+            params
+                .coeff_modulus
+                .iter()
+                .take(usize::max(params.coeff_modulus.len() - 1, 1))
+        };
+
+        let t = params.plain_modulus as u128;
+
+        Ok(Self {
+            n: params.lattice_dimension as f64,
+            t: t as f64,
+            q: moduli().map(|q| *q as f64).product(),
+            r_t: moduli().fold(1, |r, q| r * (*q as u128 % t) % t) as f64,
+        })
+    }
+
+    /**
+     * The noise in a freshly encrypted ciphertext.
+     */
+    pub fn encrypt(&self) -> f64 {
+        let Self { n, t, q, .. } = *self;
+
+        (t * (n * (t - 1.) / 2.) + 2. * NOISE_STD_DEV * f64::sqrt(12. * n * n + 9. * n)) / q
+    }
+
+    /**
+     * The noise after adding a plaintext to (or subtracting one from) a
+     * ciphertext with noise `a`.
+     */
+    pub fn add_ct_pt(&self, a: f64) -> f64 {
+        a + self.r_t * self.n * self.t / self.q
+    }
+
+    /**
+     * The noise after multiplying ciphertexts with noise `a` and `b`.
+     */
+    pub fn mul_ct_ct(&self, a: f64, b: f64) -> f64 {
+        let Self { n, t, q, .. } = *self;
+
+        t * f64::sqrt(3. * n + 2. * n * n) * (a + b)
+            + 3. * a
+            + b
+            + (t / q) * f64::sqrt(3. * n + 2. * n * n + 4. / 3. * n * n * n)
+    }
+
+    /**
+     * The noise after multiplying a ciphertext with noise `a` by a
+     * plaintext.
+     */
+    pub fn mul_ct_pt(&self, a: f64) -> f64 {
+        a * self.n * (self.t - 1.)
+    }
+
+    /**
+     * The noise after rotating or swapping the rows of a ciphertext
+     * with noise `a`.
+     */
+    pub fn rotate(&self, a: f64) -> f64 {
+        a + f64::powf(2., -ROTATION_BUDGET_BITS) / 2.
+    }
+}
+
+/**
+ * Converts invariant noise into the noise budget (in bits) it leaves.
+ */
+fn noise_to_budget(noise: f64) -> f64 {
+    f64::max(-f64::log2(2. * noise), 0.)
+}
+
+/**
+ * Estimates the noise growth through `ir` under `params`.
+ *
+ * # Remarks
+ * `ir` must be valid. Fails as [`CanonicalEmbeddingNorm::new`] does.
+ */
+pub(crate) fn estimate_noise(ir: &FheProgram, params: &Params) -> Result<NoiseReport> {
+    let model = CanonicalEmbeddingNorm::new(params)?;
+
+    let mut noise: HashMap<_, f64> = HashMap::new();
+    let mut outputs = vec![];
+
+    // Valid FHE programs are DAGs, so this can't fail.
+    for id in toposort(&ir.graph.0, None).unwrap() {
+        // Plaintext operands contribute no noise of their own, so
+        // summing over operands works for every operation.
+        let operands = ir
+            .graph
+            .neighbors_directed(id, Direction::Incoming)
+            .filter_map(|p| noise.get(&p).copied())
+            .collect::<Vec<_>>();
+
+        let first = operands.first().copied().unwrap_or(0.);
+
+        let n = match ir.graph[id].operation {
+            Operation::InputCiphertext(_) => model.encrypt(),
+            Operation::InputPlaintext(_) | Operation::Literal(_) => continue,
+            Operation::Add | Operation::Sub => operands.iter().sum(),
+            Operation::AddPlaintext | Operation::SubPlaintext => model.add_ct_pt(first),
+            Operation::Multiply => {
+                model.mul_ct_ct(first, operands.get(1).copied().unwrap_or(first))
+            }
+            Operation::MultiplyPlaintext => model.mul_ct_pt(first),
+            Operation::ShiftLeft | Operation::ShiftRight | Operation::SwapRows => {
+                model.rotate(first)
+            }
+            Operation::OutputCiphertext => {
+                outputs.push(first);
+                first
+            }
+            Operation::Negate | Operation::Relinearize | Operation::ModSwitch => first,
+        };
+
+        noise.insert(id, n);
+    }
+
+    let noisiest = outputs.into_iter().fold(model.encrypt(), f64::max);
+
+    Ok(NoiseReport {
+        initial_budget: noise_to_budget(model.encrypt()),
+        remaining_budget: noise_to_budget(noisiest),
+        multiplicative_depth: ir.multiplicative_depth(),
+    })
+}
@@ -0,0 +1,224 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::time::Duration;
+
+use petgraph::{stable_graph::NodeIndex, Direction};
+use sunscreen_fhe_program::{FheProgram, Operation};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/**
+ * How long a single node of an FHE program took to run. See
+ * [`GenericRuntime::run_with_profiling`](crate::GenericRuntime::run_with_profiling).
+ */
+pub struct OperationProfile {
+    /**
+     * The node in the compiled FHE program.
+     */
+    pub node_id: NodeIndex,
+
+    /**
+     * The node's operation.
+     */
+    pub operation: Operation,
+
+    /**
+     * The wall-clock time the node's SEAL call took.
+     */
+    pub duration: Duration,
+
+    /**
+     * The largest number of multiplications along any path from an
+     * input to this node, including the node itself.
+     */
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/**
+ * Per-operation timings from a profiled run of an FHE program.
+ */
+pub struct ProfileReport {
+    /**
+     * The profile of every node that ran, in topological order. Ties
+     * between nodes that don't depend on each other break by node index.
+     */
+    pub operations: Vec<OperationProfile>,
+
+    /**
+     * For each entry in `operations`, the indices (into `operations`)
+     * of its operands.
+     */
+    parents: Vec<Vec<usize>>,
+}
+
+impl ProfileReport {
+    /**
+     * Returns the chain of dependent operations whose durations sum to
+     * the most wall-clock time, starting from an input.
+     *
+     * # Remarks
+     * Nodes run in parallel whenever their operands are ready, so this
+     * chain, rather than the sum of all durations, bounds how long the
+     * program takes to run given enough threads. Returns an empty path
+     * if nothing ran.
+     */
+    pub fn critical_path(&self) -> Vec<OperationProfile> {
+        // The longest path ending at each operation and the previous
+        // operation along it.
+        let mut longest: Vec<(Duration, Option<usize>)> = Vec::with_capacity(self.operations.len());
+
+        for (op, parents) in self.operations.iter().zip(&self.parents) {
+            let prev = parents.iter().copied().max_by_key(|p| longest[*p].0);
+            let elapsed = prev.map(|p| longest[p].0).unwrap_or_default();
+
+            longest.push((elapsed + op.duration, prev));
+        }
+
+        let mut cur = (0..longest.len()).max_by_key(|i| longest[*i].0);
+        let mut path = vec![];
+
+        while let Some(i) = cur {
+            path.push(self.operations[i].clone());
+            cur = longest[i].1;
+        }
+
+        path.reverse();
+
+        path
+    }
+}
+
+/**
+ * Assembles the report for a run of `ir` in which each node in
+ * `timings` took the given time.
+ *
+ * # Remarks
+ * `ir` must be valid. Nodes missing from `timings` are omitted.
+ */
+pub(crate) fn build_report(ir: &FheProgram, timings: &[(NodeIndex, Duration)]) -> ProfileReport {
+    let durations = timings.iter().copied().collect::<HashMap<_, _>>();
+
+    let mut remaining = ir
+        .graph
+        .node_indices()
+        .map(|n| {
+            (
+                n,
+                ir.graph.neighbors_directed(n, Direction::Incoming).count(),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    // Kahn's algorithm with a min-heap, so independent nodes always come
+    // out in the same order.
+    let mut ready = remaining
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(n, _)| Reverse(*n))
+        .collect::<BinaryHeap<_>>();
+
+    let mut depths = HashMap::new();
+    let mut positions = HashMap::new();
+    let mut report = ProfileReport::default();
+
+    while let Some(Reverse(id)) = ready.pop() {
+        let operands = ir
+            .graph
+            .neighbors_directed(id, Direction::Incoming)
+            .collect::<Vec<_>>();
+
+        let is_mul = matches!(
+            ir.graph[id].operation,
+            Operation::Multiply | Operation::MultiplyPlaintext
+        );
+
+        let depth = operands.iter().map(|p| depths[p]).max().unwrap_or(0) + usize::from(is_mul);
+
+        depths.insert(id, depth);
+
+        if let Some(duration) = durations.get(&id) {
+            positions.insert(id, report.operations.len());
+
+            report.operations.push(OperationProfile {
+                node_id: id,
+                operation: ir.graph[id].operation.clone(),
+                duration: *duration,
+                depth,
+            });
+
+            report.parents.push(
+                operands
+                    .iter()
+                    .filter_map(|p| positions.get(p).copied())
+                    .collect(),
+            );
+        }
+
+        for c in ir.graph.neighbors_directed(id, Direction::Outgoing) {
+            let count = remaining.get_mut(&c).unwrap();
+            *count -= 1;
+
+            if *count == 0 {
+                ready.push(Reverse(c));
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sunscreen_fhe_program::{FheProgramTrait, SchemeType};
+
+    fn ms(x: u64) -> Duration {
+        Duration::from_millis(x)
+    }
+
+    #[test]
+    fn critical_path_takes_slowest_branch() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let mul = ir.add_multiply(a, b);
+        let neg = ir.add_negate(a);
+        let add = ir.add_add(mul, neg);
+        let out = ir.add_output_ciphertext(add);
+
+        let report = build_report(
+            &ir,
+            &[
+                (a, ms(1)),
+                (b, ms(2)),
+                (mul, ms(10)),
+                (neg, ms(20)),
+                (add, ms(1)),
+                (out, ms(1)),
+            ],
+        );
+
+        assert_eq!(
+            report
+                .operations
+                .iter()
+                .map(|o| (o.node_id, o.depth))
+                .collect::<Vec<_>>(),
+            vec![(a, 0), (b, 0), (mul, 1), (neg, 0), (add, 1), (out, 1)]
+        );
+
+        let path = report
+            .critical_path()
+            .iter()
+            .map(|o| o.node_id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(path, vec![a, neg, add, out]);
+    }
+
+    #[test]
+    fn empty_report_has_empty_critical_path() {
+        assert!(ProfileReport::default().critical_path().is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{InnerPlaintext, SealData};
 use static_assertions::const_assert;
 use sunscreen_compiler_common::{GraphQuery, GraphQueryError};
-use sunscreen_fhe_program::{FheProgram, FheProgramTrait, Literal, Operation::*};
+use sunscreen_fhe_program::{FheProgram, FheProgramTrait, Literal, Operation::*, SchemeType};
 
 use crossbeam::atomic::AtomicCell;
 use petgraph::{stable_graph::NodeIndex, Direction};
@@ -11,7 +11,8 @@ use std::borrow::Cow;
 use std::collections::VecDeque;
 #[cfg(not(target_arch = "wasm32"))]
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use seal_fhe::{
     Ciphertext, Error as SealError, Evaluator, GaloisKeys, Plaintext, RelinearizationKeys,
@@ -103,6 +104,24 @@ pub unsafe fn run_program_unchecked<E: Evaluator + Sync + Send>(
     evaluator: &E,
     relin_keys: &Option<&RelinearizationKeys>,
     galois_keys: &Option<&GaloisKeys>,
+) -> Result<Vec<Ciphertext>, FheProgramRunFailure> {
+    run_program_timed_unchecked(ir, inputs, evaluator, relin_keys, galois_keys, None)
+}
+
+/**
+ * Like [`run_program_unchecked`], but when `timings` is given, records
+ * how long each node took to run.
+ *
+ * # Safety
+ * See [`run_program_unchecked`].
+ */
+pub(crate) unsafe fn run_program_timed_unchecked<E: Evaluator + Sync + Send>(
+    ir: &FheProgram,
+    inputs: &[SealData],
+    evaluator: &E,
+    relin_keys: &Option<&RelinearizationKeys>,
+    galois_keys: &Option<&GaloisKeys>,
+    timings: Option<&Mutex<Vec<(NodeIndex, Duration)>>>,
 ) -> Result<Vec<Ciphertext>, FheProgramRunFailure> {
     fn get_data(
         data: &[AtomicCell<Option<Arc<SealData>>>],
@@ -160,6 +179,8 @@ pub unsafe fn run_program_unchecked<E: Evaluator + Sync + Send>(
     traverse(
         ir,
         |index| {
+            let start = Instant::now();
+
             let node = &ir.graph[index];
             let query = GraphQuery::new(&ir.graph.0);
 
@@ -242,6 +263,13 @@ pub unsafe fn run_program_unchecked<E: Evaluator + Sync + Send>(
 
                     let c = evaluator.multiply(a, b)?;
 
+                    // Keep the product's scale near the encoding scale.
+                    // BGV's modulus switches are separate operations.
+                    let c = match ir.data {
+                        SchemeType::Bfv | SchemeType::Bgv => c,
+                        SchemeType::Ckks => evaluator.rescale_to_next(&c)?,
+                    };
+
                     data[index.index()].store(Some(Arc::new(c.into())));
                 }
                 MultiplyPlaintext => {
@@ -280,6 +308,15 @@ pub unsafe fn run_program_unchecked<E: Evaluator + Sync + Send>(
 
                     data[index.index()].store(Some(Arc::new(c.into())));
                 }
+                ModSwitch => {
+                    let input = query.get_unary_operand(index)?;
+
+                    let a = get_ciphertext(&data, input.index())?;
+
+                    let c = evaluator.mod_switch_to_next(a)?;
+
+                    data[index.index()].store(Some(Arc::new(c.into())));
+                }
                 Negate => {
                     let x_id = query.get_unary_operand(index)?;
 
@@ -335,6 +372,10 @@ pub unsafe fn run_program_unchecked<E: Evaluator + Sync + Send>(
                 }
             };
 
+            if let Some(timings) = timings {
+                timings.lock().unwrap().push((index, start.elapsed()));
+            }
+
             Ok(())
         },
         None,
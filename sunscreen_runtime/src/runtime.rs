@@ -1,24 +1,36 @@
+#[cfg(feature = "async")]
+use std::future::Future;
 use std::marker::PhantomData;
-use std::time::Instant;
+#[cfg(feature = "async")]
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use merlin::Transcript;
 
 use crate::error::*;
 use crate::metadata::*;
+use crate::noise::{estimate_noise, NoiseReport};
+use crate::profile::{build_report, ProfileReport};
+use crate::run::run_program_timed_unchecked;
 use crate::ZkpProgramInput;
 use crate::{
     run_program_unchecked, serialization::WithContext, Ciphertext, FheProgramInput,
-    InnerCiphertext, InnerPlaintext, Plaintext, PrivateKey, PublicKey, SealCiphertext, SealData,
-    SealPlaintext, TryFromPlaintext, TryIntoPlaintext, TypeNameInstance,
+    InnerCiphertext, InnerPlaintext, KeyCache, NumCiphertexts, Plaintext, PrivateKey, PublicKey,
+    SealCiphertext, SealData, SealPlaintext, TryFromPlaintext, TryIntoPlaintext, TypeNameInstance,
 };
 
 use log::trace;
+use petgraph::stable_graph::NodeIndex;
+use rayon::prelude::*;
 use sunscreen_fhe_program::FheProgramTrait;
+use sunscreen_fhe_program::Operation;
 use sunscreen_fhe_program::SchemeType;
 
 use seal_fhe::{
-    BFVEvaluator, BfvEncryptionParametersBuilder, Context as SealContext, Decryptor, Encryptor,
-    KeyGenerator, Modulus, PolynomialArray,
+    BFVEvaluator, BfvEncryptionParametersBuilder, CkksEncryptionParametersBuilder,
+    Context as SealContext, Decryptor, Encryptor, GaloisKeys, KeyGenerator, Modulus,
+    PolynomialArray,
 };
 
 pub use sunscreen_compiler_common::{Type, TypeName};
@@ -67,6 +79,67 @@ enum Context {
     Seal(SealContext),
 }
 
+/**
+ * An FHE program validated and ready to run on the runtime that
+ * prepared it. See [`GenericRuntime::prepare`].
+ */
+pub struct ExecutionPlan<'p> {
+    fhe_program: &'p CompiledFheProgram,
+    params: Params,
+    evaluator: BFVEvaluator,
+}
+
+impl<'p> ExecutionPlan<'p> {
+    /**
+     * The program this plan runs.
+     */
+    pub fn fhe_program(&self) -> &'p CompiledFheProgram {
+        self.fhe_program
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * A prediction of how much noise budget an FHE program consumes. See
+ * [`GenericRuntime::estimate_noise_budget`].
+ */
+pub struct NoiseBudgetEstimate {
+    /**
+     * The noise budget (in bits) of a freshly encrypted ciphertext.
+     */
+    pub initial_budget: u32,
+
+    /**
+     * The noise budget (in bits) the program consumed.
+     */
+    pub consumed: u32,
+
+    /**
+     * The smallest noise budget (in bits) remaining in any of the
+     * program's outputs.
+     */
+    pub remaining: u32,
+
+    /**
+     * Whether every output retains a nonzero noise budget and so
+     * should decrypt correctly.
+     */
+    pub will_decrypt_correctly: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Diagnostics about a decrypted value. See
+ * [`GenericRuntime::decrypt_with_stats`].
+ */
+pub struct DecryptionStats {
+    /**
+     * The number of nonzero coefficients across all of the value's
+     * decrypted plaintext polynomials.
+     */
+    pub significant_coefficients: usize,
+}
+
 /**
  * Marker traits.
  */
@@ -180,6 +253,31 @@ pub struct GenericRuntime<T, B> {
     runtime_data: RuntimeData,
     _phantom_t: PhantomData<T>,
     zkp_backend: B,
+    authentication_key: Option<[u8; 32]>,
+}
+
+/**
+ * Computes the tag that marks `ciphertext` as produced by a runtime
+ * holding `key`.
+ */
+fn authentication_tag(key: &[u8; 32], ciphertext: &Ciphertext) -> Result<[u8; 32]> {
+    let mut transcript = Transcript::new(b"sunscreen-ciphertext-authentication");
+
+    transcript.append_message(b"key", key);
+    transcript.append_message(b"type", &bincode::serialize(&ciphertext.data_type)?);
+
+    match &ciphertext.inner {
+        InnerCiphertext::Seal(c) => {
+            for c in c {
+                transcript.append_message(b"ciphertext", &c.to_bytes()?);
+            }
+        }
+    }
+
+    let mut tag = [0u8; 32];
+    transcript.challenge_bytes(b"tag", &mut tag);
+
+    Ok(tag)
 }
 
 impl<T, B> GenericRuntime<T, B>
@@ -192,54 +290,202 @@ where
     pub fn decrypt<P>(&self, ciphertext: &Ciphertext, private_key: &PrivateKey) -> Result<P>
     where
         P: TryFromPlaintext + TypeName,
+    {
+        self.decrypt_many(std::slice::from_ref(ciphertext), private_key)
+            .map(|mut vals| vals.remove(0))
+    }
+
+    /**
+     * Decrypts each of the given ciphertexts into the type P.
+     *
+     * # Remarks
+     * The decryptor is created once for the whole batch and the
+     * ciphertexts decrypt in parallel on rayon's thread pool, so this
+     * is faster than calling [`decrypt`](Self::decrypt) in a loop.
+     * Returns the first error (in input order) any ciphertext
+     * produces.
+     */
+    pub fn decrypt_many<P>(
+        &self,
+        ciphertexts: &[Ciphertext],
+        private_key: &PrivateKey,
+    ) -> Result<Vec<P>>
+    where
+        P: TryFromPlaintext + TypeName,
+    {
+        self.decrypt_internal(ciphertexts, private_key, P::try_from_plaintext)
+            .map(|vals| vals.into_iter().map(|(val, _)| val).collect())
+    }
+
+    /**
+     * Decrypts ciphertexts that each hold part of a P, such as a
+     * multi-ciphertext type's fields encrypted separately, into a
+     * single P.
+     *
+     * # Remarks
+     * The ciphertexts' underlying SEAL ciphertexts are concatenated in
+     * order, as though they formed one [`Ciphertext`] of type P, so
+     * each part must come in the position P's encoding expects. Since
+     * the parts are typically of other types, their types aren't
+     * checked, but together they must hold exactly
+     * [`P::NUM_CIPHERTEXTS`](NumCiphertexts::NUM_CIPHERTEXTS) ciphertexts
+     * or this returns [`Error::IncorrectCiphertextCount`].
+     */
+    pub fn decrypt_vec<P>(&self, ciphertexts: &[Ciphertext], private_key: &PrivateKey) -> Result<P>
+    where
+        P: TryFromPlaintext + TypeName + NumCiphertexts,
+    {
+        let inner = ciphertexts
+            .iter()
+            .flat_map(|c| match &c.inner {
+                InnerCiphertext::Seal(c) => c.iter().cloned(),
+            })
+            .collect::<Vec<_>>();
+
+        if inner.len() != P::NUM_CIPHERTEXTS {
+            return Err(Error::IncorrectCiphertextCount);
+        }
+
+        let ciphertext = Ciphertext {
+            data_type: Type {
+                is_encrypted: true,
+                ..P::type_name()
+            },
+            inner: InnerCiphertext::Seal(inner),
+            authentication_tag: None,
+        };
+
+        self.decrypt(&ciphertext, private_key)
+    }
+
+    /**
+     * Decrypts the given ciphertext into the type P as
+     * [`decrypt`](Self::decrypt) does, also returning statistics about
+     * the decrypted plaintext.
+     *
+     * # Remarks
+     * Values occupying more coefficients than expected suggest the
+     * computation's range grew toward overflowing the plaintext
+     * modulus or lattice dimension.
+     */
+    pub fn decrypt_with_stats<P>(
+        &self,
+        ciphertext: &Ciphertext,
+        private_key: &PrivateKey,
+    ) -> Result<(P, DecryptionStats)>
+    where
+        P: TryFromPlaintext + TypeName,
+    {
+        self.decrypt_internal(
+            std::slice::from_ref(ciphertext),
+            private_key,
+            P::try_from_plaintext,
+        )
+        .map(|mut vals| vals.remove(0))
+    }
+
+    /**
+     * Like [`decrypt`](Self::decrypt), but decodes with
+     * [`try_from_plaintext_checked`](TryFromPlaintext::try_from_plaintext_checked),
+     * so types that can detect their value overflowed the plaintext
+     * modulus return an error (e.g. [`Error::OverflowDetected`]) rather
+     * than a wrapped value.
+     */
+    pub fn decrypt_checked<P>(&self, ciphertext: &Ciphertext, private_key: &PrivateKey) -> Result<P>
+    where
+        P: TryFromPlaintext + TypeName,
+    {
+        self.decrypt_internal(
+            std::slice::from_ref(ciphertext),
+            private_key,
+            P::try_from_plaintext_checked,
+        )
+        .map(|mut vals| vals.remove(0).0)
+    }
+
+    fn decrypt_internal<P, F>(
+        &self,
+        ciphertexts: &[Ciphertext],
+        private_key: &PrivateKey,
+        decode: F,
+    ) -> Result<Vec<(P, DecryptionStats)>>
+    where
+        P: TypeName,
+        F: Fn(&Plaintext, &Params) -> Result<P>,
     {
         let expected_type = Type {
             is_encrypted: true,
             ..P::type_name()
         };
 
-        if expected_type != ciphertext.data_type {
-            return Err(Error::type_mismatch(&expected_type, &ciphertext.data_type));
+        if let Some(c) = ciphertexts.iter().find(|c| c.data_type != expected_type) {
+            return Err(Error::type_mismatch(&expected_type, &c.data_type));
         }
 
         let fhe_data = self.runtime_data.unwrap_fhe();
 
-        let val = match (&fhe_data.context, &ciphertext.inner) {
-            (Context::Seal(context), InnerCiphertext::Seal(ciphertexts)) => {
+        match &fhe_data.context {
+            Context::Seal(context) => {
                 let decryptor = Decryptor::new(context, &private_key.0)?;
 
+                // Decrypting dominates, so only it runs in parallel; P
+                // needn't be Send.
                 let plaintexts = ciphertexts
-                    .iter()
-                    .map(|c| {
-                        if decryptor
-                            .invariant_noise_budget(c)
-                            .map_err(Error::SealError)?
-                            == 0
-                        {
-                            return Err(Error::TooMuchNoise);
-                        }
+                    .par_iter()
+                    .map(|ciphertext| match &ciphertext.inner {
+                        InnerCiphertext::Seal(ciphertexts) => ciphertexts
+                            .iter()
+                            .map(|c| {
+                                // CKKS has no noise budget; its noise only
+                                // reduces precision.
+                                if fhe_data.params.scheme_type != SchemeType::Ckks
+                                    && decryptor
+                                        .invariant_noise_budget(c)
+                                        .map_err(Error::SealError)?
+                                        == 0
+                                {
+                                    return Err(Error::TooMuchNoise);
+                                }
 
-                        decryptor.decrypt(c).map_err(Error::SealError)
+                                decryptor.decrypt(c).map_err(Error::SealError)
+                            })
+                            .collect::<Result<Vec<SealPlaintext>>>(),
                     })
-                    .collect::<Result<Vec<SealPlaintext>>>()?
-                    .drain(0..)
-                    .map(|p| WithContext {
-                        params: fhe_data.params.clone(),
-                        data: p,
-                    })
-                    .collect();
+                    .collect::<Result<Vec<_>>>()?;
+
+                plaintexts
+                    .into_iter()
+                    .map(|plaintexts| {
+                        let plaintexts = plaintexts
+                            .into_iter()
+                            .map(|p| WithContext {
+                                params: fhe_data.params.clone(),
+                                data: p,
+                            })
+                            .collect::<Vec<_>>();
+
+                        let stats = DecryptionStats {
+                            significant_coefficients: plaintexts
+                                .iter()
+                                .map(|p| {
+                                    (0..p.len()).filter(|i| p.get_coefficient(*i) != 0).count()
+                                })
+                                .sum(),
+                        };
 
-                P::try_from_plaintext(
-                    &Plaintext {
-                        data_type: P::type_name(),
-                        inner: InnerPlaintext::Seal(plaintexts),
-                    },
-                    &fhe_data.params,
-                )?
-            }
-        };
+                        let val = decode(
+                            &Plaintext {
+                                data_type: P::type_name(),
+                                inner: InnerPlaintext::Seal(plaintexts),
+                            },
+                            &fhe_data.params,
+                        )?;
 
-        Ok(val)
+                        Ok((val, stats))
+                    })
+                    .collect()
+            }
+        }
     }
 
     /**
@@ -251,6 +497,9 @@ where
      * than one ciphertext. This function returns the
      * *minimum* noise budget remaining of all the enclosed
      * ciphertexts.
+     *
+     * A budget of 0 means the ciphertext is corrupted and will decrypt
+     * to garbage.
      */
     pub fn measure_noise_budget(&self, c: &Ciphertext, private_key: &PrivateKey) -> Result<u32> {
         let fhe_data = self.runtime_data.unwrap_fhe();
@@ -267,6 +516,29 @@ where
         }
     }
 
+    /**
+     * Returns the keys for this runtime's parameters from `cache`,
+     * generating and caching them with
+     * [`generate_keys`](Self::generate_keys) if it has none.
+     */
+    pub fn generate_or_cache_keys(&self, cache: &KeyCache) -> Result<(PublicKey, PrivateKey)> {
+        cache.get_or_try_insert_with(self.params(), || self.generate_keys())
+    }
+
+    /**
+     * Returns the noise budget (in bits) remaining in the given
+     * ciphertext. A budget of 0 means the ciphertext is corrupted and
+     * will decrypt to garbage.
+     *
+     * # Remarks
+     * Equivalent to [`measure_noise_budget`](Self::measure_noise_budget).
+     * Useful for asserting a ciphertext is still decryptable after a
+     * chain of operations.
+     */
+    pub fn noise_budget(&self, ciphertext: &Ciphertext, private_key: &PrivateKey) -> Result<u32> {
+        self.measure_noise_budget(ciphertext, private_key)
+    }
+
     /**
      * Generates a tuple of public/private keys for the encapsulated scheme and parameters.
      *
@@ -276,39 +548,98 @@ where
      * can't require these associated keys and so long as the FHE program was compiled using the
      * search algorithm, it won't.
      *
+     * The public, relinearization, and Galois keys are generated
+     * concurrently on rayon's thread pool.
+     *
      * See [`PublicKey`] for more information.
      */
     pub fn generate_keys(&self) -> Result<(PublicKey, PrivateKey)> {
+        self.generate_keys_internal(|keygen| Ok(keygen.create_galois_keys().ok()))
+    }
+
+    /**
+     * Like [`generate_keys`](Self::generate_keys), but generates
+     * Galois keys for exactly the rotations the given FHE program
+     * performs (see
+     * [`required_galois_steps`](sunscreen_fhe_program::FheProgramTrait::required_galois_steps)),
+     * and none if it performs none.
+     *
+     * # Remarks
+     * These keys are smaller and faster to generate than the full set,
+     * but only suffice for this program; running another program that
+     * rotates by different steps fails.
+     *
+     * Unlike [`generate_keys`](Self::generate_keys), this returns an
+     * error if the program needs Galois keys but the parameters can't
+     * support them, rather than deferring
+     * [`Error::MissingGaloisKeys`] to [`run`](Self::run).
+     */
+    pub fn generate_keys_with_galois(
+        &self,
+        fhe_program: &CompiledFheProgram,
+    ) -> Result<(PublicKey, PrivateKey)> {
+        let steps = fhe_program.fhe_program_fn.required_galois_steps();
+
+        self.generate_keys_internal(|keygen| {
+            if steps.is_empty() {
+                return Ok(None);
+            }
+
+            Ok(Some(keygen.create_galois_keys_from_steps(&steps)?))
+        })
+    }
+
+    fn generate_keys_internal<F>(&self, create_galois_keys: F) -> Result<(PublicKey, PrivateKey)>
+    where
+        F: FnOnce(&KeyGenerator) -> Result<Option<GaloisKeys>> + Send,
+    {
         let fhe_data = self.runtime_data.unwrap_fhe();
 
         let keys = match &fhe_data.context {
             Context::Seal(context) => {
                 let keygen = KeyGenerator::new(context)?;
+                let secret_key = keygen.secret_key();
+
+                // Each key type is independent and slow to generate under
+                // large parameters, so generate them concurrently. Each
+                // task gets its own generator over the same secret key, so
+                // no two tasks share SEAL state.
+                let keygen_for_task = || KeyGenerator::new_from_secret_key(context, &secret_key);
+
+                let (galois_keys, (relin_keys, public_key)) = rayon::join(
+                    || create_galois_keys(&keygen),
+                    || {
+                        rayon::join(
+                            || {
+                                keygen_for_task()
+                                    .map(|keygen| keygen.create_relinearization_keys().ok())
+                            },
+                            || keygen_for_task().map(|keygen| keygen.create_public_key()),
+                        )
+                    },
+                );
 
-                let galois_keys = keygen.create_galois_keys().ok().map(|v| WithContext {
+                let galois_keys = galois_keys?.map(|v| WithContext {
                     params: fhe_data.params.clone(),
                     data: v,
                 });
 
-                let relin_keys = keygen
-                    .create_relinearization_keys()
-                    .ok()
-                    .map(|v| WithContext {
-                        params: fhe_data.params.clone(),
-                        data: v,
-                    });
+                let relin_keys = relin_keys?.map(|v| WithContext {
+                    params: fhe_data.params.clone(),
+                    data: v,
+                });
 
                 let public_keys = PublicKey {
                     public_key: WithContext {
                         params: fhe_data.params.clone(),
-                        data: keygen.create_public_key(),
+                        data: public_key?,
                     },
                     galois_key: galois_keys,
                     relin_key: relin_keys,
                 };
                 let private_key = PrivateKey(WithContext {
                     params: fhe_data.params.clone(),
-                    data: keygen.secret_key(),
+                    data: secret_key,
                 });
 
                 (public_keys, private_key)
@@ -318,6 +649,73 @@ where
         Ok(keys)
     }
 
+    /**
+     * When `require` is set, this runtime tags every ciphertext it
+     * produces and [`run`](Self::run) rejects ciphertext arguments that
+     * lack a valid tag with [`Error::UnauthenticatedCiphertext`].
+     *
+     * # Remarks
+     * This is a defense-in-depth measure for services accepting
+     * ciphertexts from elsewhere: malformed or externally crafted
+     * ciphertexts are refused before evaluation. The tag is keyed to a
+     * random secret held by this runtime instance, so only ciphertexts
+     * encrypted or computed by this instance pass. Runtimes created
+     * separately (e.g. after a restart) reject each other's
+     * ciphertexts.
+     *
+     * This is not cryptographic integrity against a key holder: anyone
+     * with the public key can encrypt values, and a ciphertext's
+     * contents are not otherwise authenticated to the party that
+     * encrypted them.
+     *
+     * Disabled by default. Calling this again with `true` generates a
+     * fresh secret, invalidating previously issued tags.
+     */
+    pub fn require_authenticated_inputs(mut self, require: bool) -> Self {
+        self.authentication_key = if require { Some(rand::random()) } else { None };
+
+        self
+    }
+
+    /**
+     * Sets the authentication tag on `ciphertext` if this runtime
+     * requires authenticated inputs.
+     */
+    fn authenticate(&self, mut ciphertext: Ciphertext) -> Result<Ciphertext> {
+        if let Some(key) = &self.authentication_key {
+            ciphertext.authentication_tag = Some(authentication_tag(key, &ciphertext)?);
+        }
+
+        Ok(ciphertext)
+    }
+
+    /**
+     * Returns [`Error::UnauthenticatedCiphertext`] if this runtime
+     * requires authenticated inputs and `ciphertext` lacks a valid tag.
+     */
+    fn check_authentication(&self, ciphertext: &Ciphertext) -> Result<()> {
+        let key = match &self.authentication_key {
+            Some(key) => key,
+            None => return Ok(()),
+        };
+
+        let expected = authentication_tag(key, ciphertext)?;
+
+        match &ciphertext.authentication_tag {
+            // Compare in constant time.
+            Some(tag)
+                if tag
+                    .iter()
+                    .zip(expected)
+                    .fold(0, |acc, (a, b)| acc | (a ^ b))
+                    == 0 =>
+            {
+                Ok(())
+            }
+            _ => Err(Error::UnauthenticatedCiphertext),
+        }
+    }
+
     /**
      * Returns the metadata for this runtime's associated FHE program.
      */
@@ -334,19 +732,312 @@ where
     pub fn run<I>(
         &self,
         fhe_program: &CompiledFheProgram,
-        mut arguments: Vec<I>,
+        arguments: Vec<I>,
         public_key: &PublicKey,
     ) -> Result<Vec<Ciphertext>>
     where
         I: Into<FheProgramInput>,
     {
-        // We're going to call run_program_unchecked, which
-        // can result in undefined behavior, non-termination,
-        // or panics on malformed programs. Since this method is safe,
-        // it must guard against calling run_program_unchecked with
-        // inputs that result in undefined behavior.
+        self.run_plan(&self.prepare(fhe_program)?, arguments, public_key)
+    }
+
+    /**
+     * Like [`run`](Self::run), but also measures how long each node of
+     * the FHE program takes to run.
+     *
+     * # Remarks
+     * Each [`OperationProfile`](crate::OperationProfile) times the node's SEAL call. Independent
+     * nodes run in parallel, so the durations can sum to more than the
+     * run's wall-clock time; use
+     * [`critical_path`](ProfileReport::critical_path) to find the chain
+     * of operations that bounds it.
+     */
+    pub fn run_with_profiling<I>(
+        &self,
+        fhe_program: &CompiledFheProgram,
+        arguments: Vec<I>,
+        public_key: &PublicKey,
+    ) -> Result<(Vec<Ciphertext>, ProfileReport)>
+    where
+        I: Into<FheProgramInput>,
+    {
+        let timings = Mutex::new(vec![]);
+
+        let outputs = self.run_plan_timed(
+            &self.prepare(fhe_program)?,
+            arguments,
+            public_key,
+            Some(&timings),
+        )?;
+
+        let report = build_report(&fhe_program.fhe_program_fn, &timings.into_inner().unwrap());
+
+        Ok((outputs, report))
+    }
+
+    /**
+     * Runs the given FHE program once for each set of arguments in
+     * `inputs`, returning each run's outputs in the same order.
+     *
+     * # Remarks
+     * The program is validated and the evaluator created once, as with
+     * [`prepare`](Self::prepare), rather than on every run. Fails with
+     * the first error any run produces.
+     */
+    pub fn run_many<I>(
+        &self,
+        fhe_program: &CompiledFheProgram,
+        inputs: Vec<Vec<I>>,
+        public_key: &PublicKey,
+    ) -> Result<Vec<Vec<Ciphertext>>>
+    where
+        I: Into<FheProgramInput>,
+    {
+        let plan = self.prepare(fhe_program)?;
+
+        inputs
+            .into_iter()
+            .map(|arguments| self.run_plan(&plan, arguments, public_key))
+            .collect()
+    }
+
+    /**
+     * Estimates how much noise budget running the given FHE program
+     * consumes by running it on fresh encryptions of zero under a
+     * throwaway key pair.
+     *
+     * # Remarks
+     * Plaintext arguments are replaced with the constant 1, since
+     * multiplying by a zero plaintext fails in SEAL.
+     *
+     * Noise growth depends somewhat on the values encrypted, so this is
+     * an estimate: outputs with only a few bits remaining may still fail
+     * to decrypt on real data. Running the program takes as long as
+     * [`run`](Self::run), plus key generation.
+     */
+    pub fn estimate_noise_budget(
+        &self,
+        fhe_program: &CompiledFheProgram,
+    ) -> Result<NoiseBudgetEstimate> {
+        self.estimate_plan_noise_budget(&self.prepare(fhe_program)?)
+    }
+
+    /**
+     * Analytically estimates the noise growth of the given FHE program
+     * under this runtime's parameters, without generating keys or
+     * running it.
+     *
+     * # Remarks
+     * The estimate bounds the worst-case noise for any input, so it's
+     * pessimistic: a program the report flags as
+     * [risky](NoiseReport::is_risky) may still decrypt correctly, but
+     * one it doesn't flag should. Use
+     * [`estimate_noise_budget`](Self::estimate_noise_budget) for a
+     * measured estimate.
+     *
+     * Returns [`Error::UnsupportedNoiseEstimate`] unless this runtime
+     * uses the BFV scheme.
+     */
+    pub fn validate(&self, fhe_program: &CompiledFheProgram) -> Result<NoiseReport> {
         fhe_program.fhe_program_fn.validate()?;
 
+        estimate_noise(&fhe_program.fhe_program_fn, self.params())
+    }
+
+    /**
+     * Runs the given FHE program as [`run`](Self::run) does, but first
+     * returns [`Error::InsufficientNoiseBudget`] if
+     * [`estimate_noise_budget`](Self::estimate_noise_budget) predicts
+     * its outputs won't decrypt correctly.
+     *
+     * # Remarks
+     * Useful in tests to catch programs that exhaust the noise budget
+     * of their parameters before running them on real data.
+     */
+    pub fn run_checked<I>(
+        &self,
+        fhe_program: &CompiledFheProgram,
+        arguments: Vec<I>,
+        public_key: &PublicKey,
+    ) -> Result<Vec<Ciphertext>>
+    where
+        I: Into<FheProgramInput>,
+    {
+        let plan = self.prepare(fhe_program)?;
+
+        if !self
+            .estimate_plan_noise_budget(&plan)?
+            .will_decrypt_correctly
+        {
+            return Err(Error::InsufficientNoiseBudget);
+        }
+
+        self.run_plan(&plan, arguments, public_key)
+    }
+
+    fn estimate_plan_noise_budget(&self, plan: &ExecutionPlan<'_>) -> Result<NoiseBudgetEstimate> {
+        let fhe_data = self.runtime_data.unwrap_fhe();
+        let ir = &plan.fhe_program.fhe_program_fn;
+
+        let context = match &fhe_data.context {
+            Context::Seal(context) => context,
+        };
+
+        let keygen = KeyGenerator::new(context)?;
+        let public_key = keygen.create_public_key();
+        let secret_key = keygen.secret_key();
+        let relin_key = keygen.create_relinearization_keys().ok();
+        let galois_key = keygen.create_galois_keys().ok();
+
+        if relin_key.is_none() && ir.requires_relin_keys() {
+            return Err(Error::MissingRelinearizationKeys);
+        }
+
+        if galois_key.is_none() && ir.requires_galois_keys() {
+            return Err(Error::MissingGaloisKeys);
+        }
+
+        let encryptor = Encryptor::with_public_key(context, &public_key)?;
+        let decryptor = Decryptor::new(context, &secret_key)?;
+
+        let constant = |c| {
+            let mut p = SealPlaintext::new()?;
+            p.resize(1);
+            p.set_coefficient(0, c);
+
+            Ok::<_, seal_fhe::Error>(p)
+        };
+
+        let zero = encryptor.encrypt(&constant(0)?)?;
+        let initial_budget = decryptor.invariant_noise_budget(&zero)?;
+
+        // Inputs are indexed by argument position, so lay out a value of
+        // the right kind at each index the program reads.
+        let mut inputs = vec![];
+
+        for n in ir.graph.node_weights() {
+            let (id, data) = match n.operation {
+                Operation::InputCiphertext(id) => (id, SealData::Ciphertext(zero.clone())),
+                Operation::InputPlaintext(id) => (id, SealData::Plaintext(constant(1)?)),
+                _ => continue,
+            };
+
+            if inputs.len() <= id {
+                inputs.resize(id + 1, None);
+            }
+
+            inputs[id] = Some(data);
+        }
+
+        let inputs = inputs
+            .into_iter()
+            .map(|i| i.ok_or(Error::IncorrectCiphertextCount))
+            .collect::<Result<Vec<_>>>()?;
+
+        // prepare validated the program and we created inputs for every
+        // input node, so this is safe.
+        let outputs = unsafe {
+            run_program_unchecked(
+                ir,
+                &inputs,
+                &plan.evaluator,
+                &relin_key.as_ref(),
+                &galois_key.as_ref(),
+            )
+        }?;
+
+        let remaining = outputs.iter().try_fold(initial_budget, |min, c| {
+            Ok::<_, seal_fhe::Error>(u32::min(min, decryptor.invariant_noise_budget(c)?))
+        })?;
+
+        Ok(NoiseBudgetEstimate {
+            initial_budget,
+            consumed: initial_budget - remaining,
+            remaining,
+            will_decrypt_correctly: remaining > 0,
+        })
+    }
+
+    /**
+     * Validates the given FHE program and sets up everything needed to
+     * evaluate it, so it can be run repeatedly with
+     * [`run_plan`](Self::run_plan).
+     *
+     * # Remarks
+     * [`run`](Self::run) validates the program's graph and creates an
+     * evaluator on every call. When running the same program many times
+     * (e.g. in a latency-sensitive server), prepare it once and reuse
+     * the plan to skip this work. The plan may only be run on this
+     * runtime.
+     */
+    pub fn prepare<'p>(&self, fhe_program: &'p CompiledFheProgram) -> Result<ExecutionPlan<'p>> {
+        // run_plan calls run_program_unchecked, which can result in
+        // undefined behavior, non-termination, or panics on malformed
+        // programs. Since run_plan is safe, every plan must guard
+        // against calling run_program_unchecked with inputs that result
+        // in undefined behavior.
+        fhe_program.fhe_program_fn.validate()?;
+
+        if fhe_program.metadata.signature.num_ciphertexts.len()
+            != fhe_program.metadata.signature.returns.len()
+        {
+            return Err(Error::ReturnTypeMetadataError);
+        }
+
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        let evaluator = match &fhe_data.context {
+            Context::Seal(context) => BFVEvaluator::new(context)?,
+        };
+
+        Ok(ExecutionPlan {
+            fhe_program,
+            params: fhe_data.params.clone(),
+            evaluator,
+        })
+    }
+
+    /**
+     * Runs a program previously validated with [`prepare`](Self::prepare).
+     * Produces the same results as [`run`](Self::run).
+     *
+     * Returns [`Error::ParameterMismatch`] if the plan was prepared by a
+     * runtime with different parameters or any argument was encrypted
+     * under different parameters.
+     */
+    pub fn run_plan<I>(
+        &self,
+        plan: &ExecutionPlan<'_>,
+        arguments: Vec<I>,
+        public_key: &PublicKey,
+    ) -> Result<Vec<Ciphertext>>
+    where
+        I: Into<FheProgramInput>,
+    {
+        self.run_plan_timed(plan, arguments, public_key, None)
+    }
+
+    /**
+     * Like [`run_plan`](Self::run_plan), but when `timings` is given,
+     * records how long each node took to run.
+     */
+    fn run_plan_timed<I>(
+        &self,
+        plan: &ExecutionPlan<'_>,
+        mut arguments: Vec<I>,
+        public_key: &PublicKey,
+        timings: Option<&Mutex<Vec<(NodeIndex, Duration)>>>,
+    ) -> Result<Vec<Ciphertext>>
+    where
+        I: Into<FheProgramInput>,
+    {
+        let fhe_program = plan.fhe_program;
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        if plan.params != fhe_data.params {
+            return Err(Error::ParameterMismatch);
+        }
+
         // Aside from FHE program correctness, check that the required keys are given.
         if public_key.relin_key.is_none() && fhe_program.fhe_program_fn.requires_relin_keys() {
             return Err(Error::MissingRelinearizationKeys);
@@ -358,6 +1049,22 @@ where
 
         let mut arguments: Vec<FheProgramInput> = arguments.drain(0..).map(|a| a.into()).collect();
 
+        for a in &arguments {
+            if let FheProgramInput::Ciphertext(c) = a {
+                self.check_authentication(c)?;
+
+                // SEAL doesn't notice ciphertexts from other parameters;
+                // it just computes garbage.
+                match &c.inner {
+                    InnerCiphertext::Seal(c) => {
+                        if c.iter().any(|c| c.params != fhe_data.params) {
+                            return Err(Error::ParameterMismatch);
+                        }
+                    }
+                }
+            }
+        }
+
         let expected_args = &fhe_program.metadata.signature.arguments;
 
         // Check the arguments match the signature.
@@ -380,96 +1087,217 @@ where
             ));
         }
 
-        if fhe_program.metadata.signature.num_ciphertexts.len()
-            != fhe_program.metadata.signature.returns.len()
-        {
-            return Err(Error::ReturnTypeMetadataError);
-        }
+        let mut inputs: Vec<SealData> = vec![];
 
-        let fhe_data = self.runtime_data.unwrap_fhe();
-
-        match &fhe_data.context {
-            Context::Seal(context) => {
-                let evaluator = BFVEvaluator::new(context)?;
-
-                let mut inputs: Vec<SealData> = vec![];
-
-                for i in arguments.drain(0..) {
-                    match i {
-                        FheProgramInput::Ciphertext(c) => match c.inner {
-                            InnerCiphertext::Seal(mut c) => {
-                                for j in c.drain(0..) {
-                                    inputs.push(SealData::Ciphertext(j.data));
-                                }
-                            }
-                        },
-                        FheProgramInput::Plaintext(p) => {
-                            let p = p.try_into_plaintext(&fhe_data.params)?;
-
-                            match p.inner {
-                                InnerPlaintext::Seal(mut p) => {
-                                    for j in p.drain(0..) {
-                                        inputs.push(SealData::Plaintext(j.data));
-                                    }
-                                }
+        for i in arguments.drain(0..) {
+            match i {
+                FheProgramInput::Ciphertext(c) => match c.inner {
+                    InnerCiphertext::Seal(mut c) => {
+                        for j in c.drain(0..) {
+                            inputs.push(SealData::Ciphertext(j.data));
+                        }
+                    }
+                },
+                FheProgramInput::Plaintext(p) => {
+                    let p = p.try_into_plaintext(&fhe_data.params)?;
+
+                    match p.inner {
+                        InnerPlaintext::Seal(mut p) => {
+                            for j in p.drain(0..) {
+                                inputs.push(SealData::Plaintext(j.data));
                             }
                         }
                     }
                 }
+            }
+        }
 
-                let relin_key = public_key.relin_key.as_ref().map(|p| &p.data);
-                let galois_key = public_key.galois_key.as_ref().map(|p| &p.data);
+        let relin_key = public_key.relin_key.as_ref().map(|p| &p.data);
+        let galois_key = public_key.galois_key.as_ref().map(|p| &p.data);
+
+        let mut raw_ciphertexts = unsafe {
+            run_program_timed_unchecked(
+                &fhe_program.fhe_program_fn,
+                &inputs,
+                &plan.evaluator,
+                &relin_key,
+                &galois_key,
+                timings,
+            )
+        }?;
 
-                let mut raw_ciphertexts = unsafe {
-                    run_program_unchecked(
-                        &fhe_program.fhe_program_fn,
-                        &inputs,
-                        &evaluator,
-                        &relin_key,
-                        &galois_key,
-                    )
-                }?;
+        let mut packed_ciphertexts = vec![];
 
-                let mut packed_ciphertexts = vec![];
+        for (i, ciphertext_count) in fhe_program
+            .metadata
+            .signature
+            .num_ciphertexts
+            .iter()
+            .enumerate()
+        {
+            packed_ciphertexts.push(
+                self.authenticate(Ciphertext {
+                    data_type: fhe_program.metadata.signature.returns[i].clone(),
+                    inner: InnerCiphertext::Seal(
+                        raw_ciphertexts
+                            .drain(0..*ciphertext_count)
+                            .map(|c| WithContext {
+                                params: fhe_data.params.clone(),
+                                data: c,
+                            })
+                            .collect(),
+                    ),
+                    authentication_tag: None,
+                })?,
+            );
+        }
 
-                for (i, ciphertext_count) in fhe_program
-                    .metadata
-                    .signature
-                    .num_ciphertexts
-                    .iter()
-                    .enumerate()
-                {
-                    packed_ciphertexts.push(Ciphertext {
-                        data_type: fhe_program.metadata.signature.returns[i].clone(),
-                        inner: InnerCiphertext::Seal(
-                            raw_ciphertexts
-                                .drain(0..*ciphertext_count)
-                                .map(|c| WithContext {
+        Ok(packed_ciphertexts)
+    }
+
+    /**
+     * Encrypts the given [`FheType`](crate::FheType) using the given public key.
+     *
+     * Returns [`Error::ParameterMismatch`] if the plaintext is incompatible with this runtime's
+     * scheme.
+     */
+    pub fn encrypt<P>(&self, val: P, public_key: &PublicKey) -> Result<Ciphertext>
+    where
+        P: TryIntoPlaintext + TypeName,
+    {
+        self.encrypt_many(std::slice::from_ref(&val), public_key)
+            .map(|mut ciphertexts| ciphertexts.remove(0))
+    }
+
+    /**
+     * Encrypts each of the given values using the given public key.
+     *
+     * # Remarks
+     * The encryptor is created once for the whole batch and the values
+     * encrypt in parallel on rayon's thread pool, so this is faster than
+     * calling [`encrypt`](Self::encrypt) in a loop. Returns the first
+     * error (in input order) any value produces.
+     */
+    pub fn encrypt_many<P>(&self, values: &[P], public_key: &PublicKey) -> Result<Vec<Ciphertext>>
+    where
+        P: TryIntoPlaintext + TypeName,
+    {
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        // Encrypting dominates, so only it runs in parallel; P needn't be
+        // Sync.
+        let plaintexts = values
+            .iter()
+            .map(|val| val.try_into_plaintext(&fhe_data.params))
+            .collect::<Result<Vec<_>>>()?;
+
+        match &fhe_data.context {
+            Context::Seal(context) => {
+                let encryptor = Encryptor::with_public_key(context, &public_key.public_key.data)?;
+
+                let ciphertexts = plaintexts
+                    .par_iter()
+                    .map(|plaintext| match &plaintext.inner {
+                        InnerPlaintext::Seal(p) => p
+                            .iter()
+                            .map(|p| {
+                                Ok(WithContext {
                                     params: fhe_data.params.clone(),
-                                    data: c,
+                                    data: encryptor.encrypt(p)?,
                                 })
-                                .collect(),
-                        ),
-                    });
-                }
-
-                Ok(packed_ciphertexts)
+                            })
+                            .collect::<Result<Vec<_>>>(),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                ciphertexts
+                    .into_iter()
+                    .map(|ciphertexts| {
+                        self.authenticate(Ciphertext {
+                            data_type: Type {
+                                is_encrypted: true,
+                                ..P::type_name()
+                            },
+                            inner: InnerCiphertext::Seal(ciphertexts),
+                            authentication_tag: None,
+                        })
+                    })
+                    .collect()
             }
         }
     }
 
     /**
-     * Encrypts the given [`FheType`](crate::FheType) using the given public key.
+     * Encrypts the given [`FheType`](crate::FheType) using the given public
+     * key and returns the ciphertext along with its initial noise budget
+     * (in bits).
+     *
+     * # Remarks
+     * Measuring the budget requires the private key. Comparing this
+     * baseline against [`noise_budget`](Self::noise_budget) after running
+     * a program tells how much budget the program consumed.
+     */
+    pub fn encrypt_with_budget<P>(
+        &self,
+        val: P,
+        public_key: &PublicKey,
+        private_key: &PrivateKey,
+    ) -> Result<(Ciphertext, u32)>
+    where
+        P: TryIntoPlaintext + TypeName,
+    {
+        let ciphertext = self.encrypt(val, public_key)?;
+        let budget = self.measure_noise_budget(&ciphertext, private_key)?;
+
+        Ok((ciphertext, budget))
+    }
+
+    /**
+     * Encrypts the given [`FheType`](crate::FheType) using the given private
+     * key rather than a public key.
+     *
+     * # Remarks
+     * The resulting ciphertext runs through FHE programs and decrypts
+     * exactly like one from [`encrypt`](Self::encrypt), but only the
+     * private key holder can produce it. Symmetric encryption
+     * introduces less noise than public key encryption, leaving slightly
+     * more noise budget.
      *
      * Returns [`Error::ParameterMismatch`] if the plaintext is incompatible with this runtime's
      * scheme.
      */
-    pub fn encrypt<P>(&self, val: P, public_key: &PublicKey) -> Result<Ciphertext>
+    pub fn encrypt_symmetric<P>(&self, val: P, private_key: &PrivateKey) -> Result<Ciphertext>
     where
         P: TryIntoPlaintext + TypeName,
     {
-        self.encrypt_return_components_switched(val, public_key, false, None)
-            .map(|x| x.ciphertext)
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        let plaintext = val.try_into_plaintext(&fhe_data.params)?;
+
+        match (&fhe_data.context, plaintext.inner) {
+            (Context::Seal(context), InnerPlaintext::Seal(inner_plain)) => {
+                let encryptor = Encryptor::with_secret_key(context, &private_key.0.data)?;
+
+                let ciphertexts = inner_plain
+                    .iter()
+                    .map(|p| {
+                        Ok(WithContext {
+                            params: fhe_data.params.clone(),
+                            data: encryptor.encrypt_symmetric(p)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                self.authenticate(Ciphertext {
+                    data_type: Type {
+                        is_encrypted: true,
+                        ..P::type_name()
+                    },
+                    inner: InnerCiphertext::Seal(ciphertexts),
+                    authentication_tag: None,
+                })
+            }
+        }
     }
 
     /**
@@ -579,7 +1407,10 @@ where
                 let ciphertexts = inner_plain
                     .iter()
                     .map(|p| {
-                        let ciphertext = if export_components {
+                        // Encryption components are only defined for BFV.
+                        let ciphertext = if export_components
+                            || fhe_data.params.scheme_type != SchemeType::Bfv
+                        {
                             encryptor.encrypt(p).map_err(Error::SealError)
                         } else {
                             let (ciphertext, u, e, r) = encrypt_function(&encryptor, p, seed)?;
@@ -612,13 +1443,14 @@ where
                     .collect();
 
                 (
-                    Ciphertext {
+                    self.authenticate(Ciphertext {
                         data_type: Type {
                             is_encrypted: true,
                             ..P::type_name()
                         },
                         inner: InnerCiphertext::Seal(ciphertexts),
-                    },
+                        authentication_tag: None,
+                    })?,
                     us,
                     es,
                     rs,
@@ -635,6 +1467,108 @@ where
     }
 }
 
+impl<T, B> GenericRuntime<T, B>
+where
+    T: marker::Fhe + Sync,
+    B: Sync,
+{
+    /**
+     * Like [`run_many`](Self::run_many), but runs the input sets in
+     * parallel on rayon's thread pool.
+     *
+     * # Remarks
+     * The program is validated and the evaluator created once and
+     * shared by every run. Fails with [`Error::IncorrectCiphertextCount`]
+     * before running anything unless every input set has one argument
+     * per parameter of the program, and otherwise with the first
+     * error (in input order) any run produces.
+     */
+    pub fn run_batched<I>(
+        &self,
+        fhe_program: &CompiledFheProgram,
+        inputs: Vec<Vec<I>>,
+        public_key: &PublicKey,
+    ) -> Result<Vec<Vec<Ciphertext>>>
+    where
+        I: Into<FheProgramInput> + Send,
+    {
+        let expected = fhe_program.metadata.signature.arguments.len();
+
+        if inputs.iter().any(|arguments| arguments.len() != expected) {
+            return Err(Error::IncorrectCiphertextCount);
+        }
+
+        let plan = self.prepare(fhe_program)?;
+
+        inputs
+            .into_par_iter()
+            .map(|arguments| self.run_plan(&plan, arguments, public_key))
+            .collect()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T, B> GenericRuntime<T, B>
+where
+    T: marker::Fhe + Send + Sync + 'static,
+    B: Send + Sync + 'static,
+{
+    /**
+     * Runs the given FHE program like [`run`](Self::run), but on
+     * tokio's blocking thread pool so the calling async executor isn't
+     * blocked while the program evaluates.
+     *
+     * # Remarks
+     * FHE programs can take hundreds of milliseconds to evaluate, which
+     * stalls every other task sharing an async executor (e.g. in a web
+     * server's request handlers). The evaluation starts immediately;
+     * the returned future resolves to its result.
+     *
+     * The program and public key are cloned into the blocking task. If
+     * the evaluation panics, the future returns
+     * [`Error::AsyncTaskFailed`] rather than propagating the panic.
+     *
+     * Requires the `async` feature.
+     *
+     * # Panics
+     * If called outside the context of a tokio runtime.
+     */
+    pub fn run_async<I>(
+        self: &Arc<Self>,
+        fhe_program: &CompiledFheProgram,
+        arguments: Vec<I>,
+        public_key: &PublicKey,
+    ) -> impl Future<Output = Result<Vec<Ciphertext>>>
+    where
+        I: Into<FheProgramInput> + Send + 'static,
+    {
+        let runtime = self.clone();
+        let fhe_program = fhe_program.clone();
+        let public_key = public_key.clone();
+
+        spawn_blocking_result(move || runtime.run(&fhe_program, arguments, &public_key))
+    }
+}
+
+/**
+ * Runs `f` on tokio's blocking thread pool, converting a panic inside
+ * `f` into [`Error::AsyncTaskFailed`].
+ */
+#[cfg(feature = "async")]
+fn spawn_blocking_result<F, R>(f: F) -> impl Future<Output = Result<R>>
+where
+    F: FnOnce() -> Result<R> + Send + 'static,
+    R: Send + 'static,
+{
+    let handle = tokio::task::spawn_blocking(f);
+
+    async move {
+        handle
+            .await
+            .map_err(|e| Error::async_task_failed(&e.to_string()))?
+    }
+}
+
 impl<T, B> GenericRuntime<T, B>
 where
     T: marker::Zkp,
@@ -865,9 +1799,33 @@ impl GenericRuntime<(), ()> {
 
     fn make_fhe_runtime_data(params: &Params) -> Result<FheRuntimeData> {
         match params.scheme_type {
-            SchemeType::Bfv => {
-                let bfv_params = BfvEncryptionParametersBuilder::new()
+            SchemeType::Bfv | SchemeType::Bgv => {
+                let builder = BfvEncryptionParametersBuilder::new()
                     .set_plain_modulus_u64(params.plain_modulus)
+                    .set_poly_modulus_degree(params.lattice_dimension)
+                    .set_coefficient_modulus(
+                        params
+                            .coeff_modulus
+                            .iter()
+                            .map(|v| Modulus::new(*v).unwrap())
+                            .collect::<Vec<Modulus>>(),
+                    );
+
+                let bfv_params = if params.scheme_type == SchemeType::Bgv {
+                    builder.build_bgv()?
+                } else {
+                    builder.build()?
+                };
+
+                let context = SealContext::new(&bfv_params, true, params.security_level)?;
+
+                Ok(FheRuntimeData {
+                    params: params.clone(),
+                    context: Context::Seal(context),
+                })
+            }
+            SchemeType::Ckks => {
+                let ckks_params = CkksEncryptionParametersBuilder::new()
                     .set_poly_modulus_degree(params.lattice_dimension)
                     .set_coefficient_modulus(
                         params
@@ -878,7 +1836,7 @@ impl GenericRuntime<(), ()> {
                     )
                     .build()?;
 
-                let context = SealContext::new(&bfv_params, true, params.security_level)?;
+                let context = SealContext::new(&ckks_params, true, params.security_level)?;
 
                 Ok(FheRuntimeData {
                     params: params.clone(),
@@ -900,6 +1858,7 @@ impl GenericRuntime<(), ()> {
             runtime_data: RuntimeData::Fhe(Self::make_fhe_runtime_data(params)?),
             _phantom_t: PhantomData,
             zkp_backend: (),
+            authentication_key: None,
         })
     }
 
@@ -914,6 +1873,7 @@ impl GenericRuntime<(), ()> {
             runtime_data: RuntimeData::Zkp(Self::make_zkp_runtime_data()),
             _phantom_t: PhantomData,
             zkp_backend: backend,
+            authentication_key: None,
         })
     }
 
@@ -933,6 +1893,7 @@ impl GenericRuntime<(), ()> {
             runtime_data,
             _phantom_t: PhantomData,
             zkp_backend: zkp_backend.clone(),
+            authentication_key: None,
         })
     }
 }
@@ -1197,3 +2158,32 @@ impl<'r, 'p, 'a, T: marker::Zkp, B: ZkpBackend> VerificationBuilder<'r, 'p, 'a,
         )
     }
 }
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use super::*;
+
+    fn block_on<F: Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    #[test]
+    fn blocking_task_returns_result() {
+        assert_eq!(
+            block_on(async { spawn_blocking_result(|| Ok(7)).await }),
+            Ok(7)
+        );
+    }
+
+    #[test]
+    fn blocking_task_panic_becomes_error() {
+        let result = block_on(async {
+            spawn_blocking_result::<_, ()>(|| panic!("evaluation failed")).await
+        });
+
+        assert!(matches!(result, Err(Error::AsyncTaskFailed(_))));
+    }
+}
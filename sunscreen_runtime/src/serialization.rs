@@ -1,12 +1,17 @@
 use std::hash::Hash;
 
 use crate::Params;
-use seal_fhe::{BfvEncryptionParametersBuilder, Context, FromBytes, Modulus, ToBytes};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use seal_fhe::{
+    BfvEncryptionParametersBuilder, CkksEncryptionParametersBuilder, Context, FromBytes, Modulus,
+    ToBytes,
+};
 use serde::{
     de::{Deserializer, MapAccess, SeqAccess, Visitor},
     ser::{Error, SerializeStruct, Serializer},
     Deserialize, Serialize,
 };
+use sunscreen_fhe_program::SchemeType;
 
 #[derive(Debug, PartialEq, Hash, Eq, Clone)]
 /**
@@ -39,6 +44,34 @@ where
     }
 }
 
+impl<T> WithContext<T>
+where
+    T: ToBytes + FromBytes + PartialEq,
+{
+    /**
+     * Serializes the contained data in SEAL's native format.
+     *
+     * # Remarks
+     * The returned bytes don't include the parameters. The receiver
+     * must already know them and pass them to
+     * [`from_bytes`](Self::from_bytes).
+     */
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        Ok(self.data.as_bytes()?)
+    }
+
+    /**
+     * Deserializes data produced by [`to_bytes`](Self::to_bytes) under
+     * the given parameters.
+     */
+    pub fn from_bytes(bytes: &[u8], params: &Params) -> crate::Result<Self> {
+        Ok(Self {
+            params: params.clone(),
+            data: deserialize_with_params(params, bytes)?,
+        })
+    }
+}
+
 impl<T> Serialize for WithContext<T>
 where
     T: ToBytes + FromBytes + PartialEq,
@@ -51,10 +84,11 @@ where
         state.serialize_field("params", &self.params)?;
         state.serialize_field(
             "data",
-            &self
-                .data
-                .as_bytes()
-                .map_err(|e| S::Error::custom(format!("Failed to serialize key: {}", e)))?,
+            &Payload(
+                self.data
+                    .as_bytes()
+                    .map_err(|e| S::Error::custom(format!("Failed to serialize key: {}", e)))?,
+            ),
         )?;
         state.end()
     }
@@ -92,7 +126,7 @@ where
                 let params = seq
                     .next_element()?
                     .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
-                let data: Vec<u8> = seq
+                let Payload(data) = seq
                     .next_element()?
                     .ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
 
@@ -107,7 +141,7 @@ where
                 A: MapAccess<'de>,
             {
                 let mut params: Option<Params> = None;
-                let mut data: Option<Vec<u8>> = None;
+                let mut data: Option<Payload> = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -121,7 +155,7 @@ where
                             }
                         }
                         "data" => {
-                            let val: Option<Vec<u8>> = map.next_value()?;
+                            let val: Option<Payload> = map.next_value()?;
 
                             if let Some(val) = val {
                                 data = Some(val);
@@ -137,7 +171,7 @@ where
 
                 let params_empty = params.is_none();
 
-                if let (Some(params), Some(data)) = (params, data) {
+                if let (Some(params), Some(Payload(data))) = (params, data) {
                     let data = deserialize_with_params(&params, &data)
                         .map_err(|e| serde::de::Error::custom(format!("{}", e)))?;
 
@@ -161,6 +195,84 @@ where
     }
 }
 
+/**
+ * The serialized SEAL bytes inside a [`WithContext`].
+ *
+ * # Remarks
+ * Human-readable formats (e.g. JSON) encode the bytes as a base64
+ * string so keys and ciphertexts can be embedded in config files
+ * compactly. Binary formats (e.g. bincode) store the raw bytes.
+ * Deserializing from a human-readable format also accepts the older
+ * array-of-numbers encoding.
+ */
+struct Payload(Vec<u8>);
+
+impl Serialize for Payload {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&BASE64.encode(&self.0))
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PayloadVisitor;
+
+        impl<'de> Visitor<'de> for PayloadVisitor {
+            type Value = Payload;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "a base64 string or a byte array")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                BASE64
+                    .decode(v)
+                    .map(Payload)
+                    .map_err(|e| E::custom(format!("Invalid base64 payload: {}", e)))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Payload(v.to_owned()))
+            }
+
+            fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+            where
+                V: SeqAccess<'de>,
+            {
+                let mut data = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+                while let Some(b) = seq.next_element()? {
+                    data.push(b);
+                }
+
+                Ok(Payload(data))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(PayloadVisitor)
+        } else {
+            Ok(Payload(Vec::<u8>::deserialize(deserializer)?))
+        }
+    }
+}
+
 fn deserialize_with_params<T>(params: &Params, data: &[u8]) -> Result<T, seal_fhe::Error>
 where
     T: FromBytes,
@@ -171,11 +283,22 @@ where
         .map(|x| Modulus::new(*x))
         .collect::<std::result::Result<Vec<Modulus>, seal_fhe::Error>>()?;
 
-    let encryption_params = BfvEncryptionParametersBuilder::new()
-        .set_coefficient_modulus(coeffs)
-        .set_plain_modulus_u64(params.plain_modulus)
-        .set_poly_modulus_degree(params.lattice_dimension)
-        .build()?;
+    let encryption_params = match params.scheme_type {
+        SchemeType::Bfv => BfvEncryptionParametersBuilder::new()
+            .set_coefficient_modulus(coeffs)
+            .set_plain_modulus_u64(params.plain_modulus)
+            .set_poly_modulus_degree(params.lattice_dimension)
+            .build()?,
+        SchemeType::Bgv => BfvEncryptionParametersBuilder::new()
+            .set_coefficient_modulus(coeffs)
+            .set_plain_modulus_u64(params.plain_modulus)
+            .set_poly_modulus_degree(params.lattice_dimension)
+            .build_bgv()?,
+        SchemeType::Ckks => CkksEncryptionParametersBuilder::new()
+            .set_coefficient_modulus(coeffs)
+            .set_poly_modulus_degree(params.lattice_dimension)
+            .build()?,
+    };
 
     let seal_context = Context::new(&encryption_params, false, params.security_level)?;
 